@@ -35,9 +35,115 @@ enum Commands {
         #[arg(short, long, default_value_t = 100)]
         n: usize,
     },
+    /// Установить модуль из .wasm файла
+    #[command(name = "module:install")]
+    ModuleInstall {
+        /// Путь к .wasm файлу (рядом могут лежать component.toml и deployment-template.toml)
+        path: PathBuf,
+    },
     /// Список установленных модулей
     #[command(name = "module:list")]
     ModuleList,
+    /// Информация об установленном модуле
+    #[command(name = "module:info")]
+    ModuleInfo {
+        /// Идентификатор модуля в формате name@version
+        id: String,
+    },
+    /// Удалить установленный модуль
+    #[command(name = "module:remove")]
+    ModuleRemove {
+        /// Идентификатор модуля в формате name@version
+        id: String,
+    },
+    /// Создать или обновить развёртывание из TOML файла
+    #[command(name = "deployment:apply")]
+    DeploymentApply {
+        /// Путь к файлу развёртывания (таблица [deployment])
+        path: PathBuf,
+    },
+    /// Список развёртываний
+    #[command(name = "deployment:list")]
+    DeploymentList,
+    /// Изменить количество реплик развёртывания
+    #[command(name = "deployment:scale")]
+    DeploymentScale {
+        /// Идентификатор модуля в формате name@version
+        module_id: String,
+        /// Группа развёртывания
+        group: String,
+        /// Новое количество реплик
+        replicas: u32,
+    },
+    /// Удалить развёртывание
+    #[command(name = "deployment:undeploy")]
+    DeploymentUndeploy {
+        /// Идентификатор модуля в формате name@version
+        module_id: String,
+        /// Группа развёртывания
+        group: String,
+    },
+    /// Остановить экземпляры развёртывания, сохранив его
+    #[command(name = "deployment:stop")]
+    DeploymentStop {
+        /// Идентификатор модуля в формате name@version
+        module_id: String,
+        /// Группа развёртывания
+        group: String,
+    },
+    /// Запустить остановленное развёртывание
+    #[command(name = "deployment:start")]
+    DeploymentStart {
+        /// Идентификатор модуля в формате name@version
+        module_id: String,
+        /// Группа развёртывания
+        group: String,
+    },
+    /// Показать изменения, которые внесёт сверка с файлами *.deployment.toml
+    #[command(name = "deployment:diff")]
+    DeploymentDiff,
+    /// Список экземпляров модулей
+    #[command(name = "instance:list")]
+    InstanceList {
+        /// Фильтр по идентификатору модуля
+        #[arg(long)]
+        module_id: Option<String>,
+        /// Фильтр по группе
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Информация об экземпляре, включая действующие лимиты ресурсов
+    #[command(name = "instance:info")]
+    InstanceInfo {
+        /// Идентификатор экземпляра: <module_id>/<group>/<replica>
+        id: String,
+    },
+    /// Статистика кэша скомпилированных модулей
+    #[command(name = "cache:stats")]
+    CacheStats,
+    /// Очистить кэш скомпилированных модулей
+    #[command(name = "cache:clear")]
+    CacheClear,
+    /// Удалить неиспользуемые бинарные файлы модулей
+    #[command(name = "storage:gc")]
+    StorageGc,
+    /// Показать итоговую конфигурацию и файл, задавший каждый параметр
+    #[command(name = "config:explain")]
+    ConfigExplain {
+        /// Показать только параметры с этим префиксом (например, arcella.log.)
+        #[arg(long)]
+        prefix: Option<String>,
+    },
+    /// Проверить, что загруженные конфигурационные файлы не изменялись
+    #[command(name = "config:integrity")]
+    ConfigIntegrity,
+    /// Справочник параметров конфигурации: тип, допустимые значения, значение по умолчанию
+    #[command(name = "config:schema")]
+    ConfigSchema {
+        /// Показать только параметры с этим префиксом (например, arcella.log.)
+        #[arg(long)]
+        prefix: Option<String>,
+    },
     /// Интерактивная консоль
     Shell,
 }
@@ -66,6 +172,29 @@ async fn send_alme_request(
     Ok(response)
 }
 
+/// Sends a request and prints the response message and data, exiting with code 1 on failure.
+async fn send_and_print(
+    socket_path: &PathBuf,
+    cmd: &str,
+    args: serde_json::Value,
+) -> anyhow::Result<()> {
+    let req = AlmeRequest {
+        cmd: cmd.to_string(),
+        args,
+    };
+    let resp = send_alme_request(socket_path, req).await?;
+    if resp.success {
+        println!("{}", resp.message);
+        if let Some(data) = resp.data {
+            println!("{:#}", data);
+        }
+    } else {
+        eprintln!("Error: {}", resp.message);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 fn get_default_socket_path() -> PathBuf {
     let base = dirs::home_dir().unwrap().join(".arcella");
     base.join("alme")
@@ -141,6 +270,77 @@ async fn handle_command(cmd: Commands) -> anyhow::Result<()> {
                 std::process::exit(1);
             }
         },
+        Commands::ModuleInstall { path } => {
+            // The daemon resolves the path on its own filesystem, so send an absolute one.
+            let path = std::fs::canonicalize(&path)
+                .map_err(|e| anyhow::anyhow!("Cannot access {:?}: {}", path, e))?;
+            let args = serde_json::json!({ "path": path });
+            send_and_print(&socket_path, "module:install", args).await?;
+        },
+        Commands::ModuleInfo { id } => {
+            let args = serde_json::json!({ "id": id });
+            send_and_print(&socket_path, "module:info", args).await?;
+        },
+        Commands::ModuleRemove { id } => {
+            let args = serde_json::json!({ "id": id });
+            send_and_print(&socket_path, "module:remove", args).await?;
+        },
+        Commands::DeploymentApply { path } => {
+            let path = std::fs::canonicalize(&path)
+                .map_err(|e| anyhow::anyhow!("Cannot access {:?}: {}", path, e))?;
+            let args = serde_json::json!({ "path": path });
+            send_and_print(&socket_path, "deployment:apply", args).await?;
+        },
+        Commands::DeploymentList => {
+            send_and_print(&socket_path, "deployment:list", serde_json::Value::Null).await?;
+        },
+        Commands::DeploymentScale { module_id, group, replicas } => {
+            let args = serde_json::json!({ "module_id": module_id, "group": group, "replicas": replicas });
+            send_and_print(&socket_path, "deployment:scale", args).await?;
+        },
+        Commands::DeploymentUndeploy { module_id, group } => {
+            let args = serde_json::json!({ "module_id": module_id, "group": group });
+            send_and_print(&socket_path, "deployment:undeploy", args).await?;
+        },
+        Commands::DeploymentStop { module_id, group } => {
+            let args = serde_json::json!({ "module_id": module_id, "group": group });
+            send_and_print(&socket_path, "deployment:stop", args).await?;
+        },
+        Commands::DeploymentStart { module_id, group } => {
+            let args = serde_json::json!({ "module_id": module_id, "group": group });
+            send_and_print(&socket_path, "deployment:start", args).await?;
+        },
+        Commands::DeploymentDiff => {
+            send_and_print(&socket_path, "deployment:diff", serde_json::Value::Null).await?;
+        },
+        Commands::InstanceList { module_id, group } => {
+            let args = serde_json::json!({ "module_id": module_id, "group": group });
+            send_and_print(&socket_path, "instance:list", args).await?;
+        },
+        Commands::InstanceInfo { id } => {
+            let args = serde_json::json!({ "id": id });
+            send_and_print(&socket_path, "instance:info", args).await?;
+        },
+        Commands::CacheStats => {
+            send_and_print(&socket_path, "cache:stats", serde_json::Value::Null).await?;
+        },
+        Commands::CacheClear => {
+            send_and_print(&socket_path, "cache:clear", serde_json::Value::Null).await?;
+        },
+        Commands::StorageGc => {
+            send_and_print(&socket_path, "storage:gc", serde_json::Value::Null).await?;
+        },
+        Commands::ConfigExplain { prefix } => {
+            let args = serde_json::json!({ "prefix": prefix });
+            send_and_print(&socket_path, "config:explain", args).await?;
+        },
+        Commands::ConfigIntegrity => {
+            send_and_print(&socket_path, "config:integrity", serde_json::Value::Null).await?;
+        },
+        Commands::ConfigSchema { prefix } => {
+            let args = serde_json::json!({ "prefix": prefix });
+            send_and_print(&socket_path, "config:schema", args).await?;
+        },
         Commands::Shell => {
             eprintln!("Interactive shell not implemented yet (use single commands)");
             std::process::exit(1);
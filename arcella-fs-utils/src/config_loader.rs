@@ -18,7 +18,11 @@
 //! ## Key Features
 //!
 //! - **File inclusion**: Files can include others via `includes = "file.toml"` or `includes = ["a.toml", "b.toml"]`.
-//! - **Glob and directory support**: `includes` may contain glob patterns (e.g., `"config.d/*.toml"`) or directories.
+//! - **Glob and directory support**: `includes` may contain glob patterns (e.g., `"config.d/*.toml"`,
+//!   `"config.d/**/*.toml"`) or directories.
+//! - **Optional includes**: Paths in `includes_optional` are loaded like `includes`, but may be absent.
+//! - **Conditional includes**: The `[includes_if]` table loads files only on matching hosts,
+//!   e.g. `"host:web-01" = "hosts/web-01.toml"` or `"env:ARCELLA_PROFILE=dev" = "dev.toml"`.
 //! - **Cycle detection**: Each file is loaded at most once across the entire configuration tree.
 //! - **Depth limiting**: Prevents infinite recursion due to deep or cyclic includes (`MAX_CONFIG_DEPTH = 5`).
 //! - **Warning collection**: Non-fatal issues (e.g., pruned subtrees, duplicate includes) are collected for later inspection.
@@ -35,11 +39,14 @@
 //!
 //! If a path in `includes` does not exist or is not a valid TOML file (e.g., a `.template.toml` file),
 //! it is **silently skipped** and a `SkippedInvalidFile` warning is recorded.
-//! This allows optional configuration files (e.g., `local.toml`) to be absent without causing an error.
+//! This allows optional configuration files (e.g., `local.toml`) to be absent without causing an error;
+//! list them in `includes_optional` to skip the warning as well.
+//! A glob pattern that matches no files is not reported either.
 
-use std::path::{Path};
+use indexmap::IndexSet;
+use std::path::{Path, PathBuf};
 
-use crate::collect_toml_includes;
+use crate::{collect_optional_toml_includes, collect_toml_includes};
 use crate::ConfigLoadWarning; 
 use crate::error::{ArcellaUtilsError, Result as ArcellaUtilsResult};
 use crate::toml;
@@ -186,11 +193,39 @@ pub async fn load_config_recursive_from_content(
     // Resolve and expand includes (e.g., globs, directories) into concrete file paths.
     // The result is sorted lexicographically to ensure deterministic loading order.
     // Invalid or missing paths are skipped and recorded as warnings.
-    let include_paths = collect_toml_includes(
+    // Paths listed by several directives of this file are loaded once, at their first position.
+    let mut include_paths: IndexSet<PathBuf> = collect_toml_includes(
         &config.includes, 
         &params.config_dir, 
         &mut state.warnings,
-    ).await?;
+    ).await?.into_iter().collect();
+
+    include_paths.extend(collect_optional_toml_includes(
+        &config.includes_optional,
+        &params.config_dir,
+        &mut state.warnings,
+    ).await?);
+
+    // Conditional includes are loaded after the unconditional ones, in document order.
+    for conditional in &config.includes_if {
+        match conditional.condition.parse::<IncludeCondition>() {
+            Ok(condition) if condition.is_met() => {
+                include_paths.extend(collect_toml_includes(
+                    &conditional.includes,
+                    &params.config_dir,
+                    &mut state.warnings,
+                ).await?);
+            }
+            Ok(_) => {}
+            Err(error) => {
+                state.warnings.push(ConfigLoadWarning::InvalidIncludeCondition {
+                    condition: conditional.condition.clone(),
+                    error,
+                    file: config_file_path.to_path_buf(),
+                });
+            }
+        }
+    }
 
     let mut all_configs = vec![config];
 
@@ -496,4 +531,59 @@ mod tests {
         assert_eq!(sub_config.values.get("arcella.logging.level").unwrap().0, TomlValue::String("info".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_load_config_recursive_with_optional_and_conditional_includes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path();
+        let mut state = ConfigLoadState::default();
+
+        std::env::set_var("ARCELLA_TEST_INCLUDE_PROFILE", "dev");
+        let host = gethostname::gethostname().to_string_lossy().to_uppercase();
+
+        let main_config_path = config_dir.join("main.toml");
+        let main_config_content = format!(r#"
+            includes = ["conf.d/*.toml"]
+            includes_optional = ["local.toml", "conf.d/a.toml"]
+
+            [includes_if]
+            "host:{host}" = "this-host.toml"
+            "host:{host}.invalid" = "other-host.toml"
+            "env:ARCELLA_TEST_INCLUDE_PROFILE=dev" = ["dev.toml"]
+            "env:ARCELLA_TEST_INCLUDE_PROFILE=prod" = ["prod.toml"]
+            "profile:dev" = "dev.toml"
+        "#);
+        fs::write(&main_config_path, main_config_content).unwrap();
+
+        fs::create_dir(config_dir.join("conf.d")).unwrap();
+        for name in ["conf.d/a.toml", "this-host.toml", "other-host.toml", "dev.toml", "prod.toml"] {
+            fs::write(config_dir.join(name), "[server]\nport = 8080\n").unwrap();
+        }
+
+        let params = ConfigLoadParams {
+            prefix: vec!["arcella".to_string()],
+            config_dir: config_dir.to_path_buf(),
+        };
+
+        load_config_recursive_from_file(
+            &params,
+            &mut state,
+            &main_config_path,
+        ).await.unwrap();
+
+        // The missing optional include and the file listed twice are not reported;
+        // only the unknown condition is.
+        let loaded: Vec<_> = state.config_files.iter().cloned().collect();
+        assert_eq!(loaded, vec![
+            main_config_path,
+            config_dir.join("conf.d/a.toml"),
+            config_dir.join("this-host.toml"),
+            config_dir.join("dev.toml"),
+        ]);
+        assert_eq!(state.warnings.len(), 1);
+        assert!(matches!(
+            &state.warnings[0],
+            ConfigLoadWarning::InvalidIncludeCondition { condition, .. } if condition == "profile:dev"
+        ));
+    }
+
 }
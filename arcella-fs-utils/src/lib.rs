@@ -39,9 +39,13 @@ pub use types::*;
 pub mod warnings;
 pub use warnings::*;
 
+/// Environment variable that sets the base directory explicitly.
+pub const ARCELLA_HOME_ENV: &str = "ARCELLA_HOME";
+
 /// Determines the base directory for Arcella based on the executable location or environment.
 ///
 /// The function follows this priority order:
+/// 0. If the `ARCELLA_HOME` environment variable is set and not empty, its value is used.
 /// 1. If the executable is located in a `bin` subdirectory and if parent of `bin` is not root
 ///    directory, the parent of `bin` is use. 
 /// 2. If the current directory (where the executable is run from) contains a `config` subdirectory,
@@ -53,6 +57,10 @@ pub use warnings::*;
 /// A `Result` containing the determined `PathBuf` or an error if the home directory
 /// cannot be determined.
 pub async fn find_base_dir() -> ArcellaUtilsResult<PathBuf> {
+    if let Some(home) = env::var_os(ARCELLA_HOME_ENV).filter(|home| !home.is_empty()) {
+        return Ok(PathBuf::from(home));
+    }
+
     if let Ok(current_exe) = env::current_exe() {
         if let Some(parent) = current_exe.parent() {
             // Case 1: executable is in a `bin` directory
@@ -203,10 +211,51 @@ pub async fn find_toml_files_in_dir(dir_path: &Path) -> ArcellaUtilsResult<Optio
     Ok(Some(sorted_paths))
 }
 
+/// Returns `true` if an include pattern contains glob metacharacters (`*`, `?`, `[`).
+fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Expands a glob pattern (e.g., `conf.d/*.toml`, `**/*.toml`) relative to `config_dir`.
+///
+/// Matches are returned in the order produced by the glob walker; entries that cannot
+/// be read are skipped. A pattern that matches nothing yields an empty vector.
+///
+/// # Arguments
+///
+/// * `pattern` - The glob pattern from `includes`.
+/// * `config_dir` - The base directory to resolve the pattern against.
+/// * `warnings` - Receives an `InvalidIncludePattern` warning if the pattern is malformed.
+fn expand_include_glob(
+    pattern: &str,
+    config_dir: &Path,
+    warnings: &mut Vec<ConfigLoadWarning>,
+) -> Vec<PathBuf> {
+    // Escape the directory so that metacharacters in it are matched literally.
+    let full_pattern = Path::new(&glob::Pattern::escape(&config_dir.to_string_lossy())).join(pattern);
+    let options = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+
+    match glob::glob_with(&full_pattern.to_string_lossy(), options) {
+        Ok(paths) => paths.filter_map(|entry| entry.ok()).collect(),
+        Err(e) => {
+            warnings.push(ConfigLoadWarning::InvalidIncludePattern {
+                pattern: pattern.to_string(),
+                error: e.to_string(),
+            });
+            Vec::new()
+        }
+    }
+}
+
 /// Collects all `.toml` files specified by `includes` patterns relative to a base directory.
 ///
 /// This function:
-/// 1. Resolves all patterns in `includes` to absolute paths based on `config_dir`.
+/// 1. Resolves all patterns in `includes` to absolute paths based on `config_dir`;
+///    glob patterns (`conf.d/*.toml`, `**/*.toml`) are expanded to the matching paths.
 /// 2. Concurrently checks the existence and type (file/directory) of all resolved paths.
 /// 3. Separates resolved paths into files and directories.
 /// 4. For each resolved file, checks if it's a valid `.toml` file (not `.template.toml`) and includes it.
@@ -215,6 +264,7 @@ pub async fn find_toml_files_in_dir(dir_path: &Path) -> ArcellaUtilsResult<Optio
 ///
 /// If a resolved path in `includes` does not exist (neither file nor directory),
 /// a `ConfigLoadWarning::SkippedInvalidFile` is added to the `warnings` vector.
+/// A glob pattern that matches nothing is not reported.
 /// Duplicate paths (e.g., from overlapping patterns) are removed.
 ///
 /// # Arguments
@@ -232,9 +282,48 @@ pub async fn collect_toml_includes(
     config_dir: &Path,
     warnings: &mut Vec<ConfigLoadWarning>,
 ) -> ArcellaUtilsResult<Vec<PathBuf>> {
-    let all_paths: HashSet<PathBuf> = includes.iter().map(|include_pattern| {
-        config_dir.join(include_pattern)
-    }).collect();
+    collect_includes(includes, config_dir, true, warnings).await
+}
+
+/// Collects `.toml` files from `includes_optional` patterns relative to a base directory.
+///
+/// Behaves like [`collect_toml_includes`], except that paths which do not exist
+/// are skipped without a warning.
+///
+/// # Arguments
+///
+/// * `includes` - A vector of string patterns representing file or directory paths to include.
+/// * `config_dir` - The base directory to resolve relative paths against.
+///
+/// # Returns
+///
+/// A `Result` containing a sorted vector of unique `PathBuf`s pointing to valid `.toml` files,
+/// or an error if an I/O issue occurs during directory scanning.
+pub async fn collect_optional_toml_includes(
+    includes: &[String],
+    config_dir: &Path,
+    warnings: &mut Vec<ConfigLoadWarning>,
+) -> ArcellaUtilsResult<Vec<PathBuf>> {
+    collect_includes(includes, config_dir, false, warnings).await
+}
+
+/// Shared implementation of [`collect_toml_includes`] and [`collect_optional_toml_includes`].
+///
+/// `warn_missing` controls whether nonexistent paths produce a `SkippedInvalidFile` warning.
+async fn collect_includes(
+    includes: &[String],
+    config_dir: &Path,
+    warn_missing: bool,
+    warnings: &mut Vec<ConfigLoadWarning>,
+) -> ArcellaUtilsResult<Vec<PathBuf>> {
+    let mut all_paths: HashSet<PathBuf> = HashSet::new();
+    for include_pattern in includes {
+        if is_glob_pattern(include_pattern) {
+            all_paths.extend(expand_include_glob(include_pattern, config_dir, warnings));
+        } else {
+            all_paths.insert(config_dir.join(include_pattern));
+        }
+    }
 
     // Concurrently check the metadata for all resolved paths
     let metadata_futures: Vec<_> = all_paths
@@ -265,10 +354,12 @@ pub async fn collect_toml_includes(
                 }
             }
             Err(_) => {
-                // Path does not exist → silently skip and warn
-                warnings.push(ConfigLoadWarning::SkippedInvalidFile {
-                    path: path.clone(),
-                });
+                // Path does not exist → silently skip and warn (unless the include is optional)
+                if warn_missing {
+                    warnings.push(ConfigLoadWarning::SkippedInvalidFile {
+                        path: path.clone(),
+                    });
+                }
             }
         }
     }
@@ -322,6 +413,21 @@ mod tests {
     use tempfile::TempDir;
     use std::fs;
 
+    mod find_base_dir_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_find_base_dir_honors_arcella_home() {
+            let temp_dir = TempDir::new().unwrap();
+
+            env::set_var(ARCELLA_HOME_ENV, temp_dir.path());
+            let base_dir = find_base_dir().await;
+            env::remove_var(ARCELLA_HOME_ENV);
+
+            assert_eq!(base_dir.unwrap(), temp_dir.path());
+        }
+    }
+
     mod find_toml_tests {
         use super::*;
 
@@ -674,6 +780,65 @@ mod tests {
             let result = collect_toml_includes(&includes, config_dir, &mut warnings).await.unwrap();
             assert_eq!(result, expected_paths);
         }
+
+        #[tokio::test]
+        async fn test_collect_toml_includes_glob_patterns() {
+            let temp_dir = TempDir::new().unwrap();
+            let config_dir = temp_dir.path();
+
+            let conf_d = config_dir.join("conf.d");
+            let nested = conf_d.join("nested");
+            fs::create_dir_all(&nested).unwrap();
+
+            let a_path = conf_d.join("a.toml");
+            let b_path = conf_d.join("b.toml");
+            let nested_path = nested.join("c.toml");
+            fs::write(&a_path, "# A").unwrap();
+            fs::write(&b_path, "# B").unwrap();
+            fs::write(&nested_path, "# C").unwrap();
+            fs::write(conf_d.join("skip.template.toml"), "# Template").unwrap();
+            fs::write(conf_d.join("notes.txt"), "Not a toml file").unwrap();
+
+            let mut warnings = Vec::new();
+
+            // A single `*` does not cross directory boundaries.
+            let includes = vec!["conf.d/*.toml".to_string()];
+            let result = collect_toml_includes(&includes, config_dir, &mut warnings).await.unwrap();
+            assert_eq!(result, vec![a_path.clone(), b_path.clone()]);
+
+            // `**` matches files at any depth.
+            let includes = vec!["conf.d/**/*.toml".to_string()];
+            let result = collect_toml_includes(&includes, config_dir, &mut warnings).await.unwrap();
+            assert_eq!(result, vec![a_path, b_path, nested_path]);
+
+            // A pattern without matches is not reported; a malformed one is.
+            let includes = vec!["missing.d/*.toml".to_string(), "conf.d/[.toml".to_string()];
+            let result = collect_toml_includes(&includes, config_dir, &mut warnings).await.unwrap();
+            assert!(result.is_empty());
+            assert_eq!(warnings.len(), 1);
+            assert!(matches!(warnings[0], ConfigLoadWarning::InvalidIncludePattern { .. }));
+        }
+
+        #[tokio::test]
+        async fn test_collect_optional_toml_includes_missing_paths() {
+            let temp_dir = TempDir::new().unwrap();
+            let config_dir = temp_dir.path();
+
+            let local_path = config_dir.join("local.toml");
+            fs::write(&local_path, "# Local").unwrap();
+
+            let includes = vec![
+                "local.toml".to_string(),
+                "secrets.toml".to_string(),
+                "local.d/".to_string(),
+            ];
+
+            let mut warnings = Vec::new();
+
+            let result = collect_optional_toml_includes(&includes, config_dir, &mut warnings).await.unwrap();
+            assert_eq!(result, vec![local_path]);
+            assert!(warnings.is_empty(), "Missing optional includes should not be reported");
+        }
     }   
 
     #[test]
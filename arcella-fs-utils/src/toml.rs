@@ -16,7 +16,7 @@
 //!   - File inclusion directives under keys named `includes`.
 //!
 //! The traversal respects a maximum depth limit (`MAX_TOML_DEPTH`) to prevent stack overflow.
-//! Unsupported TOML types (e.g., datetimes) result in an error.
+//! Datetimes become `Value::Datetime`; inline tables nested in arrays become `Value::Map`.
 //!
 //! # Entry Points
 //!
@@ -27,6 +27,9 @@
 //!
 //! - **`includes` key**: If a table contains a key named `"includes"`, its value is interpreted as
 //!   a list of configuration files to include. Both a single string and an array of strings are accepted.
+//! - **`includes_optional` key**: Same as `includes`, but the loader does not warn about missing files.
+//! - **`[includes_if]` table**: Maps conditions (`"host:<name>"`, `"env:<VAR>[=<value>]"`) to
+//!   includes that are loaded only when the condition holds (see [`ConditionalInclude`]).
 //! - **`[[array-of-tables]]`**: These are converted into a `Value::Array` of `Value::Map`. Keys inside
 //!   each table are stored relative to that table (i.e., they do *not* inherit the outer path prefix).
 //!   For example:
@@ -44,7 +47,7 @@ use ordered_float::OrderedFloat;
 use std::collections::HashMap;
 use toml_edit::{ArrayOfTables, DocumentMut, InlineTable, Item as TomlEditItem, Table, Value as TomlEditValue};
 
-use arcella_types::config::{ConfigValues, Value as TomlValue};
+use arcella_types::config::{ConfigValues, Date, Datetime, Offset, Time, Value as TomlValue};
 
 use crate::error::{ArcellaUtilsError, Result as ArcellaUtilsResult};
 use crate::types::*;
@@ -52,46 +55,115 @@ use crate::types::*;
 /// Key name used to identify file inclusion directives in TOML.
 const INCLUDES_KEY: &str = "includes";
 
-/// Extension trait to convert `toml_edit::Value` into Arcella’s canonical `Value`.
+/// Key name of inclusion directives whose missing files are not reported.
+const INCLUDES_OPTIONAL_KEY: &str = "includes_optional";
+
+/// Key name of the table holding host-conditional inclusion directives.
+const INCLUDES_IF_KEY: &str = "includes_if";
+
+/// Inclusion directives collected while traversing a TOML document.
+#[derive(Debug, Default)]
+pub struct IncludeDirectives {
+    pub includes: Vec<String>,
+    pub includes_optional: Vec<String>,
+    pub includes_if: Vec<ConditionalInclude>,
+}
+
+impl IncludeDirectives {
+    fn extend(&mut self, other: IncludeDirectives) {
+        self.includes.extend(other.includes);
+        self.includes_optional.extend(other.includes_optional);
+        self.includes_if.extend(other.includes_if);
+    }
+}
+
+/// Collects the paths of an inclusion directive.
 ///
-/// Only TOML scalar types and arrays of scalars are supported.
-/// The following TOML types are **not supported** and will cause an error:
-/// - Datetime
-/// - Inline tables (handled separately via `Table`)
+/// Both a single string and an array of strings are accepted;
+/// non-string values are silently ignored.
+fn include_paths(item: &TomlEditItem) -> Vec<String> {
+    match item {
+        TomlEditItem::Value(TomlEditValue::Array(arr)) => {
+            arr.iter().filter_map(|elem| elem.as_str().map(str::to_owned)).collect()
+        }
+        TomlEditItem::Value(single) => single.as_str().map(str::to_owned).into_iter().collect(),
+        // Non-string/array values are silently ignored.
+        // In the future, this could emit a ConfigLoadWarning.
+        _ => Vec::new(),
+    }
+}
+
+/// Extension trait to convert `toml_edit::Value` into Arcella’s canonical `Value`.
 ///
-/// Arrays are supported recursively, but must contain only supported scalar types.
+/// All TOML value types are supported:
+/// - Scalars map to the corresponding variants; datetimes (offset or local date-times,
+///   local dates and local times) map to `Value::Datetime`.
+/// - Arrays are converted recursively.
+/// - Inline tables are converted recursively into `Value::Map`. Note that an inline table
+///   assigned directly to a key is not converted here: the traversal flattens it into
+///   dotted keys like a regular table (see [`collect_paths_recursive`]).
 pub trait ValueExt {
     /// Converts a `toml_edit::Value` into Arcella’s `Value`.
     ///
     /// # Errors
     ///
-    /// Returns `ArcellaUtilsError::TOML` if the value contains an unsupported type
-    /// (e.g., datetime or nested unsupported structure).
+    /// Returns `ArcellaUtilsError::TOML` if the value is nested deeper than `MAX_TOML_DEPTH`.
     fn from_toml_value(value: &TomlEditValue) -> ArcellaUtilsResult<TomlValue>;
 }
 
 impl ValueExt for TomlValue {
     fn from_toml_value(value: &TomlEditValue) -> ArcellaUtilsResult<TomlValue> {
-        let result = match value {
-            TomlEditValue::String(s) => Self::String(s.value().into()),
-            TomlEditValue::Integer(i) => Self::Integer(*i.value()),
-            TomlEditValue::Float(f) => Self::Float(OrderedFloat(*f.value())),
-            TomlEditValue::Boolean(b) => Self::Boolean(*b.value()),
-            TomlEditValue::Array(array) => {
-                let inner_values: Vec<TomlValue> = array
-                    .iter()
-                    .map(|v| Self::from_toml_value(v)) 
-                    .collect::<ArcellaUtilsResult<_>>()?;
-                Self::Array(inner_values)
-            },
-            _ => { 
-                return Err(ArcellaUtilsError::TOML(
-                    format!("Unsupported TOML value type: {:?}", value)
-                ));
-            },
-        };
-
-        Ok(result)
+        convert_value(value, 0)
+    }
+}
+
+/// Converts a `toml_edit::Value` at nesting level `depth` (see [`ValueExt::from_toml_value`]).
+fn convert_value(value: &TomlEditValue, depth: usize) -> ArcellaUtilsResult<TomlValue> {
+    if depth > MAX_TOML_DEPTH {
+        return Err(ArcellaUtilsError::TOML(
+            format!("TOML value nested deeper than {} levels", MAX_TOML_DEPTH)
+        ));
+    }
+
+    let result = match value {
+        TomlEditValue::String(s) => TomlValue::String(s.value().into()),
+        TomlEditValue::Integer(i) => TomlValue::Integer(*i.value()),
+        TomlEditValue::Float(f) => TomlValue::Float(OrderedFloat(*f.value())),
+        TomlEditValue::Boolean(b) => TomlValue::Boolean(*b.value()),
+        TomlEditValue::Datetime(dt) => TomlValue::Datetime(convert_datetime(dt.value())),
+        TomlEditValue::Array(array) => {
+            let inner_values: Vec<TomlValue> = array
+                .iter()
+                .map(|v| convert_value(v, depth + 1))
+                .collect::<ArcellaUtilsResult<_>>()?;
+            TomlValue::Array(inner_values)
+        },
+        TomlEditValue::InlineTable(table) => {
+            let entries: HashMap<String, TomlValue> = table
+                .iter()
+                .map(|(k, v)| Ok((k.to_string(), convert_value(v, depth + 1)?)))
+                .collect::<ArcellaUtilsResult<_>>()?;
+            TomlValue::Map(entries)
+        },
+    };
+
+    Ok(result)
+}
+
+/// Converts a `toml_edit` datetime into Arcella’s [`Datetime`].
+fn convert_datetime(dt: &toml_edit::Datetime) -> Datetime {
+    Datetime {
+        date: dt.date.map(|d| Date { year: d.year, month: d.month, day: d.day }),
+        time: dt.time.map(|t| Time {
+            hour: t.hour,
+            minute: t.minute,
+            second: t.second,
+            nanosecond: t.nanosecond,
+        }),
+        offset: dt.offset.map(|o| match o {
+            toml_edit::Offset::Z => Offset::Z,
+            toml_edit::Offset::Custom { minutes } => Offset::Custom { minutes },
+        }),
     }
 }
 
@@ -117,7 +189,7 @@ fn convert_array_of_tables_to_value(
     arr: &ArrayOfTables,
     depth: usize,
     file_idx: usize,
-    includes: &mut Vec<String>,
+    includes: &mut IncludeDirectives,
 ) -> ArcellaUtilsResult<(TomlValue, TraversalResult)> {
     if depth > MAX_TOML_DEPTH {
         return Ok((TomlValue::Array(Vec::new()), TraversalResult::Pruned));
@@ -128,7 +200,7 @@ fn convert_array_of_tables_to_value(
 
     for table in arr {
         let mut temp_values = IndexMap::new();
-        let mut temp_includes = Vec::new();
+        let mut temp_includes = IncludeDirectives::default();
         let child_result = table_to_value_map_recursive(
             table,
             &[],
@@ -159,16 +231,17 @@ fn convert_array_of_tables_to_value(
 
 /// Recursively processes a TOML table, collecting configuration values and `includes` directives.
 ///
-/// Keys are built using `current_path`. The special key `"includes"` is handled separately.
-/// If its value is a string or array of strings, those paths are added to `includes`.
-/// Other types under `"includes"` are ignored (no error is raised, but traversal continues).
+/// Keys are built using `current_path`. The special keys `"includes"` and `"includes_optional"`
+/// are handled separately: if the value is a string or array of strings, those paths are added
+/// to `includes`. Other types under these keys are ignored (no error is raised, but traversal continues).
+/// A `"includes_if"` table is collected as a list of [`ConditionalInclude`]s.
 ///
 /// Depth is checked against `MAX_TOML_DEPTH`; exceeding it results in pruning.
 fn table_to_value_map_recursive(
     table: &Table,
     current_path: &[String],
     file_idx: usize, 
-    includes: &mut Vec<String>,
+    includes: &mut IncludeDirectives,
     values: &mut ConfigValues,
     depth: usize,
 ) -> ArcellaUtilsResult<TraversalResult> {
@@ -182,31 +255,27 @@ fn table_to_value_map_recursive(
         let mut key_path = current_path.to_vec();
         key_path.push(key.to_string());
 
+        // We accept both string and array forms of 'includes' for user convenience.
         if key == INCLUDES_KEY {
-            // We accept both string and array forms of 'includes' for user convenience.
-            match item {
-                TomlEditItem::Value(TomlEditValue::Array(arr)) => {
-                    for elem in arr {
-                        if let Some(s) = elem.as_str() {
-                            includes.push(s.to_owned());
-                        }
-                    }
-                }
-                // Also handle a single string value for 'includes'
-                TomlEditItem::Value(single) => {
-                    if let Some(s) = single.as_str() {
-                        includes.push(s.to_owned());
-                    }
-                }
-                // Non-string/array values under 'includes' are silently ignored.
-                // In the future, this could emit a ConfigLoadWarning.
-                _ => {
-                    // Do nothing — not an error, but also not actionable.
-                }
-            }
+            includes.includes.extend(include_paths(item));
+            continue;
+        }
 
+        if key == INCLUDES_OPTIONAL_KEY {
+            includes.includes_optional.extend(include_paths(item));
             continue;
+        }
 
+        if key == INCLUDES_IF_KEY {
+            if let Some(conditions) = item.as_table_like() {
+                for (condition, paths) in conditions.iter() {
+                    includes.includes_if.push(ConditionalInclude {
+                        condition: condition.to_string(),
+                        includes: include_paths(paths),
+                    });
+                }
+            }
+            continue;
         }
 
         let child_result = collect_paths_recursive(
@@ -233,7 +302,7 @@ fn table_to_value_map_recursive(
 ///
 /// - Keys named [`INCLUDES_KEY`] are treated as file inclusion directives. Their values
 ///   may be either a string or an array of strings; all valid string values are added
-///   to the `includes` output vector.
+///   to the `includes` output. `includes_optional` and `[includes_if]` are collected likewise.
 /// - All other scalar values are converted and stored in `values` with their full path.
 ///
 /// Table nesting deeper than [`MAX_TOML_DEPTH`] is pruned (not traversed further),
@@ -259,7 +328,7 @@ fn table_to_value_map_recursive(
 /// * `item` – The TOML item to traverse (typically a table root).
 /// * `current_path` – The hierarchical path to this item (e.g., `["arcella", "server"]`).
 /// * `file_idx` – A unique index identifying the source file (used for value provenance).
-/// * `includes` – Mutable vector to collect inclusion paths.
+/// * `includes` – Mutable set of inclusion directives to collect into.
 /// * `values` – Mutable map to store configuration key-value pairs.
 /// * `depth` – Current recursion depth (should start at 0).
 ///
@@ -272,7 +341,7 @@ pub fn collect_paths_recursive(
     item: &TomlEditItem,
     current_path: &[String],
     file_idx: usize, 
-    includes: &mut Vec<String>,
+    includes: &mut IncludeDirectives,
     values: &mut ConfigValues,
     depth: usize,
 ) -> ArcellaUtilsResult<TraversalResult> {
@@ -361,7 +430,7 @@ pub fn collect_paths(
     file_idx: usize,
 ) -> ArcellaUtilsResult<(TomlFileData, TraversalResult)> {
     let mut values: ConfigValues = IndexMap::new();
-    let mut includes: Vec<String> = Vec::new();
+    let mut includes = IncludeDirectives::default();
     let result = collect_paths_recursive(
         doc.as_item(),
         prefix,
@@ -371,7 +440,8 @@ pub fn collect_paths(
         0,
     )?;
 
-    Ok((TomlFileData{includes, values}, result))
+    let IncludeDirectives { includes, includes_optional, includes_if } = includes;
+    Ok((TomlFileData{includes, includes_optional, includes_if, values}, result))
 }
 
 /// Parses TOML content and extracts configuration data in one step.
@@ -445,6 +515,7 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -483,6 +554,7 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -511,6 +583,7 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -543,6 +616,7 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -564,6 +638,7 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -587,11 +662,44 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
         }
 
+        #[test]
+        fn test_parse_config_and_collect_optional_and_conditional_includes() {
+            let config_content = r#"
+            includes_optional = "local.toml"
+
+            [includes_if]
+            "host:web-01" = "hosts/web-01.toml"
+            "env:ARCELLA_PROFILE=dev" = ["dev.toml", "dev.d/"]
+            "#;
+
+            let (config, result) = parse_and_collect(
+                config_content,
+                &["top".to_string()],
+                0,
+            ).unwrap();
+
+            assert_eq!(result, TraversalResult::Full);
+            assert!(config.includes.is_empty());
+            assert!(config.values.is_empty());
+            assert_eq!(config.includes_optional, vec!["local.toml".to_string()]);
+            assert_eq!(config.includes_if, vec![
+                ConditionalInclude {
+                    condition: "host:web-01".to_string(),
+                    includes: vec!["hosts/web-01.toml".to_string()],
+                },
+                ConditionalInclude {
+                    condition: "env:ARCELLA_PROFILE=dev".to_string(),
+                    includes: vec!["dev.toml".to_string(), "dev.d/".to_string()],
+                },
+            ]);
+        }
+
         #[test]
         fn test_parse_config_and_collect_includes_invalid_toml() {
             let config_content = r#"
@@ -651,6 +759,7 @@ mod tests {
             let expected_config = TomlFileData{
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -697,6 +806,7 @@ mod tests {
             let expected_config = TomlFileData {
                 includes: expected_includes,
                 values: expected_values,
+                ..Default::default()
             };
 
             assert_eq!(config, (expected_config, TraversalResult::Full));
@@ -747,6 +857,40 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_datetimes_and_inline_tables_in_arrays() {
+            let config_content = r#"
+            [maintenance]
+            start = 2025-03-01T02:00:00+03:00
+            day = 2025-03-01
+            at = 02:30:00.25
+            windows = [{ start = 1979-05-27T07:32:00, hours = 2, tags = { weekly = true } }]
+            "#;
+
+            let (config, result) = parse_and_collect(config_content, &[], 0).unwrap();
+            assert_eq!(result, TraversalResult::Full);
+
+            let value = |key: &str| config.values.get(key).unwrap().0.clone();
+            let datetime = |s: &str| TomlValue::Datetime(s.parse().unwrap());
+
+            assert_eq!(value("maintenance.start"), datetime("2025-03-01T02:00:00+03:00"));
+            assert_eq!(value("maintenance.day"), datetime("2025-03-01"));
+            assert_eq!(value("maintenance.at"), TomlValue::Datetime(Datetime {
+                date: None,
+                time: Some(Time { hour: 2, minute: 30, second: 0, nanosecond: 250_000_000 }),
+                offset: None,
+            }));
+
+            let expected_window = TomlValue::Map(HashMap::from([
+                ("start".to_string(), datetime("1979-05-27T07:32:00")),
+                ("hours".to_string(), TomlValue::Integer(2)),
+                ("tags".to_string(), TomlValue::Map(HashMap::from([
+                    ("weekly".to_string(), TomlValue::Boolean(true)),
+                ]))),
+            ]));
+            assert_eq!(value("maintenance.windows"), TomlValue::Array(vec![expected_window]));
+        }
+
         #[test]
         fn test_max_toml_depth_pruned_inline_table() {
             const MAX_DEPTH: usize = crate::types::MAX_TOML_DEPTH; // 10
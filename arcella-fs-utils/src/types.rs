@@ -10,6 +10,7 @@
 use indexmap::IndexSet;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use arcella_types::config::ConfigValues;
 use crate::ConfigLoadWarning; 
@@ -53,12 +54,91 @@ impl Default for ConfigLoadState {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct TomlFileData {
     pub includes: Vec<String>,
+    /// Paths from `includes_optional`: like `includes`, but missing files are not reported.
+    pub includes_optional: Vec<String>,
+    /// Entries of the `[includes_if]` table, in document order.
+    pub includes_if: Vec<ConditionalInclude>,
     pub values: ConfigValues,
 }
 
+/// A group of includes loaded only when its condition holds on the current host.
+///
+/// Declared in the `[includes_if]` table, where each key is a condition
+/// and each value is a path or an array of paths:
+///
+/// ```toml
+/// [includes_if]
+/// "host:web-01" = "hosts/web-01.toml"
+/// "env:ARCELLA_PROFILE=dev" = ["profiles/dev.toml"]
+/// ```
+///
+/// The condition is kept as written; it is parsed into an [`IncludeCondition`]
+/// by the loader, which reports malformed conditions as warnings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionalInclude {
+    pub condition: String,
+    pub includes: Vec<String>,
+}
+
+/// The condition of a [`ConditionalInclude`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncludeCondition {
+    /// `host:<name>` — the host name equals `<name>` (ASCII case-insensitive).
+    Host(String),
+    /// `env:<VAR>` — the variable is set and not empty;
+    /// `env:<VAR>=<value>` — the variable equals `<value>`.
+    Env { name: String, value: Option<String> },
+}
+
+impl IncludeCondition {
+    /// Checks the condition against the current host name and environment.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the includes guarded by this condition should be loaded.
+    pub fn is_met(&self) -> bool {
+        match self {
+            IncludeCondition::Host(name) => {
+                gethostname::gethostname()
+                    .to_str()
+                    .is_some_and(|host| host.eq_ignore_ascii_case(name))
+            }
+            IncludeCondition::Env { name, value } => match (std::env::var(name), value) {
+                (Ok(actual), Some(expected)) => actual == *expected,
+                (Ok(actual), None) => !actual.is_empty(),
+                (Err(_), _) => false,
+            },
+        }
+    }
+}
+
+impl FromStr for IncludeCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("host", name)) if !name.is_empty() => Ok(IncludeCondition::Host(name.to_string())),
+            Some(("env", spec)) => {
+                let (name, value) = match spec.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (spec, None),
+                };
+                if name.is_empty() {
+                    return Err(format!("missing variable name in condition '{}'", s));
+                }
+                Ok(IncludeCondition::Env { name: name.to_string(), value })
+            }
+            _ => Err(format!(
+                "unknown condition '{}' (expected 'host:<name>', 'env:<VAR>' or 'env:<VAR>=<value>')",
+                s
+            )),
+        }
+    }
+}
+
 /// Indicates the outcome of a recursive traversal of a TOML document.
 ///
 /// - `Full`: The entire subtree was processed without hitting depth limits.
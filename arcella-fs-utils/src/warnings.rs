@@ -44,6 +44,13 @@ pub enum ConfigLoadWarning {
     /// A TOML document subtree was skipped because it exceeded the maximum allowed nesting depth
     /// (`MAX_TOML_DEPTH`). This is not an error, but some configuration keys may be missing.
     Pruned { path: PathBuf },
+
+    /// A glob pattern in `includes` could not be parsed.
+    InvalidIncludePattern { pattern: String, error: String },
+
+    /// A key of the `[includes_if]` table is not a valid include condition;
+    /// the includes guarded by it are skipped.
+    InvalidIncludeCondition { condition: String, error: String, file: PathBuf },
 }
 
 impl std::fmt::Display for ConfigLoadWarning {
@@ -76,6 +83,12 @@ impl std::fmt::Display for ConfigLoadWarning {
             ConfigLoadWarning::Pruned { path } => {
                 write!(f, "Pruned file {:?}", path)
             }
+            ConfigLoadWarning::InvalidIncludePattern { pattern, error } => {
+                write!(f, "Invalid include pattern '{}': {}", pattern, error)
+            }
+            ConfigLoadWarning::InvalidIncludeCondition { condition, error, file } => {
+                write!(f, "Invalid include condition '{}' in file {:?}: {}", condition, file, error)
+            }
         }
     }
 }
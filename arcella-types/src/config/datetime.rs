@@ -0,0 +1,302 @@
+// arcella/arcella-types/src/config/datetime.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Date and time values as defined by TOML.
+//!
+//! A [`Datetime`] is one of the four TOML forms:
+//!
+//! | Form                  | Example                        | Fields set              |
+//! |-----------------------|--------------------------------|-------------------------|
+//! | Offset date-time      | `1979-05-27T07:32:00-07:00`    | `date`, `time`, `offset` |
+//! | Local date-time       | `1979-05-27T07:32:00`          | `date`, `time`           |
+//! | Local date            | `1979-05-27`                   | `date`                   |
+//! | Local time            | `07:32:00.999`                 | `time`                   |
+//!
+//! It is displayed in RFC 3339 notation and parsed back from it (a space or a
+//! lowercase `t`/`z` are accepted as well), which is also its `serde` form.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A TOML date, time, or date-time, with an optional UTC offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Datetime {
+    /// The date part, absent for a local time.
+    pub date: Option<Date>,
+    /// The time part, absent for a local date.
+    pub time: Option<Time>,
+    /// The UTC offset, present only for an offset date-time.
+    pub offset: Option<Offset>,
+}
+
+/// A calendar date (`YYYY-MM-DD`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    pub year: u16,
+    /// Month, `1..=12`.
+    pub month: u8,
+    /// Day of the month, `1..=31`.
+    pub day: u8,
+}
+
+/// A time of day (`HH:MM:SS[.fraction]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Time {
+    /// Hour, `0..=23`.
+    pub hour: u8,
+    /// Minute, `0..=59`.
+    pub minute: u8,
+    /// Second, `0..=60` (60 for a leap second).
+    pub second: u8,
+    /// Fraction of the second, `0..=999_999_999`.
+    pub nanosecond: u32,
+}
+
+/// The UTC offset of an offset date-time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Offset {
+    /// UTC (`Z`).
+    Z,
+    /// A fixed offset from UTC in minutes (`+05:30` is 330).
+    Custom { minutes: i16 },
+}
+
+/// Error returned when a string is not a valid TOML date or time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatetimeParseError(String);
+
+impl fmt::Display for DatetimeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid datetime '{}'", self.0)
+    }
+}
+
+impl std::error::Error for DatetimeParseError {}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond != 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Offset::Z => f.write_str("Z"),
+            Offset::Custom { minutes } => {
+                let sign = if *minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Datetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(date) = &self.date {
+            write!(f, "{}", date)?;
+        }
+        if let Some(time) = &self.time {
+            if self.date.is_some() {
+                f.write_str("T")?;
+            }
+            write!(f, "{}", time)?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, "{}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Datetime {
+    type Err = DatetimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || DatetimeParseError(s.to_string());
+
+        // A date is recognized by the dash after the year.
+        let (date, rest) = if s.as_bytes().get(4) == Some(&b'-') {
+            let date_part = s.get(..10).ok_or_else(error)?;
+            (Some(parse_date(date_part).ok_or_else(error)?), &s[10..])
+        } else {
+            (None, s)
+        };
+
+        let time_part = match date {
+            Some(_) if rest.is_empty() => return Ok(Datetime { date, time: None, offset: None }),
+            Some(_) => match rest.as_bytes()[0] {
+                b'T' | b't' | b' ' => &rest[1..],
+                _ => return Err(error()),
+            },
+            None => rest,
+        };
+
+        let (time, rest) = parse_time(time_part).ok_or_else(error)?;
+        let offset = match rest {
+            "" => None,
+            // Only a date-time can carry an offset
+            _ if date.is_none() => return Err(error()),
+            "Z" | "z" => Some(Offset::Z),
+            _ => Some(parse_offset(rest).ok_or_else(error)?),
+        };
+
+        Ok(Datetime { date, time: Some(time), offset })
+    }
+}
+
+/// Parses exactly `len` ASCII digits at the start of `s`.
+fn parse_digits(s: &str, len: usize) -> Option<u32> {
+    let digits = s.get(..len)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Parses `YYYY-MM-DD`.
+fn parse_date(s: &str) -> Option<Date> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let year = parse_digits(s, 4)? as u16;
+    let month = parse_digits(&s[5..], 2)? as u8;
+    let day = parse_digits(&s[8..], 2)? as u8;
+
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days_in_month).contains(&day).then_some(Date { year, month, day })
+}
+
+/// Parses `HH:MM:SS[.fraction]` and returns the time and the rest of `s`.
+fn parse_time(s: &str) -> Option<(Time, &str)> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 8 || bytes[2] != b':' || bytes[5] != b':' {
+        return None;
+    }
+    let hour = parse_digits(s, 2)? as u8;
+    let minute = parse_digits(&s[3..], 2)? as u8;
+    let second = parse_digits(&s[6..], 2)? as u8;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &s[8..];
+    let mut nanosecond = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        // Digits beyond nanosecond precision are truncated
+        let significant = &fraction[..len.min(9)];
+        nanosecond = significant.parse::<u32>().ok()? * 10u32.pow(9 - significant.len() as u32);
+        rest = &fraction[len..];
+    }
+
+    Some((Time { hour, minute, second, nanosecond }, rest))
+}
+
+/// Parses `+HH:MM` or `-HH:MM`.
+fn parse_offset(s: &str) -> Option<Offset> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 6 || bytes[3] != b':' {
+        return None;
+    }
+    let sign = match bytes[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let hours = parse_digits(&s[1..], 2)? as i16;
+    let minutes = parse_digits(&s[4..], 2)? as i16;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(Offset::Custom { minutes: sign * (hours * 60 + minutes) })
+}
+
+impl Serialize for Datetime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Datetime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datetime_display_and_parse_round_trip() {
+        for s in [
+            "1979-05-27T07:32:00Z",
+            "1979-05-27T00:32:00.999999-07:00",
+            "1979-05-27T07:32:00+05:30",
+            "1979-05-27T07:32:00",
+            "1979-05-27",
+            "07:32:00",
+            "00:32:00.5",
+        ] {
+            let datetime: Datetime = s.parse().unwrap();
+            assert_eq!(datetime.to_string(), s);
+        }
+
+        let datetime: Datetime = "2024-02-29 23:59:60.1234567891z".parse().unwrap();
+        assert_eq!(datetime.to_string(), "2024-02-29T23:59:60.123456789Z");
+        assert_eq!(
+            "1979-05-27T07:32:00-07:00".parse::<Datetime>().unwrap().offset,
+            Some(Offset::Custom { minutes: -420 })
+        );
+    }
+
+    #[test]
+    fn test_datetime_parse_rejects_invalid_values() {
+        for s in [
+            "",
+            "1979-05-27T",
+            "1979-13-01",
+            "2023-02-29",
+            "1979-05-27T24:00:00",
+            "07:32",
+            "07:32:00.",
+            "07:32:00Z",
+            "1979-05-27T07:32:00+7:00",
+            "1979-05-27X07:32:00",
+        ] {
+            assert!(s.parse::<Datetime>().is_err(), "{:?} should be rejected", s);
+        }
+    }
+}
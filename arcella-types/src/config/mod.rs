@@ -24,6 +24,9 @@ use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+mod datetime;
+pub use datetime::*;
+
 /// Represents a specific error that occurred during data processing.
 ///
 /// This struct is used inside the [`Value::TypedError`] variant to carry
@@ -43,7 +46,7 @@ pub struct TypedError {
 /// `serde_json::Value` but tailored for Arcella's specific needs.
 ///
 /// It supports:
-/// - Primitive types: `String`, `Integer`, `Float`, `Boolean`, `Null`
+/// - Primitive types: `String`, `Integer`, `Float`, `Boolean`, `Datetime`, `Null`
 /// - Compound types: `Array` (list of `Value`), `Map` (key-value pairs of `String` to `Value`)
 /// - Error signaling: `Error` (for representing failures during data processing)
 ///
@@ -92,6 +95,9 @@ pub enum Value {
     /// A boolean value.
     Boolean(bool),
 
+    /// A TOML date, time, or date-time (serialized as an RFC 3339 string).
+    Datetime(Datetime),
+
     /// A map of string keys to `Value`s.
     /// Uses `HashMap` for fast lookups.
     Map(HashMap<String, Value>),
@@ -103,6 +109,82 @@ pub enum Value {
     TypedError(TypedError),
 }
 
+/// Error returned when a [`Value`] cannot be represented in another format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueConversionError(pub String);
+
+impl std::fmt::Display for ValueConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ValueConversionError {}
+
+/// Converts JSON data (e.g., ALME arguments) into a [`Value`].
+///
+/// The conversion is lossless except for integers above `i64::MAX`,
+/// which become `Value::Float`.
+impl From<serde_json::Value> for Value {
+    fn from(json: serde_json::Value) -> Self {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Boolean(b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::Integer(i),
+                None => Value::Float(OrderedFloat(n.as_f64().unwrap_or(f64::NAN))),
+            },
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Array(items) => Value::Array(items.into_iter().map(Value::from).collect()),
+            serde_json::Value::Object(map) => {
+                Value::Map(map.into_iter().map(|(k, v)| (k, Value::from(v))).collect())
+            }
+        }
+    }
+}
+
+/// Converts a [`Value`] into JSON data.
+///
+/// A `Datetime` becomes its RFC 3339 string and a `TypedError` an object with
+/// `message` and `error_type`, so these two do not convert back to the same variant.
+///
+/// # Errors
+///
+/// Fails for NaN and infinite floats, which JSON cannot represent.
+impl TryFrom<&Value> for serde_json::Value {
+    type Error = ValueConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Ok(match value {
+            Value::String(s) => serde_json::Value::from(s.as_str()),
+            Value::Integer(i) => serde_json::Value::from(*i),
+            Value::Float(f) => serde_json::Number::from_f64(f.0)
+                .map(serde_json::Value::Number)
+                .ok_or_else(|| ValueConversionError(format!("{} cannot be represented in JSON", f.0)))?,
+            Value::Boolean(b) => serde_json::Value::from(*b),
+            Value::Datetime(dt) => serde_json::Value::from(dt.to_string()),
+            Value::Array(items) => serde_json::Value::Array(
+                items.iter().map(serde_json::Value::try_from).collect::<Result<_, _>>()?
+            ),
+            Value::Map(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((k.clone(), serde_json::Value::try_from(v)?)))
+                    .collect::<Result<_, ValueConversionError>>()?
+            ),
+            Value::Null => serde_json::Value::Null,
+            Value::TypedError(e) => serde_json::json!({ "message": e.message, "error_type": e.error_type }),
+        })
+    }
+}
+
+impl TryFrom<Value> for serde_json::Value {
+    type Error = ValueConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        serde_json::Value::try_from(&value)
+    }
+}
+
 pub type ConfigValues = IndexMap<String, (Value, usize)>;
 
 /// Represents an entry within a section of the configuration.
@@ -331,6 +413,48 @@ mod tests {
         assert!(config.sections.contains_key("server"));
     }
 
+    #[test]
+    fn test_value_serde_round_trip() {
+        let value = Value::Map(HashMap::from([
+            ("start".to_string(), Value::Datetime("1979-05-27T07:32:00.5-07:00".parse().unwrap())),
+            ("day".to_string(), Value::Datetime("1979-05-27".parse().unwrap())),
+            ("hours".to_string(), Value::Array(vec![Value::Integer(2), Value::Float(OrderedFloat(0.5))])),
+        ]));
+
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(json["Map"]["start"], serde_json::json!({ "Datetime": "1979-05-27T07:32:00.5-07:00" }));
+        assert_eq!(serde_json::from_value::<Value>(json).unwrap(), value);
+
+        let invalid = serde_json::json!({ "Datetime": "1979-02-30" });
+        assert!(serde_json::from_value::<Value>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_value_json_conversion() {
+        let json = serde_json::json!({
+            "name": "worker",
+            "replicas": 3,
+            "ratio": 0.5,
+            "huge": u64::MAX,
+            "enabled": true,
+            "tags": ["a", null],
+        });
+
+        let value = Value::from(json.clone());
+        let Value::Map(map) = &value else { panic!("Expected map") };
+        assert_eq!(map.get("replicas"), Some(&Value::Integer(3)));
+        assert_eq!(map.get("huge"), Some(&Value::Float(OrderedFloat(u64::MAX as f64))));
+        assert_eq!(map.get("tags"), Some(&Value::Array(vec![Value::String("a".to_string()), Value::Null])));
+
+        let mut expected = json;
+        expected["huge"] = serde_json::json!(u64::MAX as f64);
+        assert_eq!(serde_json::Value::try_from(&value).unwrap(), expected);
+
+        let start = Value::Datetime("1979-05-27T07:32:00Z".parse().unwrap());
+        assert_eq!(serde_json::Value::try_from(start).unwrap(), serde_json::json!("1979-05-27T07:32:00Z"));
+        assert!(serde_json::Value::try_from(Value::Array(vec![Value::Float(OrderedFloat(f64::NAN))])).is_err());
+    }
+
     #[test]
     fn test_config_data_get() {
         let mut input = IndexMap::new();
@@ -11,3 +11,4 @@ pub mod alme;
 pub mod manifest;
 pub mod spec;
 pub mod config;
+pub mod worker;
@@ -0,0 +1,162 @@
+// arcella/arcella-types/src/worker/mod.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! IPC protocol between the Arcella daemon and `arcella-worker` processes.
+//!
+//! The daemon writes [`WorkerRequest`]s to the worker's stdin, and the worker
+//! writes [`WorkerEvent`]s to its stdout. Every message is a single line of JSON.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A command sent by the daemon to a worker.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum WorkerRequest {
+    /// Compile a module and keep it ready for instantiation.
+    Install {
+        module_id: String,
+        wasm_path: PathBuf,
+        /// SHA-256 of the binary (hex); `wasm_path` is checked against it before
+        /// it is compiled.
+        blob: String,
+        /// Precompiled artifact from the daemon's module cache, loaded instead of
+        /// compiling `wasm_path` when usable.
+        #[serde(default)]
+        artifact_path: Option<PathBuf>,
+    },
+
+    /// Start a new instance of an installed module.
+    Start {
+        instance_id: String,
+        module_id: String,
+        #[serde(default)]
+        entrypoint: Option<String>,
+        #[serde(default)]
+        args: Vec<String>,
+        /// Run on the worker's async runtime (`true`) or on a dedicated thread.
+        #[serde(default, rename = "async")]
+        r#async: bool,
+        /// Maximum size of each linear memory, in bytes.
+        #[serde(default)]
+        memory_limit_bytes: Option<u64>,
+        /// Fuel budget of the instance.
+        #[serde(default)]
+        fuel: Option<u64>,
+        /// Wall-clock limit for instantiation, in milliseconds.
+        #[serde(default)]
+        startup_timeout_ms: Option<u64>,
+        /// Wall-clock limit for the entry point call, in milliseconds.
+        #[serde(default)]
+        call_timeout_ms: Option<u64>,
+        /// Function of a resident component called on graceful shutdown.
+        #[serde(default)]
+        shutdown: Option<String>,
+        /// Wall-clock limit for the shutdown hook, in milliseconds.
+        #[serde(default)]
+        shutdown_timeout_ms: Option<u64>,
+    },
+
+    /// Stop a running instance.
+    ///
+    /// A graceful stop calls the shutdown hook of a resident component and
+    /// reports the final state with [`WorkerEvent::Instance`]; otherwise the
+    /// instance is dropped right away.
+    Stop {
+        instance_id: String,
+        #[serde(default)]
+        graceful: bool,
+    },
+
+    /// Health check; answered with [`WorkerEvent::Pong`].
+    Ping,
+
+    /// Stop all instances and exit.
+    Shutdown,
+}
+
+/// A message sent by a worker to the daemon.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WorkerEvent {
+    /// The worker has started and is ready to accept commands.
+    Ready {
+        pid: u32,
+    },
+
+    /// A module was compiled successfully.
+    Installed {
+        module_id: String,
+    },
+
+    /// An instance changed its state.
+    Instance {
+        instance_id: String,
+        state: WorkerInstanceState,
+    },
+
+    /// Reply to [`WorkerRequest::Ping`].
+    Pong,
+
+    /// A command could not be executed.
+    Error {
+        message: String,
+    },
+}
+
+/// State of an instance as reported by a worker.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum WorkerInstanceState {
+    /// The module is instantiated and its entry point is executing.
+    Running,
+
+    /// The entry point finished with the given exit code.
+    Exited { code: i32 },
+
+    /// Instantiation failed or guest code trapped.
+    Failed { reason: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_protocol_roundtrip() {
+        let request = WorkerRequest::Start {
+            instance_id: "hello@0.1.0/web/0".into(),
+            module_id: "hello@0.1.0".into(),
+            entrypoint: None,
+            args: vec!["hello@0.1.0".into()],
+            r#async: false,
+            memory_limit_bytes: Some(1 << 20),
+            fuel: None,
+            startup_timeout_ms: Some(5000),
+            call_timeout_ms: None,
+            shutdown: Some("stop".into()),
+            shutdown_timeout_ms: Some(1000),
+        };
+        let line = serde_json::to_string(&request).unwrap();
+        assert!(line.contains("\"cmd\":\"start\""));
+        assert_eq!(serde_json::from_str::<WorkerRequest>(&line).unwrap(), request);
+
+        let event: WorkerEvent = serde_json::from_str(
+            r#"{"event":"instance","instance_id":"a","state":{"state":"exited","code":3}}"#
+        ).unwrap();
+        assert_eq!(event, WorkerEvent::Instance {
+            instance_id: "a".into(),
+            state: WorkerInstanceState::Exited { code: 3 },
+        });
+
+        // Requests from older daemons stop instances immediately.
+        let stop: WorkerRequest = serde_json::from_str(r#"{"cmd":"stop","instance_id":"a"}"#).unwrap();
+        assert_eq!(stop, WorkerRequest::Stop { instance_id: "a".into(), graceful: false });
+    }
+}
@@ -0,0 +1,87 @@
+// arcella/arcella-wasmtime/src/engine.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Shared Wasmtime engine configuration.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use wasmtime::{Config, Engine, WasmBacktraceDetails};
+
+use crate::Result;
+
+/// Interval at which [`EpochTicker`] advances the engine epoch.
+///
+/// Every store yields to the async executor once per epoch, so this is also the
+/// upper bound on how long guest code can run without yielding.
+pub const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Creates the Wasmtime engine used to compile and run Arcella modules.
+///
+/// The engine is configured for async execution, so that guest code can be
+/// driven from tokio tasks without blocking the runtime threads, with fuel
+/// metering, so that instances can be given a CPU budget, and with epoch
+/// interruption, so that long-running guests periodically yield (see
+/// [`EpochTicker`]). Both the daemon
+/// and `arcella-worker` must use the same configuration, otherwise precompiled
+/// artifacts are not interchangeable.
+pub fn create_engine() -> Result<Engine> {
+    let mut config = Config::new();
+    config.async_support(true);
+    config.wasm_backtrace_details(WasmBacktraceDetails::Enable);
+    config.wasm_threads(false);
+    config.consume_fuel(true);
+    config.epoch_interruption(true);
+
+    Ok(Engine::new(&config)?)
+}
+
+/// Background thread that periodically increments the epoch of an engine.
+///
+/// Stores created by the executor yield to the async executor whenever the epoch
+/// advances, which lets tokio timers (timeouts, cancellation) fire even while a
+/// guest is stuck in a loop. Without a running ticker guests are never
+/// interrupted. The thread stops when the ticker is dropped.
+pub struct EpochTicker {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EpochTicker {
+    /// Starts ticking the epoch of `engine` every [`EPOCH_TICK`].
+    pub fn start(engine: &Engine) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let engine = engine.clone();
+        let thread_stop = stop.clone();
+
+        let thread = std::thread::Builder::new()
+            .name("arcella-epoch".into())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+            })
+            .expect("Failed to spawn the epoch ticker thread");
+
+        Self { stop, thread: Some(thread) }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -33,6 +33,26 @@ pub enum ArcellaWasmtimeError {
     #[error("Wasmtime error: {0}")]
     Wasmtime(#[from] wasmtime::Error),
 
+    /// Guest code trapped or failed during execution.
+    #[error("Execution failed: {0}")]
+    Trap(String),
+
+    /// The instance consumed all of its fuel.
+    #[error("Execution failed: out of fuel")]
+    OutOfFuel,
+
+    /// A guest call did not complete within its wall-clock limit.
+    #[error("Execution failed: timed out: {0}")]
+    Timeout(String),
+
+    /// Execution was interrupted by a shutdown request before the guest finished.
+    #[error("Execution cancelled: {0}")]
+    Cancelled(String),
+
+    /// A value does not match the component type it is converted to or from.
+    #[error("Value conversion error: {0}")]
+    Conversion(String),
+
 }
 
 impl From<String> for ArcellaWasmtimeError {
@@ -0,0 +1,258 @@
+// arcella/arcella-wasmtime/src/executor/component.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Asynchronous execution of Component Model components.
+
+use wasmtime::{
+    Engine, Store, StoreLimits,
+    component::{Component, Func, Instance, Linker, ResourceTable, Val},
+};
+use wasmtime_wasi::{
+    p2::{self, bindings::Command},
+    I32Exit, WasiCtx, WasiCtxView, WasiView,
+};
+
+use super::{execution_error, wasi_builder, InstanceOptions, RunOutcome};
+use crate::{ArcellaWasmtimeError, Result};
+
+/// Export name prefix of the `wasi:cli/run` interface.
+const WASI_CLI_RUN: &str = "wasi:cli/run@";
+
+/// Host state of a component store.
+struct ComponentHostState {
+    ctx: WasiCtx,
+    table: ResourceTable,
+    limits: StoreLimits,
+}
+
+impl WasiView for ComponentHostState {
+    fn ctx(&mut self) -> WasiCtxView<'_> {
+        WasiCtxView {
+            ctx: &mut self.ctx,
+            table: &mut self.table,
+        }
+    }
+}
+
+/// An instantiated component linked against WASI preview2.
+///
+/// What [`ComponentInstance::run`] does depends on the component:
+///
+/// - with an explicit entry point, the function is called once and the instance
+///   stays resident afterwards;
+/// - otherwise, if the component exports `wasi:cli/run`, it is run as a command;
+/// - otherwise the component is a reactor and stays resident right away.
+pub struct ComponentInstance {
+    store: Store<ComponentHostState>,
+    instance: Instance,
+    entrypoint: Option<Func>,
+    command: Option<Command>,
+}
+
+impl ComponentInstance {
+    /// Instantiates a component with WASI preview2 imports in async mode.
+    ///
+    /// The engine must have async support enabled (see [`crate::engine::create_engine`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Trap`] if instantiation fails, or if the
+    /// entry point does not exist or is not a function without parameters.
+    pub async fn instantiate(
+        engine: &Engine,
+        component: &Component,
+        options: &InstanceOptions,
+    ) -> Result<Self> {
+        let mut linker: Linker<ComponentHostState> = Linker::new(engine);
+        p2::add_to_linker_async(&mut linker)?;
+
+        let state = ComponentHostState {
+            ctx: wasi_builder(options).build(),
+            table: ResourceTable::new(),
+            limits: options.limits.store_limits(),
+        };
+        let mut store = Store::new(engine, state);
+        store.limiter(|state| &mut state.limits);
+        options.limits.apply_to_store(&mut store)?;
+
+        let instance = linker
+            .instantiate_async(&mut store, component)
+            .await
+            .map_err(|e| execution_error(e, Some("Instantiation failed")))?;
+
+        let entrypoint = match &options.entrypoint {
+            Some(name) => Some(lookup_entrypoint(&mut store, &instance, name)?),
+            None => None,
+        };
+
+        let exports_run = component
+            .component_type()
+            .exports(engine)
+            .any(|(name, _)| name.starts_with(WASI_CLI_RUN));
+        let command = if entrypoint.is_none() && exports_run {
+            Some(Command::new(&mut store, &instance)?)
+        } else {
+            None
+        };
+
+        Ok(Self { store, instance, entrypoint, command })
+    }
+
+    /// Returns the underlying component instance.
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    /// Runs the entry point of the component.
+    ///
+    /// # Returns
+    ///
+    /// [`RunOutcome::Exited`] for `wasi:cli/run` commands (exit code `1` if `run`
+    /// returned an error), or [`RunOutcome::Resident`] if the instance stays alive.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Trap`] if guest code traps, or if the
+    /// explicit entry point returned an `error` result, and
+    /// [`ArcellaWasmtimeError::OutOfFuel`] if it runs out of fuel.
+    pub async fn run(&mut self) -> Result<RunOutcome> {
+        if let Some(func) = self.entrypoint {
+            let mut results = vec![Val::Bool(false); func.results(&self.store).len()];
+            func.call_async(&mut self.store, &[], &mut results)
+                .await
+                .map_err(|e| execution_error(e, None))?;
+            func.post_return_async(&mut self.store).await?;
+
+            if let [Val::Result(Err(err))] = results.as_slice() {
+                return Err(ArcellaWasmtimeError::Trap(format!(
+                    "Entry point returned an error: {:?}", err
+                )));
+            }
+            return Ok(RunOutcome::Resident);
+        }
+
+        if let Some(command) = &self.command {
+            return match command.wasi_cli_run().call_run(&mut self.store).await {
+                Ok(Ok(())) => Ok(RunOutcome::Exited(0)),
+                Ok(Err(())) => Ok(RunOutcome::Exited(1)),
+                Err(e) => match e.downcast_ref::<I32Exit>() {
+                    Some(exit) => Ok(RunOutcome::Exited(exit.0)),
+                    None => Err(execution_error(e, None)),
+                },
+            };
+        }
+
+        Ok(RunOutcome::Resident)
+    }
+
+    /// Calls the shutdown hook of a resident component.
+    ///
+    /// `name` uses the same format as the entry point (`"func"` or
+    /// `"ns:pkg/iface#func"`) and must refer to a function without parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Trap`] if the function does not exist,
+    /// guest code traps or the hook returned an `error` result, and
+    /// [`ArcellaWasmtimeError::OutOfFuel`] if it runs out of fuel.
+    pub async fn call_shutdown(&mut self, name: &str) -> Result<()> {
+        let func = lookup_entrypoint(&mut self.store, &self.instance, name)?;
+        let mut results = vec![Val::Bool(false); func.results(&self.store).len()];
+        func.call_async(&mut self.store, &[], &mut results)
+            .await
+            .map_err(|e| execution_error(e, Some("Shutdown hook failed")))?;
+        func.post_return_async(&mut self.store).await?;
+
+        if let [Val::Result(Err(err))] = results.as_slice() {
+            return Err(ArcellaWasmtimeError::Trap(format!(
+                "Shutdown hook returned an error: {:?}", err
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Resolves an entry point name (`"func"` or `"ns:pkg/iface#func"`) to a function
+/// without parameters.
+fn lookup_entrypoint(
+    store: &mut Store<ComponentHostState>,
+    instance: &Instance,
+    name: &str,
+) -> Result<Func> {
+    let not_found = || ArcellaWasmtimeError::Trap(format!("Entry point '{}' not found", name));
+
+    let (interface, func_name) = match name.split_once('#') {
+        Some((interface, func_name)) => (Some(interface), func_name),
+        None => (None, name),
+    };
+
+    let parent = match interface {
+        Some(interface) => Some(
+            instance.get_export_index(&mut *store, None, interface).ok_or_else(not_found)?
+        ),
+        None => None,
+    };
+
+    let index = instance
+        .get_export_index(&mut *store, parent.as_ref(), func_name)
+        .ok_or_else(not_found)?;
+    let func = instance.get_func(&mut *store, index).ok_or_else(not_found)?;
+
+    if !func.params(&*store).is_empty() {
+        return Err(ArcellaWasmtimeError::Trap(format!(
+            "Entry point '{}' must not take parameters", name
+        )));
+    }
+
+    Ok(func)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::create_engine;
+
+    const COMPONENT: &str = r#"
+        (component
+          (core module $m
+            (func (export "start"))
+            (func (export "fail") unreachable))
+          (core instance $i (instantiate $m))
+          (func (export "start") (canon lift (core func $i "start")))
+          (func (export "fail") (canon lift (core func $i "fail"))))
+    "#;
+
+    #[tokio::test]
+    async fn test_component_entrypoint() {
+        let engine = create_engine().unwrap();
+        let component = Component::new(&engine, COMPONENT).unwrap();
+
+        let options = InstanceOptions { entrypoint: Some("start".into()), ..Default::default() };
+        let mut instance = ComponentInstance::instantiate(&engine, &component, &options).await.unwrap();
+        assert_eq!(instance.run().await.unwrap(), RunOutcome::Resident);
+
+        let options = InstanceOptions { entrypoint: Some("fail".into()), ..Default::default() };
+        let mut instance = ComponentInstance::instantiate(&engine, &component, &options).await.unwrap();
+        assert!(instance.run().await.is_err());
+
+        let options = InstanceOptions { entrypoint: Some("missing".into()), ..Default::default() };
+        assert!(ComponentInstance::instantiate(&engine, &component, &options).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reactor_component_is_resident() {
+        let engine = create_engine().unwrap();
+        let component = Component::new(&engine, COMPONENT).unwrap();
+
+        let mut instance = ComponentInstance::instantiate(&engine, &component, &InstanceOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(instance.run().await.unwrap(), RunOutcome::Resident);
+    }
+}
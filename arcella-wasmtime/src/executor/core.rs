@@ -0,0 +1,171 @@
+// arcella/arcella-wasmtime/src/executor/core.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Execution of WASI preview1 core modules.
+
+use wasmtime::{Engine, Linker, Module, Store, StoreLimits, TypedFunc};
+use wasmtime_wasi::{p1::{self, WasiP1Ctx}, I32Exit};
+
+use super::{execution_error, wasi_builder, InstanceOptions};
+use crate::{ArcellaWasmtimeError, Result};
+
+/// Default entry point of WASI preview1 commands.
+pub const WASI_START: &str = "_start";
+
+/// Host state of a core module store.
+struct CoreHostState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+/// An instantiated WASI preview1 core module, ready to run its entry point.
+pub struct CoreInstance {
+    store: Store<CoreHostState>,
+    entrypoint: TypedFunc<(), ()>,
+}
+
+impl CoreInstance {
+    /// Instantiates a WASI preview1 core module.
+    ///
+    /// The module is linked against `wasi_snapshot_preview1` and instantiated in a
+    /// fresh store. The entry point (`_start` or [`InstanceOptions::entrypoint`]) is
+    /// resolved, but not called. The engine must have async support enabled
+    /// (see [`crate::engine::create_engine`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Trap`] if instantiation fails, or if the entry
+    /// point is missing or has an unexpected signature.
+    pub async fn instantiate(
+        engine: &Engine,
+        module: &Module,
+        options: &InstanceOptions,
+    ) -> Result<Self> {
+        let mut linker: Linker<CoreHostState> = Linker::new(engine);
+        p1::add_to_linker_async(&mut linker, |state| &mut state.wasi)?;
+
+        let wasi = wasi_builder(options).build_p1();
+        let limits = options.limits.store_limits();
+        let mut store = Store::new(engine, CoreHostState { wasi, limits });
+        store.limiter(|state| &mut state.limits);
+        options.limits.apply_to_store(&mut store)?;
+
+        let instance = linker
+            .instantiate_async(&mut store, module)
+            .await
+            .map_err(|e| execution_error(e, Some("Instantiation failed")))?;
+
+        let name = options.entrypoint.as_deref().unwrap_or(WASI_START);
+        let entrypoint = instance
+            .get_typed_func::<(), ()>(&mut store, name)
+            .map_err(|e| ArcellaWasmtimeError::Trap(format!("Entry point '{}': {:#}", name, e)))?;
+
+        Ok(Self { store, entrypoint })
+    }
+
+    /// Calls the entry point and waits for it to finish.
+    ///
+    /// # Returns
+    ///
+    /// The exit code of the module: `0` if the entry point returned normally, or the
+    /// code passed to `proc_exit`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Trap`] if guest code traps, or
+    /// [`ArcellaWasmtimeError::OutOfFuel`] if it runs out of fuel.
+    pub async fn run(mut self) -> Result<i32> {
+        match self.entrypoint.call_async(&mut self.store, ()).await {
+            Ok(()) => Ok(0),
+            Err(e) => match e.downcast_ref::<I32Exit>() {
+                Some(exit) => Ok(exit.0),
+                None => Err(execution_error(e, None)),
+            },
+        }
+    }
+}
+
+/// Instantiates a WASI preview1 core module and runs it to completion.
+///
+/// Shorthand for [`CoreInstance::instantiate`] followed by [`CoreInstance::run`].
+pub async fn run_core_module(
+    engine: &Engine,
+    module: &Module,
+    options: &InstanceOptions,
+) -> Result<i32> {
+    CoreInstance::instantiate(engine, module, options).await?.run().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::create_engine;
+    use crate::executor::ResourceLimits;
+
+    #[tokio::test]
+    async fn test_run_core_module_exit_codes() {
+        let engine = create_engine().unwrap();
+
+        let wasm = r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+              (memory (export "memory") 1)
+              (func (export "_start"))
+              (func (export "fail") (call $exit (i32.const 3)))
+              (func (export "crash") unreachable))
+        "#;
+        let module = Module::new(&engine, wasm).unwrap();
+
+        let options = InstanceOptions::default();
+        assert_eq!(run_core_module(&engine, &module, &options).await.unwrap(), 0);
+
+        let options = InstanceOptions { entrypoint: Some("fail".into()), ..Default::default() };
+        assert_eq!(run_core_module(&engine, &module, &options).await.unwrap(), 3);
+
+        let options = InstanceOptions { entrypoint: Some("crash".into()), ..Default::default() };
+        let err = run_core_module(&engine, &module, &options).await.unwrap_err();
+        assert!(matches!(err, ArcellaWasmtimeError::Trap(msg) if msg.contains("unreachable")));
+
+        let options = InstanceOptions { entrypoint: Some("missing".into()), ..Default::default() };
+        assert!(run_core_module(&engine, &module, &options).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resource_limits() {
+        let engine = create_engine().unwrap();
+
+        let wasm = r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+              (memory (export "memory") 1)
+              (func (export "_start")
+                (if (i32.eq (memory.grow (i32.const 4)) (i32.const -1))
+                  (then (call $exit (i32.const 9)))))
+              (func (export "spin") (loop $l (br $l))))
+        "#;
+        let module = Module::new(&engine, wasm).unwrap();
+
+        // Growing to 5 pages is fine without a limit and refused with a 2-page limit.
+        assert_eq!(run_core_module(&engine, &module, &InstanceOptions::default()).await.unwrap(), 0);
+        let limits = ResourceLimits { memory_bytes: Some(2 * 65536), fuel: None };
+        let options = InstanceOptions { limits, ..Default::default() };
+        assert_eq!(run_core_module(&engine, &module, &options).await.unwrap(), 9);
+
+        // The initial memory must fit into the limit.
+        let limits = ResourceLimits { memory_bytes: Some(1024), fuel: None };
+        let options = InstanceOptions { limits, ..Default::default() };
+        assert!(run_core_module(&engine, &module, &options).await.is_err());
+
+        let limits = ResourceLimits { memory_bytes: None, fuel: Some(10_000) };
+        let options = InstanceOptions { entrypoint: Some("spin".into()), limits, ..Default::default() };
+        let err = run_core_module(&engine, &module, &options).await.unwrap_err();
+        assert!(matches!(err, ArcellaWasmtimeError::OutOfFuel));
+        assert!(err.to_string().contains("out of fuel"));
+    }
+}
@@ -0,0 +1,437 @@
+// arcella/arcella-wasmtime/src/executor/mod.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Execution of WebAssembly modules.
+//!
+//! The executor instantiates a compiled module in a fresh [`wasmtime::Store`] and
+//! drives its entry point. WASI preview1 core modules are handled by
+//! [`CoreInstance`], Component Model components by [`ComponentInstance`].
+//! The executor is shared by the daemon and `arcella-worker`.
+
+use std::future::Future;
+use std::path::Path;
+use std::time::Duration;
+use wasmtime::{
+    Engine, Module, Precompiled, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    component::Component,
+};
+use wasmtime_wasi::WasiCtxBuilder;
+
+use crate::{ArcellaWasmtimeError, Result};
+
+mod component;
+mod core;
+
+pub use component::ComponentInstance;
+pub use core::{run_core_module, CoreInstance, WASI_START};
+
+/// Kind of a WebAssembly binary, determined from its preamble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmKind {
+    /// A core WebAssembly module (e.g. a WASI preview1 command).
+    CoreModule,
+
+    /// A Component Model component.
+    Component,
+}
+
+/// Detects whether a binary is a core module or a component.
+///
+/// Both share the `\0asm` magic number; they differ in the `layer` field
+/// that follows the version (0 for core modules, 1 for components).
+///
+/// # Errors
+///
+/// Returns [`ArcellaWasmtimeError::Manifest`] if the bytes are not a WebAssembly binary.
+pub fn detect_wasm_kind(bytes: &[u8]) -> Result<WasmKind> {
+    if bytes.len() < 8 || &bytes[0..4] != b"\0asm" {
+        return Err(ArcellaWasmtimeError::Manifest("Not a WebAssembly binary".into()));
+    }
+
+    match u16::from_le_bytes([bytes[6], bytes[7]]) {
+        0 => Ok(WasmKind::CoreModule),
+        1 => Ok(WasmKind::Component),
+        layer => Err(ArcellaWasmtimeError::Manifest(format!(
+            "Unknown WebAssembly binary layer: {}", layer
+        ))),
+    }
+}
+
+/// Options for a single module instance.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceOptions {
+    /// Exported function to call.
+    ///
+    /// Defaults to `_start` for core modules. For components, the name may refer
+    /// to a top-level function (`"start"`) or to a function of an exported
+    /// interface (`"my:pkg/iface#start"`).
+    pub entrypoint: Option<String>,
+
+    /// Command-line arguments visible to the guest (`argv[0]` included).
+    pub args: Vec<String>,
+
+    /// Grants the guest the host environment variables and network access.
+    pub trusted: bool,
+
+    /// Redirects guest stdout to the host stderr.
+    ///
+    /// Used by `arcella-worker`, whose stdout is the IPC channel to the daemon.
+    pub stdout_to_stderr: bool,
+
+    /// Limits applied to the instance's store.
+    pub limits: ResourceLimits,
+
+    /// Wall-clock limit for instantiation, including the module's start function.
+    pub startup_timeout: Option<Duration>,
+
+    /// Wall-clock limit for the call into the guest's entry point
+    /// (`_start`, `wasi:cli/run` or an explicit entry point).
+    pub call_timeout: Option<Duration>,
+
+    /// Function of a resident component called on graceful shutdown
+    /// (same format as [`Self::entrypoint`]).
+    pub shutdown: Option<String>,
+
+    /// Wall-clock limit for the shutdown hook.
+    pub shutdown_timeout: Option<Duration>,
+}
+
+/// Resource limits of a single instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Maximum size of each linear memory, in bytes. Tables are not limited.
+    ///
+    /// Instantiation fails if a memory's initial size exceeds the limit; growing
+    /// beyond it makes `memory.grow` return `-1`.
+    pub memory_bytes: Option<u64>,
+
+    /// Fuel available to the instance. Execution traps with
+    /// [`ArcellaWasmtimeError::OutOfFuel`] once it is consumed.
+    pub fuel: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Builds the store limiter enforcing [`Self::memory_bytes`].
+    fn store_limits(&self) -> StoreLimits {
+        let mut builder = StoreLimitsBuilder::new();
+        if let Some(bytes) = self.memory_bytes {
+            let bytes = usize::try_from(bytes).unwrap_or(usize::MAX);
+            builder = builder.memory_size(bytes);
+        }
+        builder.build()
+    }
+
+    /// Gives the store its fuel budget (unlimited if [`Self::fuel`] is not set)
+    /// and makes it yield to the async executor on every epoch tick.
+    fn apply_to_store<T>(&self, store: &mut Store<T>) -> Result<()> {
+        store.set_fuel(self.fuel.unwrap_or(u64::MAX))?;
+        store.epoch_deadline_async_yield_and_update(1);
+        Ok(())
+    }
+}
+
+/// Result of running an instance's entry point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The guest finished with the given exit code.
+    Exited(i32),
+
+    /// The entry point returned, but the instance stays alive to serve calls.
+    Resident,
+}
+
+/// Compiled code ready to be instantiated.
+#[derive(Clone)]
+pub enum Executable {
+    /// A WASI preview1 core module.
+    Core(Module),
+
+    /// A Component Model component.
+    Component(Component),
+}
+
+impl Executable {
+    /// Compiles a `.wasm` file as a core module or a component, depending on its preamble.
+    pub fn from_file(engine: &Engine, wasm_path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(wasm_path)
+            .map_err(|e| ArcellaWasmtimeError::IoWithPath { source: e, path: wasm_path.to_path_buf() })?;
+
+        Self::from_bytes(engine, &bytes)
+    }
+
+    /// Compiles a `.wasm` binary as a core module or a component.
+    pub fn from_bytes(engine: &Engine, bytes: &[u8]) -> Result<Self> {
+        let executable = match detect_wasm_kind(bytes)? {
+            WasmKind::CoreModule => Self::Core(Module::new(engine, bytes)?),
+            WasmKind::Component => Self::Component(Component::new(engine, bytes)?),
+        };
+
+        Ok(executable)
+    }
+
+    /// Compiles a `.wasm` binary ahead of time into an artifact that can later be
+    /// loaded with [`Self::deserialize_file`] by an engine with the same configuration.
+    pub fn precompile(engine: &Engine, bytes: &[u8]) -> Result<Vec<u8>> {
+        let artifact = match detect_wasm_kind(bytes)? {
+            WasmKind::CoreModule => engine.precompile_module(bytes)?,
+            WasmKind::Component => engine.precompile_component(bytes)?,
+        };
+
+        Ok(artifact)
+    }
+
+    /// Loads an artifact produced by [`Self::precompile`].
+    ///
+    /// Artifacts built by an incompatible engine are rejected with an error.
+    ///
+    /// # Safety
+    ///
+    /// The file is mapped and executed as native code. It must have been produced
+    /// by [`Self::precompile`] and must not be writable by untrusted parties; see
+    /// [`Module::deserialize_file`].
+    pub unsafe fn deserialize_file(engine: &Engine, path: &Path) -> Result<Self> {
+        let executable = match Engine::detect_precompiled_file(path)? {
+            Some(Precompiled::Module) => Self::Core(Module::deserialize_file(engine, path)?),
+            Some(Precompiled::Component) => Self::Component(Component::deserialize_file(engine, path)?),
+            None => {
+                return Err(ArcellaWasmtimeError::Manifest(format!(
+                    "{:?} is not a precompiled artifact", path
+                )))
+            }
+        };
+
+        Ok(executable)
+    }
+
+    /// Returns the kind of the compiled binary.
+    pub fn kind(&self) -> WasmKind {
+        match self {
+            Self::Core(_) => WasmKind::CoreModule,
+            Self::Component(_) => WasmKind::Component,
+        }
+    }
+}
+
+/// Instantiates an executable and runs its entry point.
+///
+/// `on_running` is called once the instance is created, right before the entry
+/// point is invoked. Instantiation is bounded by [`InstanceOptions::startup_timeout`],
+/// the entry point call by [`InstanceOptions::call_timeout`]. Timeouts rely on the
+/// guest yielding, which requires a running [`crate::engine::EpochTicker`].
+///
+/// `shutdown` requests a graceful stop. A resident component then gets its
+/// [`InstanceOptions::shutdown`] hook called (bounded by
+/// [`InstanceOptions::shutdown_timeout`]) and finishes with exit code `0`. An
+/// instance that is still starting or inside its entry point cannot take calls,
+/// so it is interrupted instead.
+///
+/// # Returns
+///
+/// The exit code of the guest. Resident components complete only on `shutdown`;
+/// until then the instance stays alive, as long as the future is not dropped.
+///
+/// # Errors
+///
+/// Returns [`ArcellaWasmtimeError::Trap`] if instantiation fails, guest code traps
+/// or the shutdown hook fails, [`ArcellaWasmtimeError::OutOfFuel`] if the guest
+/// runs out of fuel, [`ArcellaWasmtimeError::Timeout`] if a timeout expires, and
+/// [`ArcellaWasmtimeError::Cancelled`] if `shutdown` interrupted the guest.
+pub async fn run_executable(
+    engine: &Engine,
+    executable: &Executable,
+    options: &InstanceOptions,
+    on_running: impl FnOnce(),
+    shutdown: impl Future<Output = ()>,
+) -> Result<i32> {
+    tokio::pin!(shutdown);
+    let cancelled = |what: &str| Err(ArcellaWasmtimeError::Cancelled(format!("stopped {}", what)));
+
+    match executable {
+        Executable::Core(module) => {
+            let run = async {
+                let instance = with_timeout(
+                    options.startup_timeout,
+                    "startup",
+                    CoreInstance::instantiate(engine, module, options),
+                ).await?;
+                on_running();
+                with_timeout(options.call_timeout, "entry point call", instance.run()).await
+            };
+            tokio::select! {
+                result = run => result,
+                _ = &mut shutdown => cancelled("before the module finished"),
+            }
+        }
+        Executable::Component(component) => {
+            let startup = with_timeout(
+                options.startup_timeout,
+                "startup",
+                ComponentInstance::instantiate(engine, component, options),
+            );
+            let mut instance = tokio::select! {
+                instance = startup => instance?,
+                _ = &mut shutdown => return cancelled("during startup"),
+            };
+            on_running();
+
+            let outcome = tokio::select! {
+                outcome = with_timeout(options.call_timeout, "entry point call", instance.run()) => outcome?,
+                _ = &mut shutdown => return cancelled("inside the entry point"),
+            };
+            match outcome {
+                RunOutcome::Exited(code) => Ok(code),
+                RunOutcome::Resident => {
+                    shutdown.await;
+                    if let Some(hook) = &options.shutdown {
+                        with_timeout(options.shutdown_timeout, "shutdown hook", instance.call_shutdown(hook)).await?;
+                    }
+                    Ok(0)
+                }
+            }
+        }
+    }
+}
+
+/// Awaits `future`, failing with [`ArcellaWasmtimeError::Timeout`] after `limit`.
+async fn with_timeout<T>(
+    limit: Option<Duration>,
+    what: &str,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, future).await.map_err(|_| {
+            ArcellaWasmtimeError::Timeout(format!("{} did not complete within {:?}", what, limit))
+        })?,
+        None => future.await,
+    }
+}
+
+/// Converts an error raised by guest code into an [`ArcellaWasmtimeError`].
+///
+/// Fuel exhaustion is reported as [`ArcellaWasmtimeError::OutOfFuel`], everything
+/// else as [`ArcellaWasmtimeError::Trap`] prefixed with `context` (if any).
+fn execution_error(e: wasmtime::Error, context: Option<&str>) -> ArcellaWasmtimeError {
+    if e.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+        return ArcellaWasmtimeError::OutOfFuel;
+    }
+
+    match context {
+        Some(context) => ArcellaWasmtimeError::Trap(format!("{}: {:#}", context, e)),
+        None => ArcellaWasmtimeError::Trap(format!("{:#}", e)),
+    }
+}
+
+/// Builds the WASI context shared by both module kinds.
+fn wasi_builder(options: &InstanceOptions) -> WasiCtxBuilder {
+    let mut builder = WasiCtxBuilder::new();
+    builder
+        .inherit_stderr()
+        .args(&options.args);
+
+    if options.stdout_to_stderr {
+        builder.stdout(std::io::stderr());
+    } else {
+        builder.inherit_stdout();
+    }
+
+    if options.trusted {
+        builder.inherit_env().inherit_network();
+    }
+
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_wasm_kind() {
+        assert_eq!(detect_wasm_kind(b"\0asm\x01\0\0\0").unwrap(), WasmKind::CoreModule);
+        assert_eq!(detect_wasm_kind(b"\0asm\x0d\0\x01\0").unwrap(), WasmKind::Component);
+        assert!(detect_wasm_kind(b"junk").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_interrupt_spinning_guests() {
+        let engine = crate::engine::create_engine().unwrap();
+        let _ticker = crate::engine::EpochTicker::start(&engine);
+
+        let spin = Module::new(&engine, r#"(module (func (export "_start") (loop $l (br $l))))"#).unwrap();
+        let options = InstanceOptions {
+            call_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let err = run_executable(&engine, &Executable::Core(spin), &options, || {}, std::future::pending())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ArcellaWasmtimeError::Timeout(msg) if msg.contains("entry point call")));
+
+        let spin_on_start = Module::new(&engine, r#"
+            (module
+              (func $spin (loop $l (br $l)))
+              (start $spin)
+              (func (export "_start")))
+        "#).unwrap();
+        let options = InstanceOptions {
+            startup_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let mut running = false;
+        let err = run_executable(
+            &engine, &Executable::Core(spin_on_start), &options, || running = true, std::future::pending(),
+        ).await.unwrap_err();
+        assert!(matches!(err, ArcellaWasmtimeError::Timeout(msg) if msg.contains("startup")));
+        assert!(!running);
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_calls_hook() {
+        let engine = crate::engine::create_engine().unwrap();
+        let _ticker = crate::engine::EpochTicker::start(&engine);
+        let component = Executable::Component(Component::new(&engine, r#"
+            (component
+              (core module $m
+                (func (export "start"))
+                (func (export "stop"))
+                (func (export "fail") unreachable)
+                (func (export "spin") (loop $l (br $l))))
+              (core instance $i (instantiate $m))
+              (func (export "start") (canon lift (core func $i "start")))
+              (func (export "stop") (canon lift (core func $i "stop")))
+              (func (export "fail") (canon lift (core func $i "fail")))
+              (func (export "spin") (canon lift (core func $i "spin"))))
+        "#).unwrap());
+
+        let run = |hook: &str| {
+            let options = InstanceOptions {
+                entrypoint: Some("start".into()),
+                shutdown: Some(hook.into()),
+                shutdown_timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            };
+            let engine = engine.clone();
+            let component = component.clone();
+            async move {
+                let shutdown = tokio::time::sleep(Duration::from_millis(20));
+                run_executable(&engine, &component, &options, || {}, shutdown).await
+            }
+        };
+
+        assert_eq!(run("stop").await.unwrap(), 0);
+        assert!(matches!(run("fail").await.unwrap_err(), ArcellaWasmtimeError::Trap(_)));
+        assert!(matches!(run("spin").await.unwrap_err(), ArcellaWasmtimeError::Timeout(_)));
+
+        // An instance that is still inside its entry point is interrupted.
+        let options = InstanceOptions { entrypoint: Some("spin".into()), ..Default::default() };
+        let shutdown = tokio::time::sleep(Duration::from_millis(20));
+        let err = run_executable(&engine, &component, &options, || {}, shutdown).await.unwrap_err();
+        assert!(matches!(err, ArcellaWasmtimeError::Cancelled(_)));
+    }
+}
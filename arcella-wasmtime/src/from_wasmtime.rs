@@ -17,6 +17,7 @@ use wasmtime::{
 
 use arcella_types::spec::ComponentItemSpec;
 use crate::Result;
+use crate::val::TypeSpec;
 
 const MAX_RECURSION_DEPTH: usize = 32;
 
@@ -163,23 +164,11 @@ fn to_spec_with_depth(
 }
 
 fn type_to_string(ty: &types::Type) -> String {
-    match ty {
-        types::Type::Bool => "bool".into(),
-        types::Type::S8 => "s8".into(),
-        types::Type::U8 => "u8".into(),
-        types::Type::S16 => "s16".into(),
-        types::Type::U16 => "u16".into(),
-        types::Type::S32 => "s32".into(),
-        types::Type::U32 => "u32".into(),
-        types::Type::S64 => "s64".into(),
-        types::Type::U64 => "u64".into(),
-        types::Type::Float32 => "f32".into(),
-        types::Type::Float64 => "f64".into(),
-        types::Type::Char => "char".into(),
-        types::Type::String => "string".into(),
-        _ => format!("unknown({:?})", ty),
+    match TypeSpec::from_type(ty) {
+        Some(spec) => spec.to_string(),
+        None => format!("unknown({:?})", ty),
     }
-}    
+}
 
 #[cfg(test)]
 mod tests {
@@ -13,12 +13,19 @@
 //! into the stable, serializable types defined in `arcella-types`.
 //!
 //! It is intended for use by the Arcella runtime and CLI tools that need to
-//! inspect WebAssembly components using Wasmtime as the engine.
+//! inspect WebAssembly components using Wasmtime as the engine. It also hosts the
+//! shared engine configuration and the module executor used by the daemon and
+//! `arcella-worker`, and converts configuration values to and from component
+//! values so that arguments received over ALME can be passed to component functions.
 
+pub mod engine;
 pub mod error;
+pub mod executor;
 mod from_wasmtime;
 pub mod manifest;
+mod val;
 
 pub use error::{ArcellaWasmtimeError, Result};
 pub use from_wasmtime::{ComponentItemSpecExt, ComponentTypeExt};
 pub use manifest::ComponentManifestExt;
+pub use val::{val_to_value, value_to_val, ComponentFuncSpecExt};
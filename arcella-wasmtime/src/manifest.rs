@@ -13,6 +13,8 @@ use std::path::{Path};
 use std::sync::OnceLock;
 use wasmtime::{
     Engine,
+    ExternType,
+    Module,
     component::{
         Component, 
     }
@@ -24,6 +26,7 @@ use arcella_types::{
 };
 use crate::ArcellaWasmtimeError;
 use crate::Result;
+use crate::executor::{detect_wasm_kind, WasmKind};
 use crate::from_wasmtime::{ComponentItemSpecExt, ComponentTypeExt};
 
 pub trait ComponentManifestExt {
@@ -62,11 +65,12 @@ impl ComponentManifestExt for ComponentManifest {
 
 }
 
-/// Extracts component metadata directly from a WebAssembly Component binary.
+/// Extracts component metadata directly from a WebAssembly binary.
 ///
 /// This function:
-/// - Only works with **WebAssembly Components** (not core Wasm or WASI preview1 modules).
-/// - Extracts imports and exports in the format `namespace:interface`.
+/// - Works with **WebAssembly Components** and core Wasm (e.g. WASI preview1) modules.
+/// - Extracts component imports and exports in the format `namespace:interface`,
+///   and core module imports as `module#name`.
 /// - Does **not** include version (`@x.y`) — this must be provided via `component.toml`
 ///   or inferred from file naming convention if needed later.
 /// - Requires a valid `name` and `version` — since they are not stored in Wasm,
@@ -98,11 +102,48 @@ pub fn component_manifest_from_wasm(engine: &Engine, wasm_path: &Path) -> Result
         ));
     }
 
-    let (name, version) = file_stem
+    component_manifest_from_wasm_with_id(engine, wasm_path, file_stem)
+}
+
+/// Extracts component metadata from a WebAssembly binary whose `name@version`
+/// identity is already known (e.g. a content-addressed blob of an installed
+/// module, whose file name is a hash).
+pub fn component_manifest_from_wasm_with_id(
+    engine: &Engine,
+    wasm_path: &Path,
+    module_id: &str,
+) -> Result<ComponentManifest> {
+    let (name, version) = module_id
         .split_once('@')
         .ok_or_else(|| ArcellaWasmtimeError::Manifest("Expected 'name@version' format".into()))?;
 
-    let component = Component::from_file(engine, &wasm_path)
+    let bytes = std::fs::read(wasm_path)
+        .map_err(|e| ArcellaWasmtimeError::IoWithPath { source: e, path: wasm_path.into() })?;
+    let (exports, imports) = match detect_wasm_kind(&bytes)? {
+        WasmKind::CoreModule => core_module_items(engine, &bytes)?,
+        WasmKind::Component => component_items(engine, &bytes)?,
+    };
+
+    let manifest = ComponentManifest {
+        name: name.into(),
+        version: version.into(),
+        description: None,
+        exports,
+        imports,
+        capabilities: ComponentCapabilities::default(),
+    };
+
+    manifest.validate()?;
+    Ok(manifest)
+
+}
+
+/// Exports and imports of a manifest, keyed by item name.
+type ManifestItems = (HashMap<String, ComponentItemSpec>, HashMap<String, ComponentItemSpec>);
+
+/// Lists the exports and imports of a WebAssembly Component.
+fn component_items(engine: &Engine, bytes: &[u8]) -> Result<ManifestItems> {
+    let component = Component::new(engine, bytes)
         .map_err(ArcellaWasmtimeError::Wasmtime)?;
     
     let component_type = component.component_type();
@@ -131,16 +172,62 @@ pub fn component_manifest_from_wasm(engine: &Engine, wasm_path: &Path) -> Result
         })
         .collect();
 
-    let manifest = ComponentManifest {
-        name: name.into(),
-        version: version.into(),
-        description: None,
-        exports: exports,
-        imports: imports,
-        capabilities: ComponentCapabilities::default(),
-    };
+    Ok((exports, imports))
+}
 
-    manifest.validate()?;
-    Ok(manifest)
+/// Lists the exports and imports of a core module. Imports are named
+/// `module#name`, e.g. `wasi_snapshot_preview1#fd_write`.
+fn core_module_items(engine: &Engine, bytes: &[u8]) -> Result<ManifestItems> {
+    let module = Module::new(engine, bytes)
+        .map_err(ArcellaWasmtimeError::Wasmtime)?;
+
+    let exports = module
+        .exports()
+        .map(|export| (export.name().to_string(), core_item_spec(export.ty())))
+        .collect();
 
+    let imports = module
+        .imports()
+        .map(|import| (format!("{}#{}", import.module(), import.name()), core_item_spec(import.ty())))
+        .collect();
+
+    Ok((exports, imports))
+}
+
+/// Describes a core module item: functions by their signature, anything else
+/// (memories, tables, globals, tags) as an unknown item.
+fn core_item_spec(ty: ExternType) -> ComponentItemSpec {
+    match ty {
+        ExternType::Func(func_ty) => ComponentItemSpec::CoreFunc(format!("{}", func_ty)),
+        other => ComponentItemSpec::Unknown { debug: Some(format!("{:?}", other)) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::create_engine;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_from_core_module() {
+        let temp_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func (param i32)))
+              (memory (export "memory") 1)
+              (func (export "_start")))
+        "#).unwrap();
+        let wasm_path = temp_dir.path().join("exit@0.1.0.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+
+        let manifest = component_manifest_from_wasm(&create_engine().unwrap(), &wasm_path).unwrap();
+        assert_eq!(manifest.id(), "exit@0.1.0");
+        assert!(matches!(manifest.exports["_start"], ComponentItemSpec::CoreFunc(_)));
+        assert!(matches!(manifest.exports["memory"], ComponentItemSpec::Unknown { .. }));
+        assert!(matches!(
+            manifest.imports["wasi_snapshot_preview1#proc_exit"],
+            ComponentItemSpec::CoreFunc(_),
+        ));
+    }
 }
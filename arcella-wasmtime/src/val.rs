@@ -0,0 +1,687 @@
+// arcella/arcella-wasmtime/src/val.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversion between `arcella_types::config::Value` and `wasmtime::component::Val`.
+//!
+//! A `Val` carries its own type, so it converts to a [`Value`] directly. The
+//! other direction needs the expected component type, which is taken from the
+//! type names recorded in a [`ComponentItemSpec::ComponentFunc`]. This lets
+//! arguments received as JSON (e.g., over ALME) be passed to component
+//! functions and their results be returned the same way.
+//!
+//! # Mapping
+//!
+//! | Component type                      | `Value`                                        |
+//! |-------------------------------------|------------------------------------------------|
+//! | `bool`                              | `Boolean`                                      |
+//! | `s8` … `u64`                        | `Integer` (range-checked)                      |
+//! | `f32`, `f64`                        | `Float` (an `Integer` is accepted as input)    |
+//! | `char`, `string`                    | `String`                                       |
+//! | `list<T>`, `tuple<…>`               | `Array`                                        |
+//! | `record{…}`                         | `Map` of the fields                            |
+//! | `option<T>`                         | `Null` or the value                            |
+//! | `result<T, E>`                      | `Map` with a single key `ok` or `err`          |
+//! | `variant{…}`                        | case name, or `Map` `{ case: payload }`        |
+//! | `enum{…}`                           | `String`                                       |
+//! | `flags{…}`                          | `Array` of the set flag names                  |
+//!
+//! Resources, futures, streams and error contexts cannot be converted.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use ordered_float::OrderedFloat;
+use wasmtime::component::{types, Val};
+
+use arcella_types::config::Value;
+use arcella_types::spec::ComponentItemSpec;
+
+use crate::{ArcellaWasmtimeError, Result};
+
+/// Maximum nesting of type names and values handled by the conversions.
+const MAX_VALUE_DEPTH: usize = 32;
+
+/// A component value type, as written in the type names of a [`ComponentItemSpec`].
+///
+/// The textual form is WIT-like; anonymous records, variants, enums and flags
+/// list their fields or cases inline, e.g. `record{name: string, port: u16}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TypeSpec {
+    Bool,
+    S8,
+    U8,
+    S16,
+    U16,
+    S32,
+    U32,
+    S64,
+    U64,
+    F32,
+    F64,
+    Char,
+    String,
+    List(Box<TypeSpec>),
+    Option(Box<TypeSpec>),
+    Result { ok: Option<Box<TypeSpec>>, err: Option<Box<TypeSpec>> },
+    Tuple(Vec<TypeSpec>),
+    Record(Vec<(String, TypeSpec)>),
+    Variant(Vec<(String, Option<TypeSpec>)>),
+    Enum(Vec<String>),
+    Flags(Vec<String>),
+}
+
+impl TypeSpec {
+    /// Describes a wasmtime component type.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the type (or a type nested in it) is a resource, future,
+    /// stream or error context.
+    pub(crate) fn from_type(ty: &types::Type) -> Option<TypeSpec> {
+        let boxed = |ty: &types::Type| TypeSpec::from_type(ty).map(Box::new);
+        Some(match ty {
+            types::Type::Bool => TypeSpec::Bool,
+            types::Type::S8 => TypeSpec::S8,
+            types::Type::U8 => TypeSpec::U8,
+            types::Type::S16 => TypeSpec::S16,
+            types::Type::U16 => TypeSpec::U16,
+            types::Type::S32 => TypeSpec::S32,
+            types::Type::U32 => TypeSpec::U32,
+            types::Type::S64 => TypeSpec::S64,
+            types::Type::U64 => TypeSpec::U64,
+            types::Type::Float32 => TypeSpec::F32,
+            types::Type::Float64 => TypeSpec::F64,
+            types::Type::Char => TypeSpec::Char,
+            types::Type::String => TypeSpec::String,
+            types::Type::List(list) => TypeSpec::List(boxed(&list.ty())?),
+            types::Type::Option(option) => TypeSpec::Option(boxed(&option.ty())?),
+            types::Type::Result(result) => TypeSpec::Result {
+                ok: match result.ok() {
+                    Some(ty) => Some(boxed(&ty)?),
+                    None => None,
+                },
+                err: match result.err() {
+                    Some(ty) => Some(boxed(&ty)?),
+                    None => None,
+                },
+            },
+            types::Type::Tuple(tuple) => {
+                TypeSpec::Tuple(tuple.types().map(|ty| TypeSpec::from_type(&ty)).collect::<Option<_>>()?)
+            }
+            types::Type::Record(record) => TypeSpec::Record(
+                record.fields()
+                    .map(|field| Some((field.name.to_string(), TypeSpec::from_type(&field.ty)?)))
+                    .collect::<Option<_>>()?
+            ),
+            types::Type::Variant(variant) => TypeSpec::Variant(
+                variant.cases()
+                    .map(|case| {
+                        let ty = match case.ty {
+                            Some(ty) => Some(TypeSpec::from_type(&ty)?),
+                            None => None,
+                        };
+                        Some((case.name.to_string(), ty))
+                    })
+                    .collect::<Option<_>>()?
+            ),
+            types::Type::Enum(enum_ty) => TypeSpec::Enum(enum_ty.names().map(str::to_string).collect()),
+            types::Type::Flags(flags) => TypeSpec::Flags(flags.names().map(str::to_string).collect()),
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for TypeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T>(f: &mut fmt::Formatter<'_>, items: &[T], item: impl Fn(&mut fmt::Formatter<'_>, &T) -> fmt::Result) -> fmt::Result {
+            for (i, it) in items.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                item(f, it)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            TypeSpec::Bool => f.write_str("bool"),
+            TypeSpec::S8 => f.write_str("s8"),
+            TypeSpec::U8 => f.write_str("u8"),
+            TypeSpec::S16 => f.write_str("s16"),
+            TypeSpec::U16 => f.write_str("u16"),
+            TypeSpec::S32 => f.write_str("s32"),
+            TypeSpec::U32 => f.write_str("u32"),
+            TypeSpec::S64 => f.write_str("s64"),
+            TypeSpec::U64 => f.write_str("u64"),
+            TypeSpec::F32 => f.write_str("f32"),
+            TypeSpec::F64 => f.write_str("f64"),
+            TypeSpec::Char => f.write_str("char"),
+            TypeSpec::String => f.write_str("string"),
+            TypeSpec::List(ty) => write!(f, "list<{}>", ty),
+            TypeSpec::Option(ty) => write!(f, "option<{}>", ty),
+            TypeSpec::Result { ok: None, err: None } => f.write_str("result"),
+            TypeSpec::Result { ok: Some(ok), err: None } => write!(f, "result<{}>", ok),
+            TypeSpec::Result { ok: None, err: Some(err) } => write!(f, "result<_, {}>", err),
+            TypeSpec::Result { ok: Some(ok), err: Some(err) } => write!(f, "result<{}, {}>", ok, err),
+            TypeSpec::Tuple(types) => {
+                f.write_str("tuple<")?;
+                join(f, types, |f, ty| write!(f, "{}", ty))?;
+                f.write_str(">")
+            }
+            TypeSpec::Record(fields) => {
+                f.write_str("record{")?;
+                join(f, fields, |f, (name, ty)| write!(f, "{}: {}", name, ty))?;
+                f.write_str("}")
+            }
+            TypeSpec::Variant(cases) => {
+                f.write_str("variant{")?;
+                join(f, cases, |f, (name, ty)| match ty {
+                    Some(ty) => write!(f, "{}({})", name, ty),
+                    None => f.write_str(name),
+                })?;
+                f.write_str("}")
+            }
+            TypeSpec::Enum(names) => {
+                f.write_str("enum{")?;
+                join(f, names, |f, name| f.write_str(name))?;
+                f.write_str("}")
+            }
+            TypeSpec::Flags(names) => {
+                f.write_str("flags{")?;
+                join(f, names, |f, name| f.write_str(name))?;
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl FromStr for TypeSpec {
+    type Err = ArcellaWasmtimeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = TypeParser { input: s, pos: 0 };
+        let ty = parser.parse_type(0)?;
+        parser.skip_whitespace();
+        if parser.pos != s.len() {
+            return Err(parser.error());
+        }
+        Ok(ty)
+    }
+}
+
+/// Recursive-descent parser of the textual form of [`TypeSpec`].
+struct TypeParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl TypeParser<'_> {
+    fn error(&self) -> ArcellaWasmtimeError {
+        ArcellaWasmtimeError::Conversion(format!("invalid type '{}' at offset {}", self.input, self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `c` (after optional whitespace) if it is next.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) { Ok(()) } else { Err(self.error()) }
+    }
+
+    /// Parses a WIT identifier (letters, digits, `-` and `_`).
+    fn ident(&mut self) -> Result<&str> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error());
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Parses a comma-separated list of items up to `close`.
+    fn list<T>(&mut self, close: char, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn parse_type(&mut self, depth: usize) -> Result<TypeSpec> {
+        if depth > MAX_VALUE_DEPTH {
+            return Err(self.error());
+        }
+        let boxed = |parser: &mut Self| parser.parse_type(depth + 1).map(Box::new);
+
+        Ok(match self.ident()? {
+            "bool" => TypeSpec::Bool,
+            "s8" => TypeSpec::S8,
+            "u8" => TypeSpec::U8,
+            "s16" => TypeSpec::S16,
+            "u16" => TypeSpec::U16,
+            "s32" => TypeSpec::S32,
+            "u32" => TypeSpec::U32,
+            "s64" => TypeSpec::S64,
+            "u64" => TypeSpec::U64,
+            "f32" | "float32" => TypeSpec::F32,
+            "f64" | "float64" => TypeSpec::F64,
+            "char" => TypeSpec::Char,
+            "string" => TypeSpec::String,
+            "list" => {
+                self.expect('<')?;
+                let ty = boxed(self)?;
+                self.expect('>')?;
+                TypeSpec::List(ty)
+            }
+            "option" => {
+                self.expect('<')?;
+                let ty = boxed(self)?;
+                self.expect('>')?;
+                TypeSpec::Option(ty)
+            }
+            "result" => {
+                if !self.eat('<') {
+                    return Ok(TypeSpec::Result { ok: None, err: None });
+                }
+                let ok = if self.eat('_') { None } else { Some(boxed(self)?) };
+                let err = if self.eat(',') { Some(boxed(self)?) } else { None };
+                self.expect('>')?;
+                TypeSpec::Result { ok, err }
+            }
+            "tuple" => {
+                self.expect('<')?;
+                TypeSpec::Tuple(self.list('>', |parser| parser.parse_type(depth + 1))?)
+            }
+            "record" => {
+                self.expect('{')?;
+                TypeSpec::Record(self.list('}', |parser| {
+                    let name = parser.ident()?.to_string();
+                    parser.expect(':')?;
+                    Ok((name, parser.parse_type(depth + 1)?))
+                })?)
+            }
+            "variant" => {
+                self.expect('{')?;
+                TypeSpec::Variant(self.list('}', |parser| {
+                    let name = parser.ident()?.to_string();
+                    let ty = if parser.eat('(') {
+                        let ty = parser.parse_type(depth + 1)?;
+                        parser.expect(')')?;
+                        Some(ty)
+                    } else {
+                        None
+                    };
+                    Ok((name, ty))
+                })?)
+            }
+            "enum" => {
+                self.expect('{')?;
+                TypeSpec::Enum(self.list('}', |parser| parser.ident().map(str::to_string))?)
+            }
+            "flags" => {
+                self.expect('{')?;
+                TypeSpec::Flags(self.list('}', |parser| parser.ident().map(str::to_string))?)
+            }
+            _ => return Err(self.error()),
+        })
+    }
+}
+
+fn conversion_error(message: String) -> ArcellaWasmtimeError {
+    ArcellaWasmtimeError::Conversion(message)
+}
+
+/// Converts a [`Value`] into a component value of the type named `ty`
+/// (e.g., `"u32"`, `"list<string>"`, `"record{name: string, port: u16}"`).
+///
+/// # Errors
+///
+/// Returns [`ArcellaWasmtimeError::Conversion`] if `ty` cannot be parsed or the
+/// value does not match it (wrong type, integer out of range, unknown case or field).
+pub fn value_to_val(value: &Value, ty: &str) -> Result<Val> {
+    convert_to_val(value, &ty.parse()?, 0)
+}
+
+fn convert_to_val(value: &Value, ty: &TypeSpec, depth: usize) -> Result<Val> {
+    if depth > MAX_VALUE_DEPTH {
+        return Err(conversion_error(format!("value nested deeper than {} levels", MAX_VALUE_DEPTH)));
+    }
+    let mismatch = || conversion_error(format!("expected {}, found {:?}", ty, value));
+    let convert = |value: &Value, ty: &TypeSpec| convert_to_val(value, ty, depth + 1);
+    let int = |value: &Value| match value {
+        Value::Integer(i) => Ok(*i),
+        _ => Err(mismatch()),
+    };
+    let range = |_| conversion_error(format!("{:?} is out of range for {}", value, ty));
+
+    Ok(match (ty, value) {
+        (TypeSpec::Bool, Value::Boolean(b)) => Val::Bool(*b),
+        (TypeSpec::S8, _) => Val::S8(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::U8, _) => Val::U8(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::S16, _) => Val::S16(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::U16, _) => Val::U16(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::S32, _) => Val::S32(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::U32, _) => Val::U32(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::S64, _) => Val::S64(int(value)?),
+        (TypeSpec::U64, _) => Val::U64(int(value)?.try_into().map_err(range)?),
+        (TypeSpec::F32, Value::Float(f)) => Val::Float32(f.0 as f32),
+        (TypeSpec::F32, Value::Integer(i)) => Val::Float32(*i as f32),
+        (TypeSpec::F64, Value::Float(f)) => Val::Float64(f.0),
+        (TypeSpec::F64, Value::Integer(i)) => Val::Float64(*i as f64),
+        (TypeSpec::Char, Value::String(s)) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Val::Char(c),
+                _ => return Err(mismatch()),
+            }
+        }
+        (TypeSpec::String, Value::String(s)) => Val::String(s.clone()),
+        (TypeSpec::List(item), Value::Array(items)) => {
+            Val::List(items.iter().map(|v| convert(v, item)).collect::<Result<_>>()?)
+        }
+        (TypeSpec::Option(_), Value::Null) => Val::Option(None),
+        (TypeSpec::Option(inner), _) => Val::Option(Some(Box::new(convert(value, inner)?))),
+        (TypeSpec::Result { ok, err }, Value::Map(map)) if map.len() == 1 => {
+            let payload = |ty: &Option<Box<TypeSpec>>, value: &Value| match (ty, value) {
+                (None, Value::Null) => Ok(None),
+                (Some(ty), _) => Ok(Some(Box::new(convert(value, ty)?))),
+                (None, _) => Err(mismatch()),
+            };
+            match (map.get("ok"), map.get("err")) {
+                (Some(value), None) => Val::Result(Ok(payload(ok, value)?)),
+                (None, Some(value)) => Val::Result(Err(payload(err, value)?)),
+                _ => return Err(mismatch()),
+            }
+        }
+        (TypeSpec::Tuple(types), Value::Array(items)) if types.len() == items.len() => Val::Tuple(
+            items.iter().zip(types).map(|(v, ty)| convert(v, ty)).collect::<Result<_>>()?
+        ),
+        (TypeSpec::Record(fields), Value::Map(map)) => {
+            if let Some(unknown) = map.keys().find(|key| !fields.iter().any(|(name, _)| name == *key)) {
+                return Err(conversion_error(format!("unknown field '{}' for {}", unknown, ty)));
+            }
+            let field = |(name, ty): &(String, TypeSpec)| -> Result<(String, Val)> {
+                let val = match (map.get(name), ty) {
+                    (Some(value), _) => convert(value, ty)?,
+                    // A missing optional field is `none`
+                    (None, TypeSpec::Option(_)) => Val::Option(None),
+                    (None, _) => return Err(conversion_error(format!("missing field '{}' for {}", name, ty))),
+                };
+                Ok((name.clone(), val))
+            };
+            Val::Record(fields.iter().map(field).collect::<Result<_>>()?)
+        }
+        (TypeSpec::Variant(cases), _) => {
+            let (name, payload) = match value {
+                Value::String(name) => (name, &Value::Null),
+                Value::Map(map) if map.len() == 1 => map.iter().next().unwrap(),
+                _ => return Err(mismatch()),
+            };
+            let payload_ty = cases.iter()
+                .find(|(case, _)| case == name)
+                .map(|(_, ty)| ty)
+                .ok_or_else(|| conversion_error(format!("unknown case '{}' for {}", name, ty)))?;
+            let payload = match (payload_ty, payload) {
+                (None, Value::Null) => None,
+                (Some(ty), _) => Some(Box::new(convert(payload, ty)?)),
+                (None, _) => return Err(mismatch()),
+            };
+            Val::Variant(name.clone(), payload)
+        }
+        (TypeSpec::Enum(names), Value::String(name)) if names.contains(name) => Val::Enum(name.clone()),
+        (TypeSpec::Flags(names), Value::Array(items)) => Val::Flags(
+            items.iter()
+                .map(|item| match item {
+                    Value::String(name) if names.contains(name) => Ok(name.clone()),
+                    _ => Err(mismatch()),
+                })
+                .collect::<Result<_>>()?
+        ),
+        _ => return Err(mismatch()),
+    })
+}
+
+/// Converts a component value into a [`Value`].
+///
+/// Note that `option<option<T>>` and `option<T>` with a `null` payload cannot
+/// be told apart once converted.
+///
+/// # Errors
+///
+/// Returns [`ArcellaWasmtimeError::Conversion`] for resources, futures, streams
+/// and error contexts, and for `u64` values above `i64::MAX`.
+pub fn val_to_value(val: &Val) -> Result<Value> {
+    convert_to_value(val, 0)
+}
+
+fn convert_to_value(val: &Val, depth: usize) -> Result<Value> {
+    if depth > MAX_VALUE_DEPTH {
+        return Err(conversion_error(format!("value nested deeper than {} levels", MAX_VALUE_DEPTH)));
+    }
+    let convert = |val: &Val| convert_to_value(val, depth + 1);
+    let payload = |val: &Option<Box<Val>>| val.as_deref().map_or(Ok(Value::Null), convert);
+    let single = |key: &str, value: Value| Value::Map(HashMap::from([(key.to_string(), value)]));
+
+    Ok(match val {
+        Val::Bool(b) => Value::Boolean(*b),
+        Val::S8(i) => Value::Integer(i64::from(*i)),
+        Val::U8(i) => Value::Integer(i64::from(*i)),
+        Val::S16(i) => Value::Integer(i64::from(*i)),
+        Val::U16(i) => Value::Integer(i64::from(*i)),
+        Val::S32(i) => Value::Integer(i64::from(*i)),
+        Val::U32(i) => Value::Integer(i64::from(*i)),
+        Val::S64(i) => Value::Integer(*i),
+        Val::U64(i) => Value::Integer(
+            i64::try_from(*i).map_err(|_| conversion_error(format!("u64 value {} does not fit an integer", i)))?
+        ),
+        Val::Float32(f) => Value::Float(OrderedFloat(f64::from(*f))),
+        Val::Float64(f) => Value::Float(OrderedFloat(*f)),
+        Val::Char(c) => Value::String(c.to_string()),
+        Val::String(s) => Value::String(s.clone()),
+        Val::List(items) | Val::Tuple(items) => Value::Array(items.iter().map(convert).collect::<Result<_>>()?),
+        Val::Record(fields) => Value::Map(
+            fields.iter().map(|(name, val)| Ok((name.clone(), convert(val)?))).collect::<Result<_>>()?
+        ),
+        Val::Variant(name, None) | Val::Enum(name) => Value::String(name.clone()),
+        Val::Variant(name, Some(val)) => single(name, convert(val)?),
+        Val::Option(val) => payload(val)?,
+        Val::Result(Ok(val)) => single("ok", payload(val)?),
+        Val::Result(Err(val)) => single("err", payload(val)?),
+        Val::Flags(names) => Value::Array(names.iter().cloned().map(Value::String).collect()),
+        other => return Err(conversion_error(format!("{:?} cannot be converted to a value", other))),
+    })
+}
+
+/// Extension trait converting the arguments and results of a component function
+/// as described by its [`ComponentItemSpec::ComponentFunc`].
+pub trait ComponentFuncSpecExt {
+    /// Converts call arguments into component values, one per parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` – `Null` for a function without parameters, an `Array` of
+    ///   positional arguments, or a `Map` of arguments by parameter name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Conversion`] if the spec is not a component
+    /// function, an argument is missing or superfluous, or does not match its type.
+    fn params_to_vals(&self, args: &Value) -> Result<Vec<Val>>;
+
+    /// Converts the results of a call into values, checking their number against the spec.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaWasmtimeError::Conversion`] if the spec is not a component
+    /// function, the number of results differs, or a result cannot be converted.
+    fn results_to_values(&self, results: &[Val]) -> Result<Vec<Value>>;
+}
+
+impl ComponentFuncSpecExt for ComponentItemSpec {
+    fn params_to_vals(&self, args: &Value) -> Result<Vec<Val>> {
+        let ComponentItemSpec::ComponentFunc { params, .. } = self else {
+            return Err(conversion_error(format!("{} is not a component function", self)));
+        };
+        let count_error = |count: usize| {
+            conversion_error(format!("expected {} argument(s) for {}, found {}", params.len(), self, count))
+        };
+
+        match args {
+            Value::Null if params.is_empty() => Ok(Vec::new()),
+            Value::Array(items) if items.len() == params.len() => items.iter()
+                .zip(params)
+                .map(|(value, (name, ty))| value_to_val(value, ty)
+                    .map_err(|e| conversion_error(format!("argument '{}': {}", name, e))))
+                .collect(),
+            Value::Array(items) => Err(count_error(items.len())),
+            Value::Map(map) => {
+                if let Some(unknown) = map.keys().find(|key| !params.iter().any(|(name, _)| name == *key)) {
+                    return Err(conversion_error(format!("unknown argument '{}' for {}", unknown, self)));
+                }
+                params.iter()
+                    .map(|(name, ty)| {
+                        let value = map.get(name)
+                            .ok_or_else(|| conversion_error(format!("missing argument '{}' for {}", name, self)))?;
+                        value_to_val(value, ty)
+                            .map_err(|e| conversion_error(format!("argument '{}': {}", name, e)))
+                    })
+                    .collect()
+            }
+            _ => Err(count_error(1)),
+        }
+    }
+
+    fn results_to_values(&self, results: &[Val]) -> Result<Vec<Value>> {
+        let ComponentItemSpec::ComponentFunc { results: types, .. } = self else {
+            return Err(conversion_error(format!("{} is not a component function", self)));
+        };
+        if types.len() != results.len() {
+            return Err(conversion_error(format!(
+                "expected {} result(s) for {}, found {}", types.len(), self, results.len()
+            )));
+        }
+        results.iter().map(val_to_value).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmtime::{component::{Component, Linker}, Engine, Store};
+
+    use crate::ComponentTypeExt;
+
+    #[test]
+    fn test_type_spec_round_trip() {
+        for ty in [
+            "u32",
+            "list<option<string>>",
+            "result",
+            "result<u8>",
+            "result<_, string>",
+            "result<tuple<s64, f64>, list<char>>",
+            "record{name: string, port: u16, tags: flags{read, write}}",
+            "variant{none, some(enum{low, high})}",
+        ] {
+            assert_eq!(ty.parse::<TypeSpec>().unwrap().to_string(), ty);
+        }
+
+        assert!("list<u32".parse::<TypeSpec>().is_err());
+        assert!("record{name string}".parse::<TypeSpec>().is_err());
+        assert!("unknown(Own)".parse::<TypeSpec>().is_err());
+    }
+
+    #[test]
+    fn test_value_val_round_trip() {
+        let ty = "record{name: string, port: u16, mode: variant{auto, fixed(f64)}, \
+                  limit: option<u32>, outcome: result<_, string>, tags: flags{read, write}}";
+        let value = Value::from(serde_json::json!({
+            "name": "alme",
+            "port": 8080,
+            "mode": { "fixed": 1.5 },
+            "outcome": { "err": "denied" },
+            "tags": ["write"],
+        }));
+
+        let val = value_to_val(&value, ty).unwrap();
+        let Val::Record(fields) = &val else { panic!("Expected record") };
+        assert_eq!(fields[1], ("port".to_string(), Val::U16(8080)));
+        assert_eq!(fields[3], ("limit".to_string(), Val::Option(None)));
+
+        let mut expected = value;
+        if let Value::Map(map) = &mut expected {
+            map.insert("limit".to_string(), Value::Null);
+        }
+        assert_eq!(val_to_value(&val).unwrap(), expected);
+
+        assert!(value_to_val(&Value::Integer(256), "u8").is_err());
+        assert!(value_to_val(&Value::Integer(-1), "u64").is_err());
+        assert!(value_to_val(&Value::String("ab".to_string()), "char").is_err());
+        assert!(value_to_val(&Value::String("other".to_string()), "enum{a, b}").is_err());
+        assert!(val_to_value(&Val::U64(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_call_component_func_with_converted_values() -> Result<()> {
+        let engine = Engine::default();
+        let wat = r#"
+            (component
+                (core module $m
+                    (func (export "add") (param i32 i32) (result i32)
+                        local.get 0
+                        local.get 1
+                        i32.add)
+                )
+                (core instance $i (instantiate $m))
+                (func (export "add") (param "a" u32) (param "b" u32) (result u32)
+                    (canon lift (core func $i "add"))
+                )
+            )
+        "#;
+        let component = Component::new(&engine, wat)?;
+        let spec = component.component_type().exports_spec(&engine)?.remove("add").unwrap();
+
+        let args = Value::from(serde_json::json!({ "b": 3, "a": 2 }));
+        let params = spec.params_to_vals(&args)?;
+        assert_eq!(params, vec![Val::U32(2), Val::U32(3)]);
+        assert!(spec.params_to_vals(&Value::from(serde_json::json!([1]))).is_err());
+
+        let mut store = Store::new(&engine, ());
+        let instance = Linker::new(&engine).instantiate(&mut store, &component)?;
+        let func = instance.get_func(&mut store, "add").unwrap();
+        let mut results = vec![Val::Bool(false)];
+        func.call(&mut store, &params, &mut results)?;
+        func.post_return(&mut store)?;
+
+        let values = spec.results_to_values(&results)?;
+        assert_eq!(serde_json::Value::try_from(&values[0]).unwrap(), serde_json::json!(5));
+        Ok(())
+    }
+}
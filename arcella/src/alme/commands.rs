@@ -18,13 +18,19 @@
 //! for every valid incoming request.
 
 use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use arcella_types::alme::proto::AlmeResponse;
+use arcella_types::manifest::ComponentManifest;
+use arcella_wasmtime::executor::WasmKind;
 
+use crate::config;
 use crate::log;
-use crate::runtime::ArcellaRuntime;
+use crate::manifest::DeploymentSpec;
+use crate::reconciler::{self, DeploymentChange};
+use crate::runtime::{install_module, prepare_module, ArcellaRuntime, Deployment, DeploymentKey, Instance, InstalledModule, InstanceState};
 
 /// Dispatches an ALME command to the appropriate handler function.
 ///
@@ -52,7 +58,25 @@ pub async fn dispatch_command(
         "ping" => handle_ping(),
         "status" => handle_status(runtime).await,
         "log:tail" => handle_log_tail(args).await,
+        "module:install" => handle_module_install(args, runtime).await,
         "module:list" => handle_module_list(runtime).await,
+        "module:info" => handle_module_info(args, runtime).await,
+        "module:remove" => handle_module_remove(args, runtime).await,
+        "deployment:apply" => handle_deployment_apply(args, runtime).await,
+        "deployment:list" => handle_deployment_list(runtime).await,
+        "deployment:scale" => handle_deployment_scale(args, runtime).await,
+        "deployment:undeploy" => handle_deployment_undeploy(args, runtime).await,
+        "deployment:stop" => handle_deployment_stop(args, runtime).await,
+        "deployment:start" => handle_deployment_start(args, runtime).await,
+        "deployment:diff" => handle_deployment_diff(runtime).await,
+        "instance:list" => handle_instance_list(args, runtime).await,
+        "instance:info" => handle_instance_info(args, runtime).await,
+        "cache:stats" => handle_cache_stats(runtime).await,
+        "cache:clear" => handle_cache_clear(runtime).await,
+        "storage:gc" => handle_storage_gc(runtime).await,
+        "config:explain" => handle_config_explain(args, runtime).await,
+        "config:integrity" => handle_config_integrity(runtime).await,
+        "config:schema" => handle_config_schema(args),
         // ... other command
         _ => AlmeResponse::error(&format!("Unknown command: {}", cmd)),
     }
@@ -87,6 +111,10 @@ fn handle_ping() -> AlmeResponse {
 /// - `start_time`: RFC3339-formatted startup timestamp
 /// - `uptime`: runtime duration in seconds
 /// - `socket_path`: filesystem path of the ALME Unix socket
+/// - `worker_groups`: `arcella-worker` processes with their PID, health and instance count
+/// - `modules`: number of installed modules
+/// - `deployments`: number of registered deployments
+/// - `instances`: number of tracked instances
 ///
 /// Returns an error response if the runtime status cannot be retrieved
 /// (e.g., due to a poisoned lock).
@@ -108,14 +136,31 @@ async fn handle_status(
     let start_time_rfc3339 = runtime_status.start_time.format(&time::format_description::well_known::Rfc3339)
         .unwrap_or_else(|_| "<invalid-timestamp>".to_string());
 
+    let worker_groups: Vec<Value> = runtime_guard.workers
+        .status()
+        .iter()
+        .map(|w| serde_json::json!({
+            "group": w.group,
+            "pid": w.pid,
+            "started_at": w.started_at.format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_else(|_| "<invalid-timestamp>".to_string()),
+            "uptime": w.uptime.as_secs(),
+            "alive": w.alive,
+            "healthy": w.healthy,
+            "instances": w.instances,
+        }))
+        .collect();
+
     let data = serde_json::json!({
         "version": env!("CARGO_PKG_VERSION"),
         "pid": runtime_status.pid,
         "start_time": format!("{}", start_time_rfc3339),
         "uptime": runtime_status.uptime.as_secs(),
         "socket_path": runtime_guard.config.socket_path.to_string_lossy(),
-        "worker_groups": "",
-        "modules": "",
+        "worker_groups": worker_groups,
+        "modules": runtime_guard.modules.len(),
+        "deployments": runtime_guard.deployments.len(),
+        "instances": runtime_guard.instances.len(),
     });
 
     AlmeResponse::success("Arcella runtime is active", Some(data))
@@ -130,7 +175,7 @@ async fn handle_status(
 /// # Arguments
 ///
 /// * `args` — Expected to contain an optional `"n"` field (unsigned integer)
-///            specifying the number of log lines to return. Defaults to 100.
+///   specifying the number of log lines to return. Defaults to 100.
 ///
 /// # Returns
 ///
@@ -152,26 +197,811 @@ async fn handle_log_tail(args: &Value) -> AlmeResponse {
     AlmeResponse::success("Log tail retrieved", Some(data))
 }
 
-/// Handles the `"module:list"` ALME command.
+/// Handles the `"module:install"` ALME command.
 ///
-/// Returns a list of all currently installed and/or active WebAssembly modules.
+/// Installs a WebAssembly module from a `.wasm` file on the daemon's filesystem.
+/// The file, together with the optional `component.toml` and `deployment-template.toml`
+/// located next to it, is copied into `modules_dir/<id>/` and registered in the runtime.
 ///
 /// # Arguments
 ///
-/// * `runtime` — Shared access to the runtime state (not yet used).
+/// * `args` — Must contain a `"path"` field (string) with the absolute path to the `.wasm` file.
+/// * `runtime` — Shared access to the runtime state.
 ///
 /// # Returns
 ///
-/// A successful [`AlmeResponse`] containing a JSON array of module descriptors.
-/// Currently returns an empty array as module management is not yet implemented.
+/// A successful [`AlmeResponse`] containing the descriptor of the installed module,
+/// or an error response if the path is missing, the bundle is invalid,
+/// or a module with the same ID is already installed.
+async fn handle_module_install(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let Some(path) = args.get("path").and_then(|v| v.as_str()) else {
+        return AlmeResponse::error("Missing required argument: path");
+    };
+    let wasm_path = PathBuf::from(path);
+
+    match install_module(runtime, &wasm_path).await {
+        Ok(module) => AlmeResponse::success(
+            &format!("Module {} installed", module.id()),
+            Some(module_descriptor(&module)),
+        ),
+        Err(e) => {
+            let message = format!("Failed to install module from {}: {}", path, e);
+            tracing::debug!("{}", message);
+            AlmeResponse::error(&message)
+        }
+    }
+}
+
+/// Handles the `"module:list"` ALME command.
+///
+/// Returns a list of all installed WebAssembly modules.
 ///
-/// # TODO
+/// # Arguments
 ///
-/// Implement actual module enumeration by querying the runtime's module registry.
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON array of module descriptors
+/// (`id`, `name`, `version`, `description`, `kind`, `wasm_path`, `installed_at`), sorted by ID.
 async fn handle_module_list(
-    _runtime: &Arc<RwLock<ArcellaRuntime>>,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
 ) -> AlmeResponse {
-    // TODO: реализовать
-    AlmeResponse::success("Module list", Some(serde_json::json!([])))
+    let runtime_guard = runtime.read().await;
+
+    let modules: Vec<Value> = runtime_guard
+        .list_modules()
+        .into_iter()
+        .map(module_descriptor)
+        .collect();
+
+    AlmeResponse::success("Module list", Some(Value::Array(modules)))
 }
 
+/// Handles the `"module:info"` ALME command.
+///
+/// Returns detailed information about a single installed module, including its
+/// exported and imported interfaces and the deployment template shipped with it.
+///
+/// # Arguments
+///
+/// * `args` — Must contain an `"id"` field (string) in `name@version` format.
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with the module details, or an error response
+/// if the ID is missing, malformed, or unknown.
+async fn handle_module_info(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let module_id = match module_id_arg(args) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let runtime_guard = runtime.read().await;
+    let module = match runtime_guard.get_module(module_id) {
+        Ok(module) => module,
+        Err(e) => return AlmeResponse::error(&e.to_string()),
+    };
+
+    let mut data = module_descriptor(module);
+    let mut exports: Vec<&String> = module.manifest.exports.keys().collect();
+    exports.sort();
+    let mut imports: Vec<&String> = module.manifest.imports.keys().collect();
+    imports.sort();
+
+    data["exports"] = serde_json::json!(exports);
+    data["imports"] = serde_json::json!(imports);
+    data["template"] = serde_json::to_value(&module.template).unwrap_or(Value::Null);
+
+    AlmeResponse::success(&format!("Module {}", module_id), Some(data))
+}
+
+/// Handles the `"module:remove"` ALME command.
+///
+/// Unregisters an installed module and deletes its files from `modules_dir`.
+///
+/// # Arguments
+///
+/// * `args` — Must contain an `"id"` field (string) in `name@version` format.
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with no data, or an error response if the ID is
+/// missing, malformed, unknown, or the module files cannot be removed.
+async fn handle_module_remove(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let module_id = match module_id_arg(args) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let mut runtime_guard = runtime.write().await;
+    match runtime_guard.remove_module(module_id).await {
+        Ok(_) => AlmeResponse::success(&format!("Module {} removed", module_id), None),
+        Err(e) => {
+            let message = format!("Failed to remove module {}: {}", module_id, e);
+            tracing::debug!("{}", message);
+            AlmeResponse::error(&message)
+        }
+    }
+}
+
+/// Handles the `"deployment:apply"` ALME command.
+///
+/// Creates a deployment of an installed module, or replaces an existing one with
+/// the same `(module_id, group)` key. The specification is merged with the module's
+/// deployment template and validated (e.g. `main` isolation allows only 1 replica).
+///
+/// # Arguments
+///
+/// * `args` — Either a `"path"` field (string) with the absolute path to a
+///   deployment TOML file (`[deployment]` table), or a `"spec"` field with the
+///   deployment specification as a JSON object.
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing the resolved deployment, or an error
+/// response if the spec is missing or invalid, or the module is not installed.
+async fn handle_deployment_apply(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let spec = if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
+        match DeploymentSpec::from_file(&PathBuf::from(path)) {
+            Ok(spec) => spec,
+            Err(e) => return AlmeResponse::error(&format!("Invalid deployment file {}: {}", path, e)),
+        }
+    } else if let Some(spec) = args.get("spec") {
+        match serde_json::from_value::<DeploymentSpec>(spec.clone()) {
+            Ok(spec) => spec,
+            Err(e) => return AlmeResponse::error(&format!("Invalid deployment spec: {}", e)),
+        }
+    } else {
+        return AlmeResponse::error("Missing required argument: path or spec");
+    };
+
+    prepare_module(runtime, &spec.module_id).await;
+    let mut runtime_guard = runtime.write().await;
+    match runtime_guard.apply_deployment(spec) {
+        Ok(deployment) => AlmeResponse::success(
+            &format!("Deployment {} applied", deployment.key()),
+            Some(deployment_descriptor(deployment)),
+        ),
+        Err(e) => {
+            let message = format!("Failed to apply deployment: {}", e);
+            tracing::debug!("{}", message);
+            AlmeResponse::error(&message)
+        }
+    }
+}
+
+/// Handles the `"deployment:list"` ALME command.
+///
+/// # Arguments
+///
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON array of deployment descriptors,
+/// sorted by module ID and group.
+async fn handle_deployment_list(
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let runtime_guard = runtime.read().await;
+
+    let deployments: Vec<Value> = runtime_guard
+        .list_deployments()
+        .into_iter()
+        .map(deployment_descriptor)
+        .collect();
+
+    AlmeResponse::success("Deployment list", Some(Value::Array(deployments)))
+}
+
+/// Handles the `"deployment:scale"` ALME command.
+///
+/// Changes the replica count of an existing deployment. The same validation
+/// rules as for `deployment:apply` are enforced.
+///
+/// # Arguments
+///
+/// * `args` — Must contain `"module_id"` and `"group"` (strings) identifying the
+///   deployment, and `"replicas"` (unsigned integer).
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing the updated deployment, or an error
+/// response if the deployment does not exist or the new replica count is invalid.
+async fn handle_deployment_scale(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let key = match deployment_key_arg(args) {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+    let Some(replicas) = args.get("replicas").and_then(|v| v.as_u64()) else {
+        return AlmeResponse::error("Missing required argument: replicas");
+    };
+    let Ok(replicas) = u32::try_from(replicas) else {
+        return AlmeResponse::error(&format!("Invalid replica count: {}", replicas));
+    };
+
+    prepare_module(runtime, &key.module_id).await;
+    let mut runtime_guard = runtime.write().await;
+    match runtime_guard.scale_deployment(&key, replicas) {
+        Ok(deployment) => AlmeResponse::success(
+            &format!("Deployment {} scaled to {} replicas", key, replicas),
+            Some(deployment_descriptor(deployment)),
+        ),
+        Err(e) => {
+            let message = format!("Failed to scale deployment {}: {}", key, e);
+            tracing::debug!("{}", message);
+            AlmeResponse::error(&message)
+        }
+    }
+}
+
+/// Handles the `"deployment:undeploy"` ALME command.
+///
+/// Removes a deployment from the runtime.
+///
+/// # Arguments
+///
+/// * `args` — Must contain `"module_id"` and `"group"` (strings).
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with no data, or an error response if the
+/// deployment does not exist.
+async fn handle_deployment_undeploy(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let key = match deployment_key_arg(args) {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+
+    let mut runtime_guard = runtime.write().await;
+    match runtime_guard.undeploy(&key) {
+        Ok(_) => AlmeResponse::success(&format!("Deployment {} removed", key), None),
+        Err(e) => AlmeResponse::error(&e.to_string()),
+    }
+}
+
+/// Handles the `"deployment:stop"` ALME command.
+///
+/// Stops all instances of a deployment. The deployment stays registered and
+/// remains stopped across daemon restarts until `deployment:start`.
+///
+/// # Arguments
+///
+/// * `args` — Must contain `"module_id"` and `"group"` (strings).
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with the deployment descriptor, or an error
+/// response if the deployment does not exist.
+async fn handle_deployment_stop(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let key = match deployment_key_arg(args) {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+
+    let mut runtime_guard = runtime.write().await;
+    match runtime_guard.stop_deployment(&key) {
+        Ok(deployment) => AlmeResponse::success(
+            &format!("Deployment {} stopped", key),
+            Some(deployment_descriptor(deployment)),
+        ),
+        Err(e) => AlmeResponse::error(&e.to_string()),
+    }
+}
+
+/// Handles the `"deployment:start"` ALME command.
+///
+/// Starts the instances of a deployment stopped with `deployment:stop`.
+///
+/// # Arguments
+///
+/// * `args` — Must contain `"module_id"` and `"group"` (strings).
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with the deployment descriptor, or an error
+/// response if the deployment does not exist.
+async fn handle_deployment_start(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let key = match deployment_key_arg(args) {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
+
+    prepare_module(runtime, &key.module_id).await;
+    let mut runtime_guard = runtime.write().await;
+    match runtime_guard.start_deployment(&key) {
+        Ok(deployment) => AlmeResponse::success(
+            &format!("Deployment {} started", key),
+            Some(deployment_descriptor(deployment)),
+        ),
+        Err(e) => AlmeResponse::error(&e.to_string()),
+    }
+}
+
+/// Handles the `"deployment:diff"` ALME command.
+///
+/// Dry run of the deployment reconciler: compares the `*.deployment.toml` files
+/// of the deployments directory with the current deployments and reports what
+/// the next reconciliation pass would change, without applying anything.
+///
+/// # Arguments
+///
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON object with fields:
+/// - `dir`: the deployments directory
+/// - `changes`: list of `{action, deployment, source, replicas}`, where `action` is
+///   one of `create`, `update`, `scale`, `adopt` or `undeploy`
+/// - `errors`: files that cannot be used, as `{file, error}`
+///
+/// Returns an error response if the directory cannot be read.
+async fn handle_deployment_diff(
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let runtime_guard = runtime.read().await;
+    let dir = &runtime_guard.config.deployments_dir;
+
+    let desired = match reconciler::load_desired(dir) {
+        Ok(desired) => desired,
+        Err(e) => return AlmeResponse::error(&e.to_string()),
+    };
+
+    let changes: Vec<Value> = reconciler::diff(&runtime_guard, &desired)
+        .iter()
+        .map(|change| {
+            let (source, replicas) = match change {
+                DeploymentChange::Create { source, spec }
+                | DeploymentChange::Update { source, spec }
+                | DeploymentChange::Adopt { source, spec } => {
+                    (Some(source), serde_json::json!(spec.replicas))
+                }
+                DeploymentChange::Scale { source, spec, from } => {
+                    (Some(source), serde_json::json!({ "from": from, "to": spec.replicas }))
+                }
+                DeploymentChange::Undeploy { .. } => (None, Value::Null),
+            };
+            serde_json::json!({
+                "action": change.action(),
+                "deployment": change.key().to_string(),
+                "source": source.map(|s| s.to_string_lossy()),
+                "replicas": replicas,
+            })
+        })
+        .collect();
+
+    let errors: Vec<Value> = desired.errors
+        .iter()
+        .map(|(file, error)| serde_json::json!({ "file": file.to_string_lossy(), "error": error }))
+        .collect();
+
+    let data = serde_json::json!({
+        "dir": dir.to_string_lossy(),
+        "changes": changes,
+        "errors": errors,
+    });
+
+    AlmeResponse::success(&format!("{} pending change(s)", changes.len()), Some(data))
+}
+
+/// Handles the `"instance:list"` ALME command.
+///
+/// Returns the instances (replicas) of all deployments with their lifecycle state:
+/// `starting`, `running`, `exited` (with `exit_code`) or `failed` (with `reason`).
+///
+/// # Arguments
+///
+/// * `args` — Optional `"module_id"` and `"group"` fields (strings) to filter by deployment.
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON array of instance descriptors.
+async fn handle_instance_list(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let module_id = args.get("module_id").and_then(|v| v.as_str());
+    let group = args.get("group").and_then(|v| v.as_str());
+
+    let runtime_guard = runtime.read().await;
+
+    let instances: Vec<Value> = runtime_guard
+        .list_instances()
+        .into_iter()
+        .filter(|i| module_id.is_none_or(|m| i.deployment.module_id == m))
+        .filter(|i| group.is_none_or(|g| i.deployment.group == g))
+        .map(instance_descriptor)
+        .collect();
+
+    AlmeResponse::success("Instance list", Some(Value::Array(instances)))
+}
+
+/// Handles the `"instance:info"` ALME command.
+///
+/// Returns the state of a single instance together with the resource limits
+/// enforced on it: `memory_bytes` (from `memory_mb` and the module's
+/// `memory_max`), `fuel`, `startup_timeout_seconds`, `call_timeout_seconds`,
+/// and the requested `cpu_shares` (informational).
+///
+/// # Arguments
+///
+/// * `args` — Must contain an `"id"` field (string): `<module_id>/<group>/<replica>`.
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with the instance details, or an error response
+/// if the ID is missing or unknown.
+async fn handle_instance_info(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let Some(instance_id) = args.get("id").and_then(|v| v.as_str()) else {
+        return AlmeResponse::error("Missing required argument: id");
+    };
+
+    let runtime_guard = runtime.read().await;
+    let instance = match runtime_guard.get_instance(instance_id) {
+        Ok(instance) => instance,
+        Err(e) => return AlmeResponse::error(&e.to_string()),
+    };
+    let limits = match runtime_guard.effective_limits(&instance.deployment) {
+        Ok(limits) => limits,
+        Err(e) => return AlmeResponse::error(&e.to_string()),
+    };
+    let resolved = match runtime_guard.get_deployment(&instance.deployment) {
+        Ok(deployment) => &deployment.resolved,
+        Err(e) => return AlmeResponse::error(&e.to_string()),
+    };
+
+    let mut data = instance_descriptor(instance);
+    data["limits"] = serde_json::json!({
+        "memory_bytes": limits.memory_bytes,
+        "fuel": limits.fuel,
+        "startup_timeout_seconds": resolved.startup.timeout().map(|t| t.as_secs()),
+        "call_timeout_seconds": resolved.resources.call_timeout().map(|t| t.as_secs()),
+        "cpu_shares": resolved.resources.cpu_shares,
+    });
+
+    AlmeResponse::success(&format!("Instance {}", instance_id), Some(data))
+}
+
+/// Handles the `"cache:stats"` ALME command.
+///
+/// Reports the state of the precompiled module cache.
+///
+/// # Arguments
+///
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON object with fields:
+/// - `entries`: number of cached artifacts
+/// - `size_bytes`: total size of the cached artifacts
+/// - `max_size_bytes`: configured size limit (`cache.max_size_mb`)
+/// - `hits`, `misses`, `evictions`: counters since the daemon started
+async fn handle_cache_stats(
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let runtime_guard = runtime.read().await;
+    let stats = match runtime_guard.cache.stats() {
+        Ok(stats) => stats,
+        Err(e) => return AlmeResponse::error(&e.to_string()),
+    };
+
+    let data = serde_json::json!({
+        "entries": stats.entries,
+        "size_bytes": stats.size_bytes,
+        "max_size_bytes": stats.max_size_bytes,
+        "hits": stats.hits,
+        "misses": stats.misses,
+        "evictions": stats.evictions,
+    });
+
+    AlmeResponse::success("Module cache statistics", Some(data))
+}
+
+/// Handles the `"cache:clear"` ALME command.
+///
+/// Removes all precompiled artifacts. Modules are recompiled on their next start.
+///
+/// # Arguments
+///
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with the number of removed artifacts (`removed`),
+/// or an error response if the cache directory cannot be cleaned.
+async fn handle_cache_clear(
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let runtime_guard = runtime.read().await;
+    match runtime_guard.cache.clear() {
+        Ok(removed) => AlmeResponse::success(
+            &format!("Removed {} cached artifact(s)", removed),
+            Some(serde_json::json!({ "removed": removed })),
+        ),
+        Err(e) => AlmeResponse::error(&e.to_string()),
+    }
+}
+
+/// Handles the `"storage:gc"` ALME command.
+///
+/// Removes module blobs that are no longer referenced by any installed module
+/// (e.g. after `module:remove`).
+///
+/// # Arguments
+///
+/// * `runtime` — Shared access to the runtime state. The write lock is held for
+///   the duration of the scan, so that no module is installed concurrently.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] with the hashes of the removed blobs (`removed`)
+/// and the number of freed bytes (`freed_bytes`), or an error response if the
+/// blob store cannot be scanned.
+async fn handle_storage_gc(
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let runtime_guard = runtime.write().await;
+    match runtime_guard.storage.gc().await {
+        Ok(report) => AlmeResponse::success(
+            &format!("Removed {} unreferenced blob(s)", report.removed.len()),
+            Some(serde_json::json!({
+                "removed": report.removed,
+                "freed_bytes": report.freed_bytes,
+            })),
+        ),
+        Err(e) => AlmeResponse::error(&e.to_string()),
+    }
+}
+
+/// Handles the `"config:explain"` ALME command.
+///
+/// Reports the last loaded configuration: the loaded files in layer order, every
+/// final setting with the file and layer that set it, and the load warnings.
+/// Settings that require a restart may differ from the ones the daemon runs with.
+///
+/// # Arguments
+///
+/// * `args` — Optional `"prefix"`: only settings whose key starts with it are listed
+///   (e.g. `"arcella.log."`).
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON object with fields:
+/// - `files`: `{ layer, path }` of every loaded file (layer 0 is the built-in defaults)
+/// - `values`: `{ key, value, layer, file }` of every matching setting
+/// - `warnings`: warnings reported while loading the configuration
+async fn handle_config_explain(
+    args: &Value,
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let prefix = args.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
+    let runtime_guard = runtime.read().await;
+    let sources = &runtime_guard.config.sources;
+
+    let files: Vec<Value> = sources.files.iter().enumerate()
+        .map(|(layer, path)| serde_json::json!({ "layer": layer, "path": path }))
+        .collect();
+    let values: Vec<Value> = sources.explain(prefix).into_iter()
+        .map(|entry| serde_json::json!({
+            "key": entry.key,
+            "value": config::format_value(entry.value),
+            "layer": entry.layer,
+            "file": entry.file,
+        }))
+        .collect();
+    let warnings: Vec<String> = sources.warnings.iter().map(|w| w.to_string()).collect();
+
+    AlmeResponse::success(
+        &format!("{} setting(s) from {} file(s)", values.len(), files.len()),
+        Some(serde_json::json!({ "files": files, "values": values, "warnings": warnings })),
+    )
+}
+
+/// Handles the `"config:schema"` ALME command.
+///
+/// Reports the reference of all known configuration keys: the schema that
+/// loading validates the configuration against, with the built-in defaults.
+///
+/// # Arguments
+///
+/// * `args` — Optional `"prefix"`: only keys starting with it are listed
+///   (e.g. `"arcella.log."`).
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON object with a `keys` array of
+/// `{ key, type, allowed, default, on_invalid, description }` (`key` may contain
+/// `*` matching any single segment; `default` is `null` for keys without one),
+/// or an error response if the built-in defaults cannot be read.
+fn handle_config_schema(args: &Value) -> AlmeResponse {
+    let prefix = args.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
+    let reference = match config::schema::reference() {
+        Ok(reference) => reference,
+        Err(e) => return AlmeResponse::error(&format!("Failed to build the config schema: {}", e)),
+    };
+
+    let keys: Vec<Value> = reference.iter()
+        .filter(|entry| entry.schema.key.starts_with(prefix))
+        .map(|entry| serde_json::json!({
+            "key": entry.schema.key,
+            "type": entry.schema.kind.to_string(),
+            "allowed": entry.schema.constraint.to_string(),
+            "default": entry.default.as_ref().map(config::format_value),
+            "on_invalid": entry.schema.on_invalid.to_string(),
+            "description": entry.schema.description,
+        }))
+        .collect();
+
+    AlmeResponse::success(
+        &format!("{} configuration key(s)", keys.len()),
+        Some(serde_json::json!({ "keys": keys })),
+    )
+}
+
+/// Handles the `"config:integrity"` ALME command.
+///
+/// Checks right away that the loaded configuration files were not modified, and
+/// reacts to a violation as configured by `integrity.action` (see
+/// [`ArcellaRuntime::check_integrity`]).
+///
+/// # Arguments
+///
+/// * `runtime` — Shared access to the runtime state.
+///
+/// # Returns
+///
+/// A successful [`AlmeResponse`] containing a JSON object with fields:
+/// - `intact`: `true` if no file was modified
+/// - `violation`: description of the violation, or `null`
+/// - `action`: the configured reaction (`warn`, `refuse_deployments` or `shutdown`)
+/// - `files`: the monitored files
+async fn handle_config_integrity(
+    runtime: &Arc<RwLock<ArcellaRuntime>>,
+) -> AlmeResponse {
+    let mut runtime_guard = runtime.write().await;
+    let violation = runtime_guard.check_integrity().await;
+
+    let message = match &violation {
+        Some(violation) => violation.clone(),
+        None => format!("{} configuration file(s) unchanged", runtime_guard.integrity_paths().len()),
+    };
+    let data = serde_json::json!({
+        "intact": violation.is_none(),
+        "violation": violation,
+        "action": runtime_guard.config.integrity_action.to_string(),
+        "files": runtime_guard.integrity_paths(),
+    });
+
+    AlmeResponse::success(&message, Some(data))
+}
+
+/// Builds the JSON descriptor of an instance used by `instance:*` responses.
+fn instance_descriptor(instance: &Instance) -> Value {
+    let status = instance.status();
+    let format_time = |t: &time::OffsetDateTime| {
+        t.format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_else(|_| "<invalid-timestamp>".to_string())
+    };
+
+    let mut data = serde_json::json!({
+        "id": instance.id,
+        "module_id": instance.deployment.module_id,
+        "group": instance.deployment.group,
+        "replica": instance.replica,
+        "restarts": instance.restarts,
+        "state": status.state.to_string(),
+        "started_at": format_time(&status.started_at),
+        "finished_at": status.finished_at.as_ref().map(format_time),
+    });
+
+    match &status.state {
+        InstanceState::Exited { code } => data["exit_code"] = serde_json::json!(code),
+        InstanceState::Failed { reason } => data["reason"] = serde_json::json!(reason),
+        _ => {}
+    }
+
+    data
+}
+
+/// Extracts the `"module_id"` and `"group"` arguments of deployment commands.
+fn deployment_key_arg(args: &Value) -> Result<DeploymentKey, AlmeResponse> {
+    let Some(module_id) = args.get("module_id").and_then(|v| v.as_str()) else {
+        return Err(AlmeResponse::error("Missing required argument: module_id"));
+    };
+    let Some(group) = args.get("group").and_then(|v| v.as_str()) else {
+        return Err(AlmeResponse::error("Missing required argument: group"));
+    };
+    Ok(DeploymentKey::new(module_id, group))
+}
+
+/// Builds the JSON descriptor of a deployment used by `deployment:*` responses.
+fn deployment_descriptor(deployment: &Deployment) -> Value {
+    let format_time = |t: &time::OffsetDateTime| {
+        t.format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_else(|_| "<invalid-timestamp>".to_string())
+    };
+
+    serde_json::json!({
+        "module_id": deployment.spec.module_id,
+        "group": deployment.spec.group,
+        "replicas": deployment.resolved.replicas,
+        "stopped": deployment.stopped,
+        "managed": deployment.managed,
+        "deployment": deployment.resolved,
+        "created_at": format_time(&deployment.created_at),
+        "updated_at": format_time(&deployment.updated_at),
+    })
+}
+
+/// Extracts and validates the `"id"` argument of module commands.
+fn module_id_arg(args: &Value) -> Result<&str, AlmeResponse> {
+    let Some(module_id) = args.get("id").and_then(|v| v.as_str()) else {
+        return Err(AlmeResponse::error("Missing required argument: id"));
+    };
+    if !ComponentManifest::validate_module_id(module_id) {
+        return Err(AlmeResponse::error(&format!(
+            "Invalid module ID '{}': expected 'name@version'", module_id
+        )));
+    }
+    Ok(module_id)
+}
+
+/// Builds the JSON descriptor of an installed module used by `module:*` responses.
+fn module_descriptor(module: &InstalledModule) -> Value {
+    let installed_at = module.installed_at.format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_else(|_| "<invalid-timestamp>".to_string());
+
+    serde_json::json!({
+        "id": module.id(),
+        "name": module.manifest.name,
+        "version": module.manifest.version,
+        "description": module.manifest.description,
+        "kind": match module.kind {
+            WasmKind::CoreModule => "core",
+            WasmKind::Component => "component",
+        },
+        "wasm_path": module.wasm_path.to_string_lossy(),
+        "blob": module.blob,
+        "installed_at": installed_at,
+    })
+}
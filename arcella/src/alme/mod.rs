@@ -13,6 +13,7 @@ use tokio::task::JoinHandle;
 
 use crate::runtime::ArcellaRuntime;
 use crate::error::Result as ArcellaResult;
+use crate::lock::InstanceLock;
 
 mod commands;
 mod server;
@@ -47,9 +48,12 @@ impl Drop for AlmeServerHandle {
 
 /// Starts the ALME (Arcella Local Management Extensions) server in the background,
 /// providing IPC access to the shared runtime instance.
-pub async fn start(runtime: Arc<RwLock<ArcellaRuntime>>) -> ArcellaResult<AlmeServerHandle>  {
+///
+/// The instance lock proves that no other daemon of this `base_dir` is alive, which
+/// allows the server to replace a stale socket.
+pub async fn start(runtime: Arc<RwLock<ArcellaRuntime>>, lock: &InstanceLock) -> ArcellaResult<AlmeServerHandle>  {
     let socket_path = runtime.read().await.config.socket_path.clone();
 
-    server::spawn_server(socket_path, runtime).await    
+    server::spawn_server(socket_path, runtime, lock).await    
 
 }
\ No newline at end of file
@@ -19,8 +19,9 @@
 //! - Dispatches commands to handlers in [`crate::alme::commands`]
 //! - Returns structured JSON responses
 //! - Supports graceful shutdown via a broadcast channel
-//! - Enforces security limits (max request size, read timeout)
-//! - Automatically cleans up stale socket files on startup
+//! - Enforces security limits (`alme.max_request_length`, `alme.read_timeout_seconds`)
+//! - Cleans up stale socket files on startup, once the instance lock proves
+//!   that their owner is gone
 //!
 //! The protocol is synchronous and connection-scoped: each client may send
 //! multiple commands over a single connection, and the server responds to each
@@ -40,12 +41,16 @@ use arcella_types::alme::proto::{AlmeRequest, AlmeResponse};
 
 use crate::runtime::ArcellaRuntime;
 use crate::error::{ArcellaError, Result as ArcellaResult};
+use crate::lock::InstanceLock;
 
-/// Maximum allowed length of an incoming ALME request in bytes.
+/// Maximum allowed length of an incoming ALME request in bytes, used if
+/// `alme.max_request_length` is invalid.
 /// Requests exceeding this limit will be rejected to prevent resource exhaustion.
-static MAX_REQUEST_LENGTH: usize = 64 * 1024; // 64 KB
+static MAX_REQUEST_LENGTH: u64 = 64 * 1024; // 64 KB
 
-static MAX_READER_TIMEOUT: u64 = 60; // seconds
+/// Idle time after which a connection is closed, used if `alme.read_timeout_seconds`
+/// is invalid.
+static MAX_READER_TIMEOUT: TokioDuration = TokioDuration::from_secs(60);
 
 /// Spawns the ALME (Arcella Local Management Extensions) server as a background task.
 ///
@@ -53,7 +58,10 @@ static MAX_READER_TIMEOUT: u64 = 60; // seconds
 /// incoming management commands (e.g., `install`, `start`, `status`) by delegating them
 /// to the provided shared `ArcellaRuntime` instance.
 ///
-/// On startup, any existing file at `socket_path` is removed to handle stale sockets.
+/// On startup, an existing file at `socket_path` is removed as a stale socket: the
+/// caller holds the [`InstanceLock`], so the daemon that created it is no longer
+/// running. A socket that still accepts connections belongs to a daemon of another
+/// `base_dir` and is never removed.
 /// The socket file is created with permissions `0o600` (read/write for owner only) for security.
 ///
 /// A graceful shutdown can be initiated by calling [crate::alme::AlmeServerHandle::shutdown],
@@ -64,6 +72,7 @@ static MAX_READER_TIMEOUT: u64 = 60; // seconds
 ///
 /// * `socket_path` - The filesystem path where the Unix socket will be created.
 /// * `runtime` - A thread-safe shared reference to the main Arcella runtime instance.
+/// * `_lock` - The instance lock of the daemon.
 ///
 /// # Returns
 ///
@@ -72,14 +81,22 @@ static MAX_READER_TIMEOUT: u64 = 60; // seconds
 /// # Errors
 ///
 /// Returns an error if:
+/// - The socket is in use by another daemon.
 /// - The socket cannot be bound (e.g., due to permission issues).
 /// - The socket file permissions cannot be set
 pub async fn spawn_server(
     socket_path: PathBuf, 
     runtime: Arc<RwLock<ArcellaRuntime>>,
+    _lock: &InstanceLock,
 ) -> ArcellaResult<super::AlmeServerHandle> {
 
     if socket_path.exists() {
+        if UnixStream::connect(&socket_path).await.is_ok() {
+            return Err(ArcellaError::Internal(format!(
+                "ALME socket {:?} is in use by another daemon", socket_path
+            )));
+        }
+        tracing::info!("Removing stale ALME socket {:?}", socket_path);
         if let Err(e) = fs::remove_file(&socket_path) {
             tracing::error!("Failed to remove stale socket {:?}: {}", socket_path, e);
         }
@@ -191,6 +208,20 @@ async fn handle_connection(
     mut shutdown_rx: broadcast::Receiver<()>,
 ) -> ArcellaResult<()> {
 
+    // Read per connection, so that a reloaded configuration applies to new connections
+    let (max_request_length, read_timeout) = {
+        let config = runtime.read().await.config.clone();
+        let max_request_length = config.get_u64("arcella.alme.max_request_length").unwrap_or_else(|e| {
+            tracing::warn!("{}, using {}", e, MAX_REQUEST_LENGTH);
+            MAX_REQUEST_LENGTH
+        });
+        let read_timeout = config.get_duration("arcella.alme.read_timeout_seconds").unwrap_or_else(|e| {
+            tracing::warn!("{}, using {:?}", e, MAX_READER_TIMEOUT);
+            MAX_READER_TIMEOUT
+        });
+        (max_request_length, read_timeout)
+    };
+
     let (reader, mut writer) = tokio::io::split(stream);
     let mut reader = BufReader::new(reader);
     let mut buffer = String::new();
@@ -199,14 +230,14 @@ async fn handle_connection(
         buffer.clear();
 
         let line = tokio::select! {
-            reader_result = timeout(TokioDuration::from_secs(MAX_READER_TIMEOUT), reader.read_line(&mut buffer)) => {
+            reader_result = timeout(read_timeout, reader.read_line(&mut buffer)) => {
                 match reader_result {
                     Ok(Ok(0)) => {
                         tracing::trace!("Get EOF from client");
                         break Ok(()); // EOF - client close connection
                     },
                     Ok(Ok(n)) => {
-                        if n > MAX_REQUEST_LENGTH {
+                        if n as u64 > max_request_length {
                             let message = format!("Request too large");
                             let resp = AlmeResponse::error(&message);
                             tracing::warn!("{}", message);
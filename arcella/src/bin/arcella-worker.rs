@@ -0,0 +1,264 @@
+// arcella/arcella/src/bin/arcella-worker.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `arcella-worker`: a process that runs the isolated instances of one worker group.
+//!
+//! The worker is spawned by the daemon's `WorkerManager`. It reads
+//! [`WorkerRequest`]s from stdin and reports [`WorkerEvent`]s on stdout, one JSON
+//! message per line. Guest stdout is redirected to stderr, so that it cannot
+//! corrupt the IPC channel. The worker exits on [`WorkerRequest::Shutdown`] or
+//! when its stdin is closed (i.e. the daemon went away).
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+use tracing_subscriber::EnvFilter;
+use wasmtime::Engine;
+
+use arcella_types::worker::{WorkerEvent, WorkerInstanceState, WorkerRequest};
+use arcella_wasmtime::{
+    engine::{create_engine, EpochTicker},
+    executor::{run_executable, Executable, InstanceOptions, ResourceLimits},
+};
+
+/// Arcella worker process (started by the Arcella daemon)
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    /// Name of the worker group served by this process
+    #[arg(long)]
+    group: String,
+
+    /// Log filter for the worker's stderr output
+    #[arg(long, default_value = "info")]
+    log_level: String,
+}
+
+/// State of the worker process.
+struct Worker {
+    engine: Engine,
+    modules: HashMap<String, Executable>,
+    instances: HashMap<String, InstanceHandle>,
+    events: mpsc::UnboundedSender<WorkerEvent>,
+}
+
+/// Signals of a started instance.
+struct InstanceHandle {
+    /// Cancels the instance; dropping the sender has the same effect.
+    stop: oneshot::Sender<()>,
+    /// Asks the instance to shut down gracefully (taken on first use).
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl Worker {
+    /// Handles a single request. Returns `false` when the worker should exit.
+    fn handle(&mut self, request: WorkerRequest) -> bool {
+        match request {
+            WorkerRequest::Install { module_id, wasm_path, blob, artifact_path } => {
+                match self.load(&wasm_path, &blob, artifact_path.as_deref()) {
+                    Ok(executable) => {
+                        tracing::info!("Installed module {}", module_id);
+                        self.modules.insert(module_id.clone(), executable);
+                        self.send(WorkerEvent::Installed { module_id });
+                    }
+                    Err(e) => self.send(WorkerEvent::Error {
+                        message: format!("Failed to install {}: {}", module_id, e),
+                    }),
+                }
+            }
+            WorkerRequest::Start {
+                instance_id, module_id, entrypoint, args, r#async,
+                memory_limit_bytes, fuel, startup_timeout_ms, call_timeout_ms,
+                shutdown, shutdown_timeout_ms,
+            } => {
+                let Some(executable) = self.modules.get(&module_id).cloned() else {
+                    self.send(WorkerEvent::Instance {
+                        instance_id,
+                        state: WorkerInstanceState::Failed {
+                            reason: format!("Module {} is not installed in the worker", module_id),
+                        },
+                    });
+                    return true;
+                };
+
+                let options = InstanceOptions {
+                    entrypoint,
+                    args,
+                    stdout_to_stderr: true,
+                    limits: ResourceLimits { memory_bytes: memory_limit_bytes, fuel },
+                    startup_timeout: startup_timeout_ms.map(Duration::from_millis),
+                    call_timeout: call_timeout_ms.map(Duration::from_millis),
+                    shutdown,
+                    shutdown_timeout: shutdown_timeout_ms.map(Duration::from_millis),
+                    ..Default::default()
+                };
+                let handle = self.spawn_instance(instance_id.clone(), executable, options, r#async);
+                self.instances.insert(instance_id, handle);
+            }
+            WorkerRequest::Stop { instance_id, graceful: true } => {
+                if let Some(shutdown_tx) = self.instances.get_mut(&instance_id).and_then(|h| h.shutdown.take()) {
+                    let _ = shutdown_tx.send(());
+                    tracing::info!("Shutting down instance {}", instance_id);
+                }
+            }
+            WorkerRequest::Stop { instance_id, graceful: false } => {
+                if let Some(handle) = self.instances.remove(&instance_id) {
+                    let _ = handle.stop.send(());
+                    tracing::info!("Stopped instance {}", instance_id);
+                }
+            }
+            WorkerRequest::Ping => self.send(WorkerEvent::Pong),
+            WorkerRequest::Shutdown => return false,
+        }
+        true
+    }
+
+    /// Loads a module from the daemon's precompiled artifact if possible, and
+    /// compiles the `.wasm` file otherwise, provided that its SHA-256 is `blob`.
+    fn load(&self, wasm_path: &Path, blob: &str, artifact_path: Option<&Path>) -> anyhow::Result<Executable> {
+        if let Some(artifact_path) = artifact_path {
+            // SAFETY: the artifact comes from the daemon's cache directory and was
+            // produced by `Executable::precompile` with the same engine configuration.
+            match unsafe { Executable::deserialize_file(&self.engine, artifact_path) } {
+                Ok(executable) => return Ok(executable),
+                Err(e) => tracing::warn!("Cannot load artifact {:?}, compiling: {}", artifact_path, e),
+            }
+        }
+
+        let bytes = std::fs::read(wasm_path)
+            .with_context(|| format!("Cannot read {:?}", wasm_path))?;
+        let actual = hex::encode(Sha256::digest(&bytes));
+        if actual != blob {
+            bail!("{:?} is corrupted: blob hash is {}, expected {}", wasm_path, actual, blob);
+        }
+
+        Ok(Executable::from_bytes(&self.engine, &bytes)?)
+    }
+
+    /// Runs an instance in its own task and reports its state changes.
+    fn spawn_instance(
+        &self,
+        instance_id: String,
+        executable: Executable,
+        options: InstanceOptions,
+        is_async: bool,
+    ) -> InstanceHandle {
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let engine = self.engine.clone();
+        let events = self.events.clone();
+
+        let run = async move {
+            let on_running = || {
+                let _ = events.send(WorkerEvent::Instance {
+                    instance_id: instance_id.clone(),
+                    state: WorkerInstanceState::Running,
+                });
+            };
+
+            tokio::select! {
+                result = run_executable(&engine, &executable, &options, on_running, shutdown_signal(shutdown_rx)) => {
+                    let state = match result {
+                        Ok(code) => WorkerInstanceState::Exited { code },
+                        Err(e) => WorkerInstanceState::Failed { reason: e.to_string() },
+                    };
+                    let _ = events.send(WorkerEvent::Instance { instance_id, state });
+                }
+                _ = stop_rx => {}
+            }
+        };
+
+        if is_async {
+            tokio::spawn(run);
+        } else {
+            let handle = tokio::runtime::Handle::current();
+            tokio::task::spawn_blocking(move || handle.block_on(run));
+        }
+
+        InstanceHandle { stop: stop_tx, shutdown: Some(shutdown_tx) }
+    }
+
+    fn send(&self, event: WorkerEvent) {
+        let _ = self.events.send(event);
+    }
+}
+
+/// Resolves when a graceful shutdown is requested; never resolves if the
+/// sender is dropped without sending.
+async fn shutdown_signal(shutdown_rx: oneshot::Receiver<()>) {
+    if shutdown_rx.await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(EnvFilter::new(&cli.log_level))
+        .init();
+
+    let (events, mut events_rx) = mpsc::unbounded_channel::<WorkerEvent>();
+
+    // All stdout writes go through a single task to keep messages line-atomic.
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(event) = events_rx.recv().await {
+            let mut line = serde_json::to_vec(&event)?;
+            line.push(b'\n');
+            stdout.write_all(&line).await?;
+            stdout.flush().await?;
+        }
+        Ok::<_, anyhow::Error>(())
+    });
+
+    let engine = create_engine()?;
+    let _epoch_ticker = EpochTicker::start(&engine);
+
+    let mut worker = Worker {
+        engine,
+        modules: HashMap::new(),
+        instances: HashMap::new(),
+        events,
+    };
+    worker.send(WorkerEvent::Ready { pid: std::process::id() });
+    tracing::info!("Worker for group '{}' started", cli.group);
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let keep_running = match serde_json::from_str::<WorkerRequest>(&line) {
+            Ok(request) => worker.handle(request),
+            Err(e) => {
+                worker.send(WorkerEvent::Error { message: format!("Invalid request: {}", e) });
+                true
+            }
+        };
+        if !keep_running {
+            break;
+        }
+    }
+
+    tracing::info!("Worker for group '{}' shutting down", cli.group);
+
+    // Dropping the worker cancels all instances and closes the event channel.
+    drop(worker);
+    writer.await??;
+
+    Ok(())
+}
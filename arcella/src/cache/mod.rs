@@ -7,20 +7,408 @@
 // This file may not be copied, modified, or distributed
 // except according to those terms.
 
+//! Cache of precompiled WebAssembly artifacts.
+//!
+//! Compiling a module with Cranelift is by far the most expensive step of starting
+//! an instance. [`ModuleCache`] stores the native artifacts produced by
+//! [`Executable::precompile`] under `cache_dir`, so that subsequent starts (and
+//! daemon restarts) only have to map them with [`Executable::deserialize_file`].
+//!
+//! Artifacts are keyed by the SHA-256 of the `.wasm` content plus a fingerprint of
+//! the engine configuration, so that a changed module or an upgraded engine never
+//! picks up a stale artifact. Before use, the `.wasm` content is checked against
+//! the hash recorded when the module was installed. The total size of the cache
+//! is bounded: when it grows beyond `cache.max_size_mb`, the least recently used
+//! artifacts (by modification time, refreshed on every hit) are evicted.
+
+use sha2::{Digest, Sha256};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::SystemTime;
+use wasmtime::Engine;
+
+use arcella_wasmtime::executor::Executable;
 
 use crate::config::ArcellaConfig;
 use crate::error::{ArcellaError, Result as ArcellaResult};
 
+/// File extension of cached artifacts.
+const ARTIFACT_EXTENSION: &str = "cwasm";
+
+/// Point-in-time statistics of the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of artifacts on disk.
+    pub entries: usize,
+
+    /// Total size of the artifacts on disk, in bytes.
+    pub size_bytes: u64,
+
+    /// Configured size limit, in bytes.
+    pub max_size_bytes: u64,
+
+    /// Lookups served from the cache since the daemon started.
+    pub hits: u64,
+
+    /// Lookups that required compilation since the daemon started.
+    pub misses: u64,
+
+    /// Artifacts evicted to stay within the size limit since the daemon started.
+    pub evictions: u64,
+}
+
+/// A module loaded through the cache.
+#[derive(Clone)]
+pub struct LoadedModule {
+    /// The compiled module, for instances in the daemon.
+    pub executable: Executable,
+
+    /// The precompiled artifact, for worker processes; `None` if it was unusable.
+    pub artifact_path: Option<PathBuf>,
+}
+
 pub struct ModuleCache {
+    cache_dir: PathBuf,
+    max_size_bytes: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
 }
 
 impl ModuleCache {
     pub async fn new(
         config: &Arc<ArcellaConfig>,
     ) -> ArcellaResult<Self> {
+        tokio::fs::create_dir_all(&config.cache_dir)
+            .await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: config.cache_dir.clone() })?;
+
         Ok(Self {
+            cache_dir: config.cache_dir.clone(),
+            max_size_bytes: AtomicU64::new(config.cache_max_size),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         })
     }
 
-}
\ No newline at end of file
+    /// Returns the path of an up-to-date precompiled artifact for the verified
+    /// content `bytes` of `wasm_path`, whose SHA-256 is `content_hash`.
+    ///
+    /// On a cache miss the module is compiled with [`Executable::precompile`] and
+    /// the artifact is written atomically, after which the cache is trimmed to its
+    /// size limit. On a hit the artifact is marked as recently used.
+    fn artifact(
+        &self,
+        engine: &Engine,
+        wasm_path: &Path,
+        bytes: &[u8],
+        content_hash: &str,
+    ) -> ArcellaResult<PathBuf> {
+        let artifact_path = self.artifact_path(engine, content_hash);
+
+        if artifact_path.is_file() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            touch(&artifact_path);
+            tracing::debug!("Cache hit for {:?}", wasm_path);
+            return Ok(artifact_path);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        tracing::debug!("Cache miss for {:?}, precompiling", wasm_path);
+
+        let artifact = Executable::precompile(engine, bytes)?;
+        let tmp_path = artifact_path.with_extension("tmp");
+        std::fs::write(&tmp_path, &artifact)
+            .and_then(|_| std::fs::rename(&tmp_path, &artifact_path))
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: artifact_path.clone() })?;
+
+        self.evict(Some(&artifact_path));
+
+        Ok(artifact_path)
+    }
+
+    /// Loads a module for execution, going through the artifact cache.
+    ///
+    /// The `.wasm` file is checked against `blob` first. An artifact that cannot
+    /// be loaded (e.g. it is corrupted) is removed and the module is compiled
+    /// directly from the verified binary.
+    ///
+    /// This compiles and does file I/O; call it from a blocking context.
+    ///
+    /// # Arguments
+    ///
+    /// * `module_id` — ID of the module, for error reporting.
+    /// * `wasm_path` — Path to the module's blob.
+    /// * `blob` — SHA-256 of the binary (hex), as recorded in `module.json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaError::ModuleCorrupted`] if the content of `wasm_path` does
+    /// not match `blob`, or an error if the `.wasm` file cannot be read or compiled,
+    /// or if the artifact cannot be written.
+    pub fn load(
+        &self,
+        engine: &Engine,
+        module_id: &str,
+        wasm_path: &Path,
+        blob: &str,
+    ) -> ArcellaResult<LoadedModule> {
+        let bytes = read_verified(module_id, wasm_path, blob)?;
+        let artifact_path = self.artifact(engine, wasm_path, &bytes, blob)?;
+
+        // SAFETY: artifacts are only written by `artifact()` into the daemon-owned
+        // cache directory, and are keyed by the engine fingerprint.
+        match unsafe { Executable::deserialize_file(engine, &artifact_path) } {
+            Ok(executable) => Ok(LoadedModule { executable, artifact_path: Some(artifact_path) }),
+            Err(e) => {
+                tracing::warn!("Discarding unusable cache artifact {:?}: {}", artifact_path, e);
+                let _ = std::fs::remove_file(&artifact_path);
+                let executable = Executable::from_bytes(engine, &bytes)?;
+                Ok(LoadedModule { executable, artifact_path: None })
+            }
+        }
+    }
+
+    /// Returns the current cache statistics.
+    pub fn stats(&self) -> ArcellaResult<CacheStats> {
+        let entries = self.list_artifacts()?;
+
+        Ok(CacheStats {
+            entries: entries.len(),
+            size_bytes: entries.iter().map(|(_, size, _)| size).sum(),
+            max_size_bytes: self.max_size_bytes.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Removes all artifacts from the cache.
+    ///
+    /// # Returns
+    ///
+    /// The number of removed artifacts.
+    pub fn clear(&self) -> ArcellaResult<usize> {
+        let entries = self.list_artifacts()?;
+        for (path, _, _) in &entries {
+            std::fs::remove_file(path)
+                .map_err(|e| ArcellaError::IoWithPath { source: e, path: path.clone() })?;
+        }
+
+        tracing::info!("Cleared module cache ({} artifacts)", entries.len());
+        Ok(entries.len())
+    }
+
+    /// Changes the size limit of the cache (e.g. after a configuration reload),
+    /// evicting artifacts if the cache no longer fits.
+    pub fn set_max_size(&self, max_size_bytes: u64) {
+        let previous = self.max_size_bytes.swap(max_size_bytes, Ordering::Relaxed);
+        if max_size_bytes < previous {
+            self.evict(None);
+        }
+    }
+
+    /// Builds the artifact path for a module: `<sha256 of wasm>-<engine fingerprint>.cwasm`.
+    fn artifact_path(&self, engine: &Engine, content_hash: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        engine.precompile_compatibility_hash().hash(&mut hasher);
+        let fingerprint = hasher.finish();
+
+        self.cache_dir.join(format!("{}-{:016x}.{}", content_hash, fingerprint, ARTIFACT_EXTENSION))
+    }
+
+    /// Evicts the least recently used artifacts until the cache fits its size limit.
+    ///
+    /// `keep` is never evicted, so that a single artifact larger than the limit
+    /// can still be used.
+    fn evict(&self, keep: Option<&Path>) {
+        let mut entries = match self.list_artifacts() {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to scan module cache: {}", e);
+                return;
+            }
+        };
+
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+
+        for (path, size, _) in entries {
+            if total <= self.max_size_bytes.load(Ordering::Relaxed) {
+                break;
+            }
+            if Some(path.as_path()) == keep {
+                continue;
+            }
+
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    total -= size;
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                    tracing::debug!("Evicted {:?} from module cache", path);
+                }
+                Err(e) => tracing::warn!("Failed to evict {:?}: {}", path, e),
+            }
+        }
+    }
+
+    /// Lists the artifacts in `cache_dir` with their size and modification time.
+    fn list_artifacts(&self) -> ArcellaResult<Vec<(PathBuf, u64, SystemTime)>> {
+        let read_dir = std::fs::read_dir(&self.cache_dir)
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: self.cache_dir.clone() })?;
+
+        let mut artifacts = Vec::new();
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(ARTIFACT_EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_file() {
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                artifacts.push((path, metadata.len(), modified));
+            }
+        }
+
+        Ok(artifacts)
+    }
+}
+
+/// Reads a module's `.wasm` file and checks it against the hash recorded at
+/// installation, so that a blob modified after installation is never compiled.
+fn read_verified(module_id: &str, wasm_path: &Path, blob: &str) -> ArcellaResult<Vec<u8>> {
+    let bytes = std::fs::read(wasm_path)
+        .map_err(|e| ArcellaError::IoWithPath { source: e, path: wasm_path.to_path_buf() })?;
+
+    let actual = hex::encode(Sha256::digest(&bytes));
+    if actual != blob {
+        return Err(ArcellaError::ModuleCorrupted {
+            module_id: module_id.to_string(),
+            expected: blob.to_string(),
+            actual,
+        });
+    }
+
+    Ok(bytes)
+}
+
+/// Marks an artifact as recently used by updating its modification time.
+fn touch(path: &Path) {
+    let result = std::fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+
+    if let Err(e) = result {
+        tracing::debug!("Failed to update mtime of {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arcella_wasmtime::engine::create_engine;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    async fn create_cache(base_dir: &Path, max_size: u64) -> ModuleCache {
+        let mut config = ArcellaConfig::for_tests(base_dir);
+        config.cache_max_size = max_size;
+        ModuleCache::new(&Arc::new(config)).await.unwrap()
+    }
+
+    /// Writes a module and returns its path and hash.
+    fn write_module(dir: &Path, name: &str, export: &str) -> (PathBuf, String) {
+        let wasm = wat::parse_str(format!("(module (func (export \"{}\")))", export)).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, &wasm).unwrap();
+        (path, hex::encode(Sha256::digest(&wasm)))
+    }
+
+    /// Loads a module and returns the path of its artifact.
+    fn artifact(cache: &ModuleCache, engine: &Engine, module_id: &str, wasm_path: &Path, blob: &str) -> PathBuf {
+        cache.load(engine, module_id, wasm_path, blob).unwrap().artifact_path.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_and_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine().unwrap();
+        let cache = create_cache(temp_dir.path(), u64::MAX).await;
+        let (wasm_path, blob) = write_module(temp_dir.path(), "a.wasm", "a");
+
+        let loaded = cache.load(&engine, "a", &wasm_path, &blob).unwrap();
+        assert!(matches!(loaded.executable, Executable::Core(_)));
+        assert!(loaded.artifact_path.is_some());
+        cache.load(&engine, "a", &wasm_path, &blob).unwrap();
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 1, 1));
+        assert!(stats.size_bytes > 0);
+
+        // A corrupted artifact is discarded and the module still loads. The file is
+        // replaced rather than overwritten, since loaded modules keep it mapped.
+        let artifact = artifact(&cache, &engine, "a", &wasm_path, &blob);
+        std::fs::remove_file(&artifact).unwrap();
+        std::fs::write(&artifact, b"garbage").unwrap();
+        assert_eq!(cache.load(&engine, "a", &wasm_path, &blob).unwrap().artifact_path, None);
+        assert!(!artifact.exists());
+
+        cache.load(&engine, "a", &wasm_path, &blob).unwrap();
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_rejects_modified_blob() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine().unwrap();
+        let cache = create_cache(temp_dir.path(), u64::MAX).await;
+        let (wasm_path, blob) = write_module(temp_dir.path(), "a.wasm", "a");
+        cache.load(&engine, "a", &wasm_path, &blob).unwrap();
+
+        // A modified blob is rejected before the cached artifact is looked up.
+        write_module(temp_dir.path(), "a.wasm", "b");
+        let err = cache.load(&engine, "a", &wasm_path, &blob).err().unwrap();
+        assert!(matches!(err, ArcellaError::ModuleCorrupted { module_id, .. } if module_id == "a"));
+        assert_eq!(cache.stats().unwrap().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine().unwrap();
+        let probe = create_cache(&temp_dir.path().join("probe"), u64::MAX).await;
+        let (a, blob_a) = write_module(temp_dir.path(), "a.wasm", "a");
+        let (b, blob_b) = write_module(temp_dir.path(), "b.wasm", "b");
+        let (c, blob_c) = write_module(temp_dir.path(), "c.wasm", "c");
+
+        // Room for two artifacts, but not for three.
+        let size = std::fs::metadata(artifact(&probe, &engine, "a", &a, &blob_a)).unwrap().len();
+        let cache = create_cache(temp_dir.path(), size * 2 + size / 2).await;
+
+        let path_a = artifact(&cache, &engine, "a", &a, &blob_a);
+        std::thread::sleep(Duration::from_millis(20));
+        let path_b = artifact(&cache, &engine, "b", &b, &blob_b);
+        std::thread::sleep(Duration::from_millis(20));
+        cache.load(&engine, "a", &a, &blob_a).unwrap(); // `a` is now more recent than `b`
+        std::thread::sleep(Duration::from_millis(20));
+        let path_c = artifact(&cache, &engine, "c", &c, &blob_c);
+
+        assert!(path_a.exists());
+        assert!(!path_b.exists());
+        assert!(path_c.exists());
+        assert_eq!(cache.stats().unwrap().evictions, 1);
+
+        // Shrinking the limit evicts right away.
+        cache.set_max_size(size + size / 2);
+        assert!(!path_a.exists());
+        assert!(path_c.exists());
+        assert_eq!(cache.stats().unwrap().evictions, 2);
+    }
+}
@@ -8,16 +8,18 @@
 // except according to those terms.
 
 use futures::future;
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use indexmap::{map::Entry, IndexMap, IndexSet};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::fs;
+use tracing_subscriber::filter::LevelFilter;
 
 use arcella_types::{
     config::{
+        ConfigData,
         ConfigValues,
         Value as TomlValue
     }
@@ -26,6 +28,8 @@ use arcella_fs_utils as fs_utils;
 
 use crate::error::{ArcellaError, Result as ArcellaResult};
 
+pub mod schema;
+
 const REDEF_SUFFIX: &str = "#redef";
 const MAIN_CONFIG_FILENAME: &str = "arcella.toml";
 const DEFAULT_CONFIG_FILENAME: &str = "default_config.toml";
@@ -47,10 +51,460 @@ pub struct ArcellaConfig {
     pub log_dir: PathBuf,
     pub modules_dir: PathBuf,
     pub cache_dir: PathBuf,
+    /// Max total size of the module cache, in bytes.
+    pub cache_max_size: u64,
     pub socket_path: PathBuf,
+    /// Directory with declarative `*.deployment.toml` files.
+    pub deployments_dir: PathBuf,
+    /// Interval between reconciliation passes over `deployments_dir` (`None` = disabled).
+    pub reconcile_interval: Option<Duration>,
+    /// Loaded configuration files (the main one and its includes) that must not
+    /// change while the daemon runs.
     pub integrity_check_paths: Vec<PathBuf>,
+    /// Interval between integrity checks of `integrity_check_paths` (`None` = disabled).
+    pub integrity_check_interval: Option<Duration>,
+    /// Reaction to a changed configuration file.
+    pub integrity_action: IntegrityAction,
+    /// Global log level set on the command line; overrides `default_level` of `tracing.cfg`.
+    pub log_level: Option<LevelFilter>,
+    /// Merged values of all settings; read them with the typed getters
+    /// (e.g. [`ArcellaConfig::get_section`]).
+    pub data: ConfigData,
+    /// Merged values of the loaded configuration files and where each of them was set.
+    pub sources: ConfigSources,
+}
+
+/// Reaction of the daemon to a configuration file that changed after it was loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntegrityAction {
+    /// Log a warning.
+    #[default]
+    Warn,
+    /// Log a warning and refuse to apply deployments until the configuration is reloaded.
+    RefuseDeployments,
+    /// Log an error and shut the daemon down.
+    Shutdown,
+}
+
+impl FromStr for IntegrityAction {
+    type Err = ArcellaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(Self::Warn),
+            "refuse_deployments" => Ok(Self::RefuseDeployments),
+            "shutdown" => Ok(Self::Shutdown),
+            _ => Err(ArcellaError::Config(format!(
+                "Invalid integrity action '{}' (expected warn, refuse_deployments or shutdown)", s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Warn => write!(f, "warn"),
+            Self::RefuseDeployments => write!(f, "refuse_deployments"),
+            Self::Shutdown => write!(f, "shutdown"),
+        }
+    }
+}
+
+/// The merged configuration values together with their origin.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    /// Final values, with the index of the file (layer) that set each of them.
+    pub values: ConfigValues,
+
+    /// Loaded files in load order: `files[i]` is layer `i`. Layer 0 is the built-in
+    /// defaults, layer 1 the main configuration file, followed by its includes.
+    pub files: Vec<PathBuf>,
+
+    /// Warnings collected while loading the configuration.
+    pub warnings: Vec<fs_utils::ConfigLoadWarning>,
+}
+
+/// A final configuration value and the file that set it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedValue<'a> {
+    pub key: &'a str,
+    pub value: &'a TomlValue,
+    pub layer: usize,
+    pub file: &'a Path,
+}
+
+impl ConfigSources {
+    /// Lists the final values whose key starts with `prefix` (all values for an
+    /// empty prefix), with the file and layer that set each of them.
+    pub fn explain(&self, prefix: &str) -> Vec<ExplainedValue<'_>> {
+        self.values
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, (value, layer))| ExplainedValue {
+                key,
+                value,
+                layer: *layer,
+                file: self.files.get(*layer).map(PathBuf::as_path).unwrap_or(Path::new("?")),
+            })
+            .collect()
+    }
+}
+
+/// Formats a configuration value in TOML notation.
+pub fn format_value(value: &TomlValue) -> String {
+    match value {
+        TomlValue::String(s) => format!("{:?}", s),
+        TomlValue::Integer(i) => i.to_string(),
+        TomlValue::Float(f) => f.to_string(),
+        TomlValue::Boolean(b) => b.to_string(),
+        TomlValue::Datetime(dt) => dt.to_string(),
+        TomlValue::Array(items) => {
+            format!("[{}]", items.iter().map(format_value).collect::<Vec<_>>().join(", "))
+        }
+        TomlValue::Map(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let entries: Vec<_> = entries.into_iter()
+                .map(|(key, value)| format!("{} = {}", key, format_value(value)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        TomlValue::Null => "null".to_string(),
+        TomlValue::TypedError(e) => format!("<{}: {}>", e.error_type, e.message),
+    }
+}
+
+/// Settings given on the daemon command line. They take precedence over the
+/// configuration files.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    /// Base directory, instead of `ARCELLA_HOME` and the lookup of [`fs_utils::find_base_dir`].
+    pub base_dir: Option<PathBuf>,
+
+    /// Main configuration file, instead of `<base_dir>/config/arcella.toml`.
+    pub config_file: Option<PathBuf>,
+
+    /// Path of the ALME socket, instead of `alme.socket.path`.
+    pub socket_path: Option<PathBuf>,
+
+    /// Global log level, instead of `default_level` of `tracing.cfg`.
+    pub log_level: Option<LevelFilter>,
+
+    /// Settings given with `--set`, applied in order on top of the environment overrides.
+    pub settings: Vec<SettingOverride>,
+}
+
+/// Prefix of the environment variables that override settings
+/// (`ARCELLA__LOG__DEFAULT_LEVEL` sets `arcella.log.default_level`).
+pub const ENV_OVERRIDE_PREFIX: &str = "ARCELLA__";
+
+/// A setting given outside the configuration files: on the command line or in
+/// the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingOverride {
+    /// Dotted key, e.g. `arcella.cache.dir`.
+    pub key: String,
+
+    /// Raw value; parsed as a TOML value, or taken as a string if it is not one.
+    pub value: String,
+
+    /// Origin of the override, reported as its file (e.g. `--set arcella.cache.dir`).
+    pub source: String,
+}
+
+impl SettingOverride {
+    /// Parses a `--set KEY=VALUE` argument.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the argument has no `=` or an empty key.
+    pub fn from_arg(arg: &str) -> ArcellaResult<Self> {
+        match arg.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok(Self {
+                key: key.trim().to_string(),
+                value: value.to_string(),
+                source: format!("--set {}", key.trim()),
+            }),
+            _ => Err(ArcellaError::Config(format!("Expected KEY=VALUE, got '{}'", arg))),
+        }
+    }
+
+    /// Maps an `ARCELLA__SECTION__KEY` environment variable onto `arcella.section.key`.
+    ///
+    /// Returns `None` for variables without the [`ENV_OVERRIDE_PREFIX`].
+    pub fn from_env(name: &str, value: &str) -> Option<Self> {
+        let path = name.strip_prefix(ENV_OVERRIDE_PREFIX)?;
+        if path.is_empty() {
+            return None;
+        }
+        let key = std::iter::once("arcella".to_string())
+            .chain(path.split("__").map(str::to_lowercase))
+            .collect::<Vec<_>>()
+            .join(".");
+        Some(Self { key, value: value.to_string(), source: format!("env {}", name) })
+    }
+
+    /// Parses the raw value as a TOML value (`10`, `true`, `"text"`, `[1, 2]`),
+    /// falling back to a plain string (`debug`, `/srv/cache`).
+    fn parse_value(&self) -> TomlValue {
+        fs_utils::toml::parse_and_collect(&format!("value = {}", self.value), &[], 0)
+            .ok()
+            .and_then(|(data, _)| data.values.get("value").map(|(value, _)| value.clone()))
+            .unwrap_or_else(|| TomlValue::String(self.value.clone()))
+    }
 }
 
+impl ArcellaConfig {
+    /// Builds a configuration rooted at `base_dir` with the default directory layout
+    /// and the built-in default settings.
+    #[cfg(test)]
+    pub fn for_tests(base_dir: &Path) -> Self {
+        Self {
+            base_dir: base_dir.to_path_buf(),
+            config_dir: base_dir.join("config"),
+            log_dir: base_dir.join("log"),
+            modules_dir: base_dir.join("modules"),
+            cache_dir: base_dir.join("cache"),
+            cache_max_size: 512 * 1024 * 1024,
+            socket_path: base_dir.join("alme"),
+            deployments_dir: base_dir.join("deployments"),
+            reconcile_interval: None,
+            integrity_check_paths: Vec::new(),
+            integrity_check_interval: None,
+            integrity_action: IntegrityAction::Warn,
+            log_level: None,
+            data: default_data().expect("built-in defaults are valid"),
+            sources: ConfigSources::default(),
+        }
+    }
+}
+
+impl ArcellaConfig {
+    /// Returns the string value of `key` (e.g. `arcella.log.filename`).
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the key is not set or is not a string.
+    pub fn get_str(&self, key: &str) -> ArcellaResult<&str> {
+        get_str(&self.data, key)
+    }
+
+    /// Returns the value of `key` as a non-negative integer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the key is not set or is not a non-negative integer.
+    pub fn get_u64(&self, key: &str) -> ArcellaResult<u64> {
+        get_u64(&self.data, key)
+    }
+
+    /// Returns the value of `key`, a non-negative number of seconds, as a duration.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the key is not set or is not a
+    /// non-negative integer or float.
+    pub fn get_duration(&self, key: &str) -> ArcellaResult<Duration> {
+        get_duration(&self.data, key)
+    }
+
+    /// Returns the path in `key`, resolved against `base_dir` if it is relative.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the key is not set or is not a string.
+    pub fn get_path(&self, key: &str) -> ArcellaResult<PathBuf> {
+        resolve_path(&self.data, &self.base_dir, key)
+    }
+
+    /// Deserializes all settings under `section` (e.g. `arcella.alme`) into `T`.
+    ///
+    /// Keys of nested sections become nested structures, so `arcella.alme.socket.path`
+    /// is the field `path` of the field `socket`. A missing section is deserialized
+    /// from an empty table, which succeeds if all fields of `T` have defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the settings do not match `T`.
+    pub fn get_section<T: DeserializeOwned>(&self, section: &str) -> ArcellaResult<T> {
+        serde_json::from_value(section_to_json(&self.data, section)?)
+            .map_err(|e| ArcellaError::Config(format!("{}: {}", section, e)))
+    }
+}
+
+fn get_value<'a>(data: &'a ConfigData, key: &str) -> ArcellaResult<&'a TomlValue> {
+    data.get(key).ok_or_else(|| ArcellaError::Config(format!("{} is not set", key)))
+}
+
+fn get_str<'a>(data: &'a ConfigData, key: &str) -> ArcellaResult<&'a str> {
+    match get_value(data, key)? {
+        TomlValue::String(s) => Ok(s),
+        _ => Err(ArcellaError::Config(format!("{} must be a string", key))),
+    }
+}
+
+fn get_u64(data: &ConfigData, key: &str) -> ArcellaResult<u64> {
+    match get_value(data, key)? {
+        TomlValue::Integer(i) if *i >= 0 => Ok(*i as u64),
+        _ => Err(ArcellaError::Config(format!("{} must be a non-negative integer", key))),
+    }
+}
+
+fn get_duration(data: &ConfigData, key: &str) -> ArcellaResult<Duration> {
+    match get_value(data, key)? {
+        TomlValue::Integer(seconds) if *seconds >= 0 => Ok(Duration::from_secs(*seconds as u64)),
+        TomlValue::Float(seconds) if seconds.0 >= 0.0 && seconds.0.is_finite() => {
+            Ok(Duration::from_secs_f64(seconds.0))
+        }
+        _ => Err(ArcellaError::Config(format!("{} must be a non-negative number of seconds", key))),
+    }
+}
+
+fn resolve_path(data: &ConfigData, base_dir: &Path, key: &str) -> ArcellaResult<PathBuf> {
+    Ok(base_dir.join(get_str(data, key)?))
+}
+
+/// Builds a JSON object of the settings under `section`, nesting subsections.
+fn section_to_json(data: &ConfigData, section: &str) -> ArcellaResult<serde_json::Value> {
+    let name = |key: &str| key.rsplit('.').next().unwrap_or(key).to_string();
+
+    let mut object = serde_json::Map::new();
+    for (key, value) in data.get_section_data(section).unwrap_or_default() {
+        let json = serde_json::Value::try_from(value)
+            .map_err(|e| ArcellaError::Config(format!("{}: {}", key, e)))?;
+        object.insert(name(&key), json);
+    }
+    for subsection in data.get_subsection_names(section).unwrap_or_default() {
+        object.insert(name(&subsection), section_to_json(data, &subsection)?);
+    }
+    Ok(serde_json::Value::Object(object))
+}
+
+/// Settings that differ between the running configuration and a reloaded one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Changed settings that are applied to the running daemon.
+    pub hot: Vec<&'static str>,
+
+    /// Changed settings that only take effect after a restart.
+    pub restart: Vec<&'static str>,
+}
+
+impl ConfigDiff {
+    /// Returns `true` if no setting has changed.
+    pub fn is_empty(&self) -> bool {
+        self.hot.is_empty() && self.restart.is_empty()
+    }
+
+    /// Adds the changes found by another comparison (e.g. of `tracing.cfg`).
+    pub fn extend(&mut self, other: ConfigDiff) {
+        self.hot.extend(other.hot);
+        self.restart.extend(other.restart);
+    }
+}
+
+/// Settings in [`ArcellaConfig::data`] that are read on every use (the ALME
+/// limits per connection, the tracing settings file on every reload), so a
+/// reloaded value applies at once. Each entry is the key and the name it is
+/// reported under.
+const HOT_DATA_KEYS: &[(&str, &str)] = &[
+    ("arcella.alme.max_request_length", "alme.max_request_length"),
+    ("arcella.alme.read_timeout_seconds", "alme.read_timeout_seconds"),
+    ("arcella.log.tracing_config", "log.tracing_config"),
+];
+
+/// Settings in [`ArcellaConfig::data`] that are read once at startup. A key
+/// ending with `.` stands for all settings of that section.
+const RESTART_DATA_KEYS: &[(&str, &str)] = &[
+    ("arcella.log.filename", "log.filename"),
+    ("arcella.workers.", "workers.*"),
+];
+
+/// Returns `true` if the value of `key` (or of any key of the section, for a
+/// key ending with `.`) differs between two configurations.
+fn data_changed(old: &ConfigData, new: &ConfigData, key: &str) -> bool {
+    if key.ends_with('.') {
+        let section = |data: &ConfigData| -> Vec<(String, TomlValue)> {
+            data.values.iter()
+                .filter(|(k, _)| k.starts_with(key))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        };
+        section(old) != section(new)
+    } else {
+        old.get(key) != new.get(key)
+    }
+}
+
+impl ArcellaConfig {
+    /// Compares the running configuration with a reloaded one.
+    ///
+    /// The cache size limit, the deployments directory and reconciliation
+    /// interval, the integrity check settings, the ALME request limits and the
+    /// tracing settings file can be changed at runtime; directories, the ALME socket, the log file and the
+    /// worker settings are set up once at startup and require a restart.
+    pub fn diff(&self, new: &ArcellaConfig) -> ConfigDiff {
+        let mut diff = ConfigDiff::default();
+
+        let mut check = |changed: bool, key: &'static str, hot: bool| {
+            match (changed, hot) {
+                (false, _) => {}
+                (true, true) => diff.hot.push(key),
+                (true, false) => diff.restart.push(key),
+            }
+        };
+
+        check(self.cache_max_size != new.cache_max_size, "cache.max_size_mb", true);
+        check(self.deployments_dir != new.deployments_dir, "deployments.dir", true);
+        check(self.reconcile_interval != new.reconcile_interval, "deployments.reconcile_interval_seconds", true);
+        check(self.integrity_check_interval != new.integrity_check_interval, "integrity.interval_seconds", true);
+        check(self.integrity_action != new.integrity_action, "integrity.action", true);
+        check(self.base_dir != new.base_dir, "base_dir", false);
+        check(self.log_dir != new.log_dir, "log.dir", false);
+        check(self.modules_dir != new.modules_dir, "modules.dir", false);
+        check(self.cache_dir != new.cache_dir, "cache.dir", false);
+        check(self.socket_path != new.socket_path, "alme.socket.path", false);
+        for (key, name) in HOT_DATA_KEYS {
+            check(data_changed(&self.data, &new.data, key), name, true);
+        }
+        for (key, name) in RESTART_DATA_KEYS {
+            check(data_changed(&self.data, &new.data, key), name, false);
+        }
+
+        diff
+    }
+
+    /// Returns a copy of the running configuration with the hot-reloadable
+    /// settings taken from `new`.
+    ///
+    /// The sources are taken from `new` as well, so they describe the files as
+    /// last loaded, including settings that wait for a restart.
+    pub fn with_hot_settings(&self, new: &ArcellaConfig) -> ArcellaConfig {
+        let mut values = self.data.values.clone();
+        for (key, _) in HOT_DATA_KEYS {
+            match new.data.get(key) {
+                Some(value) => { values.insert(key.to_string(), value.clone()); }
+                None => { values.shift_remove(*key); }
+            }
+        }
+
+        ArcellaConfig {
+            cache_max_size: new.cache_max_size,
+            deployments_dir: new.deployments_dir.clone(),
+            reconcile_interval: new.reconcile_interval,
+            integrity_check_paths: new.integrity_check_paths.clone(),
+            integrity_check_interval: new.integrity_check_interval,
+            integrity_action: new.integrity_action,
+            data: ConfigData::new(values),
+            sources: new.sources.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Detects configuration files that were modified after they were loaded, by
+/// comparing their modification times with the ones recorded at creation.
 #[derive(Debug, Clone)]
 pub struct IntegrityChecker {
     paths: Vec<PathBuf>,
@@ -58,6 +512,11 @@ pub struct IntegrityChecker {
 }
 
 impl IntegrityChecker {
+    /// Records the current modification times of `paths`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if a file does not exist or its metadata cannot be read.
     pub fn new(paths: Vec<PathBuf>) -> ArcellaResult<Self> {
         let mut initial_mtimes = HashMap::new();
         for path in &paths {
@@ -70,6 +529,17 @@ impl IntegrityChecker {
         Ok(IntegrityChecker { paths, initial_mtimes })
     }
 
+    /// Returns the monitored files.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Checks that none of the monitored files was modified or removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first modified file, or an I/O error if a
+    /// file can no longer be read.
     pub async fn check(&self) -> ArcellaResult<()> {
         let current_mtimes = get_current_mtimes(&self.paths).await?;
         check_mtimes_changed(&self.initial_mtimes, &current_mtimes)
@@ -83,13 +553,13 @@ fn check_mtimes_changed(
     for (path, current_mtime) in current_mtimes {
         if let Some(initial_mtime) = initial_mtimes.get(path) {
             if current_mtime != initial_mtime {
-                return Err(ArcellaError::Internal(
-                    format!("Config integrity violation: file {:?} was modified after startup", path)
+                return Err(ArcellaError::IntegrityViolation(
+                    format!("file {:?} was modified after startup", path)
                 ));
             }
         } else {
-            return Err(ArcellaError::Internal(
-                format!("Config integrity violation: file {:?} not found in initial list", path)
+            return Err(ArcellaError::IntegrityViolation(
+                format!("file {:?} not found in initial list", path)
             ));
         }
     }
@@ -161,24 +631,66 @@ struct ResolvedValue {
     redef_allowed_by: Option<usize>, // кто разрешил переопределение (None = запрещено)
 }
 
-pub async fn load() -> ArcellaResult<(ArcellaConfig, Vec<fs_utils::ConfigLoadWarning>)> {
-    
+/// Loads the configuration: the built-in defaults, the main configuration file
+/// and its includes, and on top the `ARCELLA__*` environment variables and the
+/// command-line overrides.
+///
+/// Relative directories and the socket path from the configuration files are
+/// resolved against `base_dir`; relative paths given on the command line are
+/// resolved against the current directory.
+///
+/// # Arguments
+///
+/// * `overrides` — settings given on the command line.
+/// * `warnings` — receives the warnings collected while loading, also when
+///   loading fails, so that they can be reported with the error.
+///
+/// # Errors
+///
+/// Returns an error if a configuration file cannot be read or parsed, or if a
+/// required setting is missing or invalid.
+pub async fn load(
+    overrides: &ConfigOverrides,
+    warnings: &mut Vec<fs_utils::ConfigLoadWarning>,
+) -> ArcellaResult<ArcellaConfig> {
+    let mut state = fs_utils::ConfigLoadState {
+        config_files: IndexSet::new(),
+        visited_paths: HashSet::new(),
+        warnings: std::mem::take(warnings),
+    };
+    let result = load_with_state(overrides, &mut state).await;
+    *warnings = state.warnings;
+    result
+}
+
+async fn load_with_state(
+    overrides: &ConfigOverrides,
+    state: &mut fs_utils::ConfigLoadState,
+) -> ArcellaResult<ArcellaConfig> {
     // 1. Find base_dir
-    let base_dir = fs_utils::find_base_dir().await?;
+    let base_dir = match &overrides.base_dir {
+        Some(base_dir) => absolute(base_dir)?,
+        None => fs_utils::find_base_dir().await?,
+    };
 
     // 2. Set config_dir
     let config_dir = base_dir.join("config");    
 
-    // 3. Ensure config_dir exists
-    let (main_config_path, warnings) = ensure_main_config_exists(&config_dir).await?;
-
-    // 4. Load default config
-    let mut state  = fs_utils::ConfigLoadState {
-        config_files: IndexSet::new(),
-        visited_paths: HashSet::new(),
-        warnings: warnings,
+    // 3. Ensure the main config exists (it is created from the template if it is the default one)
+    let (main_config_path, created) = match &overrides.config_file {
+        Some(config_file) => {
+            let config_file = absolute(config_file)?;
+            if !config_file.is_file() {
+                return Err(ArcellaError::Config(format!("Config file {:?} not found", config_file)));
+            }
+            (config_file, vec![])
+        }
+        None => ensure_main_config_exists(&config_dir).await?,
     };
 
+    state.warnings.extend(created);
+
+    // 4. Load default config
     let (file_idx, _) = state.config_files.insert_full(
         PathBuf::from_str(DEFAULT_CONFIG_FILENAME).unwrap()
     );
@@ -188,15 +700,15 @@ pub async fn load() -> ArcellaResult<(ArcellaConfig, Vec<fs_utils::ConfigLoadWar
         file_idx,
     )?;
 
-    // 5. Load arcella.toml and includes
+    // 5. Load arcella.toml and includes (relative to the directory of the main config)
     let params = fs_utils::ConfigLoadParams {
         prefix: vec!["arcella".to_string()],
-        config_dir: config_dir.to_path_buf(),
+        config_dir: main_config_path.parent().unwrap_or(&config_dir).to_path_buf(),
     };
 
     let configs = fs_utils::load_config_recursive_from_file(
         &params,
-        &mut state,
+        state,
         &main_config_path,
     ).await?;
 
@@ -204,63 +716,140 @@ pub async fn load() -> ArcellaResult<(ArcellaConfig, Vec<fs_utils::ConfigLoadWar
         &default_config,
         &configs,
         &state.config_files,
-        &config_dir,
+        &main_config_path,
         &mut state.warnings,
     )?;
+
+    // Every loaded file except the built-in defaults (layer 0)
+    let integrity_check_paths = state.config_files.iter().skip(1).cloned().collect();
+
+    // 6. Apply the environment and command-line overrides on top
+    let settings: Vec<SettingOverride> = std::env::vars()
+        .filter_map(|(name, value)| SettingOverride::from_env(&name, &value))
+        .chain(overrides.settings.iter().cloned())
+        .collect();
+    apply_overrides(&mut final_values, &settings, state);
+
+    // 7. Check the types and ranges of the known keys
+    schema::validate(&mut final_values, &default_config.values, &state.config_files, &mut state.warnings)?;
     final_values.sort_keys();
 
-    let log_dir = match final_values.get("arcella.log.dir") {
-        Some((TomlValue::String(s) ,_)) => {
-            PathBuf::from(s)
-        }
-        _ => {
-            return Err(ArcellaError::Internal("arcella.log.dir is not set".to_string()));
-        }
-    };
+    let data = ConfigData::new(
+        final_values.iter().map(|(key, (value, _))| (key.clone(), value.clone())).collect()
+    );
 
-    let modules_dir = match final_values.get("arcella.modules.dir") {
-        Some((TomlValue::String(s) ,_)) => {
-            PathBuf::from(s)
-        }
-        _ => {
-            return Err(ArcellaError::Internal("arcella.modules.dir is not set".to_string()));
-        }
-    };
+    let log_dir = resolve_path(&data, &base_dir, "arcella.log.dir")?;
+    let modules_dir = resolve_path(&data, &base_dir, "arcella.modules.dir")?;
+    let cache_dir = resolve_path(&data, &base_dir, "arcella.cache.dir")?;
+    let socket_path = resolve_path(&data, &base_dir, "arcella.alme.socket.path")?;
+    let deployments_dir = resolve_path(&data, &base_dir, "arcella.deployments.dir")?;
 
-    let cache_dir = match final_values.get("arcella.cache.dir") {
-        Some((TomlValue::String(s) ,_)) => {
-            PathBuf::from(s)
-        }
-        _ => {
-            return Err(ArcellaError::Internal("arcella.cache.dir is not set".to_string()));
-        }
+    let cache_max_size = match get_u64(&data, "arcella.cache.max_size_mb")? {
+        0 => return Err(ArcellaError::Config("arcella.cache.max_size_mb must be positive".to_string())),
+        mb => mb.saturating_mul(1024 * 1024),
     };
 
-    let socket_path = match final_values.get("arcella.alme.socket.path") {
-        Some((TomlValue::String(s) ,_)) => {
-            PathBuf::from(s)
-        }
-        _ => {
-            return Err(ArcellaError::Internal("arcella.alme.socket.path is not set".to_string()));
-        }
+    // A zero interval disables the periodic task
+    let reconcile_interval = Some(get_duration(&data, "arcella.deployments.reconcile_interval_seconds")?)
+        .filter(|interval| !interval.is_zero());
+    let integrity_check_interval = Some(get_duration(&data, "arcella.integrity.interval_seconds")?)
+        .filter(|interval| !interval.is_zero());
+    let integrity_action = get_str(&data, "arcella.integrity.action")?.parse::<IntegrityAction>()?;
+
+    let socket_path = match &overrides.socket_path {
+        Some(path) => absolute(path)?,
+        None => socket_path,
     };
 
-    Ok((ArcellaConfig {
+    Ok(ArcellaConfig {
         base_dir: base_dir,
         config_dir: config_dir,
         log_dir: log_dir,
         modules_dir: modules_dir,
         cache_dir: cache_dir,
+        cache_max_size,
         socket_path: socket_path,
-        integrity_check_paths: vec![],
-    }, state.warnings))
+        deployments_dir,
+        reconcile_interval,
+        integrity_check_paths,
+        integrity_check_interval,
+        integrity_action,
+        log_level: overrides.log_level,
+        data,
+        sources: ConfigSources {
+            values: final_values,
+            files: state.config_files.iter().cloned().collect(),
+            warnings: state.warnings.clone(),
+        },
+    })
+}
+
+/// Returns the log directory of the built-in defaults, for reporting a failure to
+/// load the configuration.
+///
+/// Returns `None` if the base directory cannot be determined.
+pub async fn fallback_log_dir(overrides: &ConfigOverrides) -> Option<PathBuf> {
+    let base_dir = match &overrides.base_dir {
+        Some(base_dir) => absolute(base_dir).ok()?,
+        None => fs_utils::find_base_dir().await.ok()?,
+    };
+    resolve_path(&default_data().ok()?, &base_dir, "arcella.log.dir").ok()
+}
+
+/// Returns the settings of the built-in default configuration.
+fn default_data() -> ArcellaResult<ConfigData> {
+    let (defaults, _) = fs_utils::toml::parse_and_collect(
+        DEFAULT_CONFIG_CONTENT,
+        &["arcella".to_string()],
+        0,
+    )?;
+    Ok(ConfigData::new(
+        defaults.values.into_iter().map(|(key, (value, _))| (key, value)).collect()
+    ))
+}
+
+/// Applies setting overrides to the merged values, each as a layer of its own.
+///
+/// As for included files, an override may only set a key of the default
+/// configuration or a key under `arcella.custom` / `arcella.modules`; other
+/// overrides are ignored with a warning.
+fn apply_overrides(
+    final_values: &mut ConfigValues,
+    settings: &[SettingOverride],
+    state: &mut fs_utils::ConfigLoadState,
+) {
+    for setting in settings {
+        if !final_values.contains_key(&setting.key) && !is_newable(&setting.key) {
+            state.warnings.push(fs_utils::ConfigLoadWarning::ValueError {
+                key: setting.key.clone(),
+                error: "Override ignored due to missing in default config".to_string(),
+                file: PathBuf::from(&setting.source),
+            });
+            continue;
+        }
+
+        let (layer, _) = state.config_files.insert_full(PathBuf::from(&setting.source));
+        final_values.insert(setting.key.clone(), (setting.parse_value(), layer));
+    }
+}
+
+/// Returns `true` for keys that may be set even though the default
+/// configuration does not define them.
+fn is_newable(key: &str) -> bool {
+    key.starts_with("arcella.custom") || key.starts_with("arcella.modules")
+}
+
+/// Makes a path given on the command line absolute.
+fn absolute(path: &Path) -> ArcellaResult<PathBuf> {
+    std::path::absolute(path)
+        .map_err(|e| ArcellaError::IoWithPath { source: e, path: path.to_path_buf() })
 }
 
 fn merge_config(
     default_config: &fs_utils::TomlFileData,
     configs: &Vec<fs_utils::TomlFileData>,
     config_files: &IndexSet<PathBuf>,
-    config_dir: &Path,
+    main_config_path: &Path,
     warnings: &mut Vec<fs_utils::ConfigLoadWarning>
 ) -> Result< ConfigValues, ArcellaError> {
     
@@ -319,7 +908,7 @@ fn merge_config(
         }
     }
 
-    let main_idx = config_files.get_index_of(&config_dir.join(MAIN_CONFIG_FILENAME)).unwrap();
+    let main_idx = config_files.get_index_of(main_config_path).unwrap();
     let default_idx = config_files.get_index_of(&PathBuf::from_str(DEFAULT_CONFIG_FILENAME).unwrap()).unwrap();
 
     let mut final_values: ConfigValues = IndexMap::new();
@@ -337,8 +926,7 @@ fn merge_config(
     for (key, preliminary_value) in &preliminary_values {
         // Флаг говорит о том, что раздел конфигурации допускает 
         // доопределение параметров отсутствующих в конфигурации по умолчанию
-        let is_newable = key.starts_with("arcella.custom") 
-            || key.starts_with("arcella.modules");
+        let is_newable = is_newable(key);
         let new_value = &preliminary_value.value;
         let insert_index = preliminary_value.source_layer;
 
@@ -422,6 +1010,7 @@ mod tests {
         let default_config = fs_utils::TomlFileData {
             includes: vec![],
             values: default_values,
+            ..Default::default()
         };
 
         // arcella.toml (layer 1)
@@ -435,6 +1024,7 @@ mod tests {
         let main_config = fs_utils::TomlFileData {
             includes: vec![],
             values: main_config_values,
+            ..Default::default()
         };
 
         // level_1.toml (layer 2, предполагаем, что он загружен через includes)
@@ -448,6 +1038,7 @@ mod tests {
         let level_1_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_1_values,
+            ..Default::default()
         };
 
         let configs = vec![main_config, level_1_config];
@@ -458,7 +1049,7 @@ mod tests {
             &default_config, 
             &configs, 
             &config_files, 
-            &config_dir,
+            &config_dir.join(MAIN_CONFIG_FILENAME),
             &mut warnings).expect("merge_config should succeed");
 
         // Проверяем итоговую конфигурацию
@@ -503,6 +1094,7 @@ mod tests {
         let default_config = fs_utils::TomlFileData {
             includes: vec![],
             values: default_values,
+            ..Default::default()
         };
 
         // arcella.toml (layer 1) - не помечает host как #redef
@@ -512,6 +1104,7 @@ mod tests {
         let main_config = fs_utils::TomlFileData {
             includes: vec![],
             values: main_config_values,
+            ..Default::default()
         };
 
         // level_1.toml (layer 2)
@@ -521,6 +1114,7 @@ mod tests {
         let level_1_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_1_values,
+            ..Default::default()
         };
 
         // level_2.toml (layer 3) - пытается изменить host
@@ -530,6 +1124,7 @@ mod tests {
         let level_2_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_2_values,
+            ..Default::default()
         };
 
         let configs = vec![main_config, level_1_config, level_2_config];
@@ -540,7 +1135,7 @@ mod tests {
             &default_config, 
             &configs, 
             &config_files, 
-            &config_dir,
+            &config_dir.join(MAIN_CONFIG_FILENAME),
             &mut warnings).expect("merge_config should succeed");
 
         assert_eq!(result.get("arcella.server.host"), Some(&(make_toml_value("192.168.1.1"), 1))); // Остается значение из arcella.toml
@@ -576,6 +1171,7 @@ mod tests {
         let default_config = fs_utils::TomlFileData {
             includes: vec![],
             values: default_values,
+            ..Default::default()
         };
 
         // arcella.toml (layer 1) - помечает level как #redef
@@ -585,6 +1181,7 @@ mod tests {
         let main_config = fs_utils::TomlFileData {
             includes: vec![],
             values: main_config_values,
+            ..Default::default()
         };
 
         // level_1.toml (layer 2) - может изменить level, так как arcella.toml пометила его как #redef
@@ -594,6 +1191,7 @@ mod tests {
         let level_1_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_1_values,
+            ..Default::default()
         };
 
         // level_2.toml (layer 3) - может изменить level, так как level_1.toml пометил его как #redef
@@ -603,6 +1201,7 @@ mod tests {
         let level_2_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_2_values,
+            ..Default::default()
         };
 
         let configs = vec![main_config, level_1_config, level_2_config];
@@ -613,7 +1212,7 @@ mod tests {
             &default_config, 
             &configs, 
             &config_files, 
-            &config_dir,
+            &config_dir.join(MAIN_CONFIG_FILENAME),
             &mut warnings).expect("merge_config should succeed");
 
         // Значение level должно быть переопределено из level_1.toml, так как #redef разрешил это в arcella.toml
@@ -633,6 +1232,7 @@ mod tests {
         let default_config = fs_utils::TomlFileData {
             includes: vec![],
             values: default_values,
+            ..Default::default()
         };
 
         // arcella.toml (layer 1)
@@ -641,6 +1241,7 @@ mod tests {
         let main_config = fs_utils::TomlFileData {
             includes: vec![],
             values: main_config_values,
+            ..Default::default()
         };
 
         // level_1.toml (layer 2) - добавляет новый ключ в arcella.custom
@@ -650,6 +1251,7 @@ mod tests {
         let level_1_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_1_values,
+            ..Default::default()
         };
 
         let configs = vec![main_config, level_1_config];
@@ -660,7 +1262,7 @@ mod tests {
             &default_config, 
             &configs, 
             &config_files, 
-            &config_dir,
+            &config_dir.join(MAIN_CONFIG_FILENAME),
             &mut warnings).expect("merge_config should succeed");
 
         assert_eq!(result.get("arcella.custom.new_key"), Some(&(make_toml_value("new_value"), 2)));
@@ -675,6 +1277,7 @@ mod tests {
         let default_config = fs_utils::TomlFileData {
             includes: vec![],
             values: default_values,
+            ..Default::default()
         };
 
         // arcella.toml (layer 1)
@@ -682,6 +1285,7 @@ mod tests {
         let main_config = fs_utils::TomlFileData {
             includes: vec![],
             values: main_config_values,
+            ..Default::default()
         };
 
         // level_1.toml (layer 2) - пытается добавить новый ключ в arcella.server
@@ -690,6 +1294,7 @@ mod tests {
         let level_1_config = fs_utils::TomlFileData {
             includes: vec![],
             values: level_1_values,
+            ..Default::default()
         };
 
         let configs = vec![main_config, level_1_config];
@@ -706,7 +1311,7 @@ mod tests {
             &default_config, 
             &configs, 
             &config_files, 
-            &config_dir,
+            &config_dir.join(MAIN_CONFIG_FILENAME),
             &mut warnings).expect("merge_config should succeed");
 
         // Новый ключ не должен появиться
@@ -723,5 +1328,152 @@ mod tests {
         }
     }    
 
-}
 
+    #[test]
+    fn test_config_diff_separates_hot_settings() {
+        let base_dir = PathBuf::from("/opt/arcella");
+        let current = ArcellaConfig::for_tests(&base_dir);
+        assert!(current.diff(&current.clone()).is_empty());
+
+        let mut new = current.clone();
+        new.cache_max_size = 1024;
+        new.reconcile_interval = Some(Duration::from_secs(5));
+        new.socket_path = base_dir.join("other.sock");
+
+        let diff = current.diff(&new);
+        assert_eq!(diff.hot, vec!["cache.max_size_mb", "deployments.reconcile_interval_seconds"]);
+        assert_eq!(diff.restart, vec!["alme.socket.path"]);
+
+        let applied = current.with_hot_settings(&new);
+        assert_eq!(applied.cache_max_size, 1024);
+        assert_eq!(applied.reconcile_interval, Some(Duration::from_secs(5)));
+        assert_eq!(applied.socket_path, current.socket_path);
+    }
+
+    #[test]
+    fn test_config_diff_compares_data_settings() {
+        let current = ArcellaConfig::for_tests(Path::new("/opt/arcella"));
+
+        let mut values = current.data.values.clone();
+        values.insert("arcella.alme.read_timeout_seconds".into(), TomlValue::Integer(5));
+        values.insert("arcella.log.filename".into(), TomlValue::String("other.log".into()));
+        values.insert("arcella.log.tracing_config".into(), TomlValue::String("etc/tracing.cfg".into()));
+        values.insert("arcella.workers.heartbeat_interval_seconds".into(), TomlValue::Integer(1));
+        let mut new = current.clone();
+        new.data = ConfigData::new(values);
+
+        let diff = current.diff(&new);
+        assert_eq!(diff.hot, vec!["alme.read_timeout_seconds", "log.tracing_config"]);
+        assert_eq!(diff.restart, vec!["log.filename", "workers.*"]);
+
+        let applied = current.with_hot_settings(&new);
+        assert_eq!(applied.get_duration("arcella.alme.read_timeout_seconds").unwrap(), Duration::from_secs(5));
+        assert_eq!(applied.get_path("arcella.log.tracing_config").unwrap(), Path::new("/opt/arcella/etc/tracing.cfg"));
+        assert_eq!(applied.get_str("arcella.log.filename").unwrap(), "arcella.log");
+        assert_eq!(applied.get_u64("arcella.workers.heartbeat_interval_seconds").unwrap(), 5);
+    }
+
+    #[test]
+    fn test_explain_reports_file_and_layer() {
+        let mut values: ConfigValues = IndexMap::new();
+        values.insert("arcella.log.dir".to_string(), (make_toml_value("log"), 0));
+        values.insert("arcella.log.default_level".to_string(), (make_toml_value("debug"), 2));
+        values.insert("arcella.cache.max_size_mb".to_string(), (TomlValue::Integer(64), 1));
+        let sources = ConfigSources {
+            values,
+            files: vec![
+                PathBuf::from(DEFAULT_CONFIG_FILENAME),
+                PathBuf::from("config/arcella.toml"),
+                PathBuf::from("config/log.toml"),
+            ],
+            warnings: vec![],
+        };
+
+        let explained = sources.explain("arcella.log.");
+        assert_eq!(explained.len(), 2);
+        assert_eq!(explained[1].key, "arcella.log.default_level");
+        assert_eq!(explained[1].layer, 2);
+        assert_eq!(explained[1].file, Path::new("config/log.toml"));
+        assert_eq!(sources.explain("").len(), 3);
+
+        assert_eq!(format_value(explained[1].value), "\"debug\"");
+        assert_eq!(
+            format_value(&TomlValue::Array(vec![TomlValue::Integer(1), TomlValue::Boolean(true)])),
+            "[1, true]"
+        );
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let mut config = ArcellaConfig::for_tests(Path::new("/srv/arcella"));
+
+        assert_eq!(config.get_str("arcella.log.filename").unwrap(), "arcella.log");
+        assert_eq!(config.get_u64("arcella.alme.max_request_length").unwrap(), 64 * 1024);
+        assert_eq!(
+            config.get_duration("arcella.deployments.reconcile_interval_seconds").unwrap(),
+            Duration::from_secs(10)
+        );
+        assert_eq!(
+            config.get_path("arcella.log.tracing_config").unwrap(),
+            Path::new("/srv/arcella/config/tracing.cfg")
+        );
+        assert!(config.get_u64("arcella.log.filename").is_err());
+        assert!(matches!(config.get_str("arcella.log.missing"), Err(ArcellaError::Config(_))));
+
+        #[derive(Deserialize)]
+        struct Alme {
+            read_timeout_seconds: u64,
+            socket: Socket,
+        }
+        #[derive(Deserialize)]
+        struct Socket {
+            path: String,
+        }
+        let alme: Alme = config.get_section("arcella.alme").unwrap();
+        assert_eq!(alme.read_timeout_seconds, 60);
+        assert_eq!(alme.socket.path, "alme");
+        assert!(config.get_section::<Alme>("arcella.missing").is_err());
+
+        let mut values = IndexMap::new();
+        values.insert("arcella.test.timeout".to_string(), TomlValue::Float(0.5.into()));
+        values.insert("arcella.test.negative".to_string(), TomlValue::Integer(-1));
+        config.data = ConfigData::new(values);
+        assert_eq!(config.get_duration("arcella.test.timeout").unwrap(), Duration::from_millis(500));
+        assert!(config.get_duration("arcella.test.negative").is_err());
+    }
+
+    #[test]
+    fn test_overrides_are_applied_with_provenance() {
+        assert_eq!(
+            SettingOverride::from_env("ARCELLA__LOG__DEFAULT_LEVEL", "debug").unwrap().key,
+            "arcella.log.default_level"
+        );
+        assert_eq!(SettingOverride::from_env("ARCELLA_HOME", "/srv"), None);
+        assert!(SettingOverride::from_arg("arcella.cache.dir").is_err());
+
+        let mut final_values: ConfigValues = IndexMap::new();
+        final_values.insert("arcella.log.default_level".to_string(), (make_toml_value("info"), 0));
+        final_values.insert("arcella.cache.max_size_mb".to_string(), (TomlValue::Integer(512), 1));
+        let mut state = fs_utils::ConfigLoadState {
+            config_files: IndexSet::from([PathBuf::from(DEFAULT_CONFIG_FILENAME), PathBuf::from("arcella.toml")]),
+            visited_paths: HashSet::new(),
+            warnings: vec![],
+        };
+
+        let settings = vec![
+            SettingOverride::from_env("ARCELLA__LOG__DEFAULT_LEVEL", "debug").unwrap(),
+            SettingOverride::from_arg("arcella.cache.max_size_mb=64").unwrap(),
+            SettingOverride::from_arg("arcella.custom.label=\"edge\"").unwrap(),
+            SettingOverride::from_arg("arcella.unknown=1").unwrap(),
+        ];
+        apply_overrides(&mut final_values, &settings, &mut state);
+
+        assert_eq!(final_values["arcella.log.default_level"], (make_toml_value("debug"), 2));
+        assert_eq!(final_values["arcella.cache.max_size_mb"], (TomlValue::Integer(64), 3));
+        assert_eq!(final_values["arcella.custom.label"], (make_toml_value("edge"), 4));
+        assert!(!final_values.contains_key("arcella.unknown"));
+        assert_eq!(state.config_files[2], PathBuf::from("env ARCELLA__LOG__DEFAULT_LEVEL"));
+        assert_eq!(state.config_files[3], PathBuf::from("--set arcella.cache.max_size_mb"));
+        assert_eq!(state.warnings.len(), 1);
+    }
+}
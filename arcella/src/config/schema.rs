@@ -0,0 +1,326 @@
+// arcella/arcella/src/config/schema.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Schema of the Arcella configuration keys.
+//!
+//! [`SCHEMA`] lists the expected type, the allowed values and a description of
+//! every known key. [`validate`] checks the merged configuration against it while
+//! it is loaded, so that a string where an integer is expected is reported with
+//! the file that set it instead of failing later in the subsystem that reads it.
+//! [`reference`] renders the schema together with the built-in defaults for the
+//! ALME command `config:schema`.
+//!
+//! Keys without a schema entry (e.g. under `arcella.custom`) are not validated.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use indexmap::IndexSet;
+
+use arcella_types::config::{ConfigValues, Value as TomlValue};
+use arcella_fs_utils as fs_utils;
+
+use crate::error::{ArcellaError, Result as ArcellaResult};
+
+/// Log levels accepted by the `arcella.log` settings.
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+/// Expected type of a configuration value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    String,
+    Integer,
+    /// An integer or a float.
+    Number,
+    Boolean,
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValueKind::String => "string",
+            ValueKind::Integer => "integer",
+            ValueKind::Number => "number",
+            ValueKind::Boolean => "boolean",
+        })
+    }
+}
+
+/// Values allowed in addition to the type check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constraint {
+    /// Any value of the expected type.
+    Any,
+    /// A number not less than `min`.
+    Min(f64),
+    /// One of the listed strings.
+    OneOf(&'static [&'static str]),
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Any => Ok(()),
+            Constraint::Min(min) => write!(f, ">= {}", min),
+            Constraint::OneOf(allowed) => write!(f, "one of: {}", allowed.join(", ")),
+        }
+    }
+}
+
+/// What loading does with a value that does not match the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnInvalid {
+    /// Report a `ValueError` warning and use the built-in default instead.
+    Warn,
+    /// Fail to load the configuration.
+    Fail,
+}
+
+impl fmt::Display for OnInvalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OnInvalid::Warn => "warn",
+            OnInvalid::Fail => "fail",
+        })
+    }
+}
+
+/// Schema of one configuration key.
+#[derive(Debug, Clone, Copy)]
+pub struct KeySchema {
+    /// Full dotted key; a `*` segment matches any single segment.
+    pub key: &'static str,
+    pub kind: ValueKind,
+    pub constraint: Constraint,
+    pub on_invalid: OnInvalid,
+    pub description: &'static str,
+}
+
+impl KeySchema {
+    const fn new(key: &'static str, kind: ValueKind, constraint: Constraint, on_invalid: OnInvalid, description: &'static str) -> Self {
+        Self { key, kind, constraint, on_invalid, description }
+    }
+
+    /// Returns `true` if `key` is described by this entry.
+    fn matches(&self, key: &str) -> bool {
+        let mut pattern = self.key.split('.');
+        let mut segments = key.split('.');
+        loop {
+            match (pattern.next(), segments.next()) {
+                (None, None) => return true,
+                (Some(p), Some(s)) if p == "*" || p == s => {}
+                _ => return false,
+            }
+        }
+    }
+
+    /// Checks `value` against the type and the constraint of this entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the mismatch.
+    fn check(&self, value: &TomlValue) -> Result<(), String> {
+        let number = match (self.kind, value) {
+            (ValueKind::String, TomlValue::String(_)) => None,
+            (ValueKind::Boolean, TomlValue::Boolean(_)) => None,
+            (ValueKind::Integer | ValueKind::Number, TomlValue::Integer(i)) => Some(*i as f64),
+            (ValueKind::Number, TomlValue::Float(f)) if f.0.is_finite() => Some(f.0),
+            _ => return Err(format!("expected {}, found {}", self.kind, super::format_value(value))),
+        };
+
+        let valid = match (self.constraint, number, value) {
+            (Constraint::Min(min), Some(number), _) => number >= min,
+            (Constraint::OneOf(allowed), _, TomlValue::String(s)) => allowed.contains(&s.as_str()),
+            _ => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("expected {} {}, found {}", self.kind, self.constraint, super::format_value(value)))
+        }
+    }
+}
+
+/// Schema of all known configuration keys.
+pub static SCHEMA: &[KeySchema] = &[
+    KeySchema::new("arcella.log.default_level", ValueKind::String, Constraint::OneOf(LOG_LEVELS), OnInvalid::Warn,
+        "Global log level"),
+    KeySchema::new("arcella.log.structured", ValueKind::Boolean, Constraint::Any, OnInvalid::Warn,
+        "Enable structured (JSON) logging"),
+    KeySchema::new("arcella.log.stderr", ValueKind::Boolean, Constraint::Any, OnInvalid::Warn,
+        "Log to stderr"),
+    KeySchema::new("arcella.log.dir", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "Log directory (relative to base_dir or absolute)"),
+    KeySchema::new("arcella.log.filename", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "Log file name (relative to the log directory or absolute)"),
+    KeySchema::new("arcella.log.tracing_config", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "Tracing settings file (relative to base_dir or absolute)"),
+    KeySchema::new("arcella.log.alme_buffer_size", ValueKind::Integer, Constraint::Min(0.0), OnInvalid::Warn,
+        "Number of log records kept in memory for log:tail"),
+    KeySchema::new("arcella.log.modules.*", ValueKind::String, Constraint::OneOf(LOG_LEVELS), OnInvalid::Warn,
+        "Log level of a module"),
+    KeySchema::new("arcella.alme.socket.path", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "ALME socket path (relative to base_dir or absolute)"),
+    KeySchema::new("arcella.alme.max_request_length", ValueKind::Integer, Constraint::Min(1.0), OnInvalid::Warn,
+        "Max length of a single ALME request in bytes"),
+    KeySchema::new("arcella.alme.read_timeout_seconds", ValueKind::Number, Constraint::Min(1.0), OnInvalid::Warn,
+        "Seconds an idle ALME connection is kept open"),
+    KeySchema::new("arcella.cache.dir", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "Cache directory (relative to base_dir or absolute)"),
+    KeySchema::new("arcella.cache.max_size_mb", ValueKind::Integer, Constraint::Min(1.0), OnInvalid::Fail,
+        "Max total size of precompiled artifacts in MB"),
+    KeySchema::new("arcella.modules.dir", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "Modules directory (relative to base_dir or absolute)"),
+    KeySchema::new("arcella.workers.heartbeat_interval_seconds", ValueKind::Integer, Constraint::Min(1.0), OnInvalid::Warn,
+        "Seconds between two health checks of a worker process"),
+    KeySchema::new("arcella.workers.shutdown_timeout_seconds", ValueKind::Integer, Constraint::Min(0.0), OnInvalid::Warn,
+        "Seconds a worker process gets to exit on shutdown before it is killed"),
+    KeySchema::new("arcella.deployments.dir", ValueKind::String, Constraint::Any, OnInvalid::Fail,
+        "Directory with *.deployment.toml files (relative to base_dir or absolute)"),
+    KeySchema::new("arcella.deployments.reconcile_interval_seconds", ValueKind::Number, Constraint::Min(0.0), OnInvalid::Fail,
+        "Seconds between reconciliation passes (0 disables them)"),
+    KeySchema::new("arcella.integrity.interval_seconds", ValueKind::Number, Constraint::Min(0.0), OnInvalid::Fail,
+        "Seconds between configuration integrity checks (0 disables them)"),
+    KeySchema::new("arcella.integrity.action", ValueKind::String, Constraint::OneOf(&["warn", "refuse_deployments", "shutdown"]), OnInvalid::Fail,
+        "Reaction to a changed configuration file"),
+];
+
+/// Returns the schema entry describing `key`, if any.
+pub fn find(key: &str) -> Option<&'static KeySchema> {
+    SCHEMA.iter().find(|entry| entry.matches(key))
+}
+
+/// Validates the merged configuration values against [`SCHEMA`].
+///
+/// An invalid value of an [`OnInvalid::Warn`] key is reported as a
+/// `ValueError` warning and replaced by its built-in default (or removed if
+/// the defaults do not define it).
+///
+/// # Arguments
+///
+/// * `values` — The merged values, with the index of the file that set each one.
+/// * `defaults` — The values of the built-in default configuration.
+/// * `config_files` — The loaded files, indexed like the values.
+/// * `warnings` — Receives the warnings.
+///
+/// # Errors
+///
+/// Returns a configuration error for the first invalid value of an
+/// [`OnInvalid::Fail`] key.
+pub fn validate(
+    values: &mut ConfigValues,
+    defaults: &ConfigValues,
+    config_files: &IndexSet<PathBuf>,
+    warnings: &mut Vec<fs_utils::ConfigLoadWarning>,
+) -> ArcellaResult<()> {
+    let file = |idx: usize| config_files.get_index(idx).cloned().unwrap_or_default();
+
+    let mut invalid = Vec::new();
+    for (key, (value, idx)) in values.iter() {
+        let Some(entry) = find(key) else { continue };
+        if let Err(error) = entry.check(value) {
+            match entry.on_invalid {
+                OnInvalid::Fail => {
+                    return Err(ArcellaError::Config(format!("{} in {:?}: {}", key, file(*idx), error)));
+                }
+                OnInvalid::Warn => invalid.push((key.clone(), error, *idx)),
+            }
+        }
+    }
+
+    for (key, error, idx) in invalid {
+        let error = match defaults.get(&key) {
+            Some(default) => {
+                values.insert(key.clone(), default.clone());
+                format!("{}; using the default {}", error, super::format_value(&default.0))
+            }
+            None => {
+                values.shift_remove(&key);
+                format!("{}; ignored", error)
+            }
+        };
+        warnings.push(fs_utils::ConfigLoadWarning::ValueError { key, error, file: file(idx) });
+    }
+
+    Ok(())
+}
+
+/// Reference entry of one configuration key, as reported by `config:schema`.
+#[derive(Debug, Clone)]
+pub struct KeyReference {
+    pub schema: &'static KeySchema,
+    /// The built-in default, if the key has one.
+    pub default: Option<TomlValue>,
+}
+
+/// Builds the reference of all keys in [`SCHEMA`] with their built-in defaults.
+///
+/// # Errors
+///
+/// Returns an error if the built-in default configuration cannot be parsed.
+pub fn reference() -> ArcellaResult<Vec<KeyReference>> {
+    let defaults = super::default_data()?;
+    Ok(SCHEMA.iter()
+        .map(|schema| KeyReference {
+            schema,
+            default: defaults.get(schema.key).cloned(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_default_key_has_a_valid_schema() {
+        let (defaults, _) = fs_utils::toml::parse_and_collect(
+            super::super::DEFAULT_CONFIG_CONTENT,
+            &["arcella".to_string()],
+            0,
+        ).unwrap();
+
+        for (key, (value, _)) in &defaults.values {
+            let entry = find(key).unwrap_or_else(|| panic!("no schema for {}", key));
+            assert_eq!(entry.check(value), Ok(()), "default of {}", key);
+        }
+    }
+
+    #[test]
+    fn test_validate_warns_or_fails() {
+        let config_files: IndexSet<PathBuf> = ["default_config.toml", "arcella.toml"]
+            .into_iter().map(PathBuf::from).collect();
+        let mut defaults = ConfigValues::new();
+        defaults.insert("arcella.alme.max_request_length".to_string(), (TomlValue::Integer(65536), 0));
+
+        let mut values = defaults.clone();
+        values.insert("arcella.alme.max_request_length".to_string(), (TomlValue::String("big".to_string()), 1));
+        values.insert("arcella.log.modules.arcella::alme".to_string(), (TomlValue::String("loud".to_string()), 1));
+        values.insert("arcella.custom.anything".to_string(), (TomlValue::Boolean(true), 1));
+
+        let mut warnings = Vec::new();
+        validate(&mut values, &defaults, &config_files, &mut warnings).unwrap();
+
+        assert_eq!(values.get("arcella.alme.max_request_length"), Some(&(TomlValue::Integer(65536), 0)));
+        assert!(!values.contains_key("arcella.log.modules.arcella::alme"));
+        assert!(values.contains_key("arcella.custom.anything"));
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            warnings[0].to_string(),
+            "Error processing value for key 'arcella.alme.max_request_length' in file \"arcella.toml\": \
+             expected integer, found \"big\"; using the default 65536"
+        );
+
+        values.insert("arcella.integrity.action".to_string(), (TomlValue::String("panic".to_string()), 1));
+        let error = validate(&mut values, &defaults, &config_files, &mut warnings).unwrap_err();
+        assert!(error.to_string().contains(
+            "arcella.integrity.action in \"arcella.toml\": expected string one of: warn, refuse_deployments, shutdown, found \"panic\""
+        ));
+    }
+}
@@ -47,6 +47,10 @@ pub enum ArcellaError {
     #[error("Config error: {0}")]
     Config(String),
 
+    /// A loaded configuration file was modified after startup.
+    #[error("Config integrity violation: {0}")]
+    IntegrityViolation(String),
+
     /// JSON serialization/deserialization error.
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error), 
@@ -59,6 +63,44 @@ pub enum ArcellaError {
     #[error("Runtime error: {0}")]
     RuntimeError(String),
 
+    /// The requested module is not installed.
+    #[error("Module not found: {0}")]
+    ModuleNotFound(String),
+
+    /// A module with the same ID is already installed.
+    #[error("Module already installed: {0}")]
+    ModuleAlreadyInstalled(String),
+
+    /// The module cannot be removed while it is deployed.
+    #[error("Module {0} is in use by {1} deployment(s)")]
+    ModuleInUse(String, usize),
+
+    /// The stored binary of a module does not match the hash in its record.
+    #[error("Module {module_id} is corrupted: blob hash is {actual}, expected {expected}")]
+    ModuleCorrupted {
+        module_id: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// The requested deployment does not exist.
+    #[error("Deployment not found: {0}")]
+    DeploymentNotFound(String),
+
+    /// The requested instance does not exist.
+    #[error("Instance not found: {0}")]
+    InstanceNotFound(String),
+
+    /// Another daemon holds the lock of the base directory.
+    #[error(
+        "Another Arcella daemon is already running in {base_dir:?}{}",
+        pid.map(|pid| format!(" (pid {})", pid)).unwrap_or_default()
+    )]
+    AlreadyRunning {
+        base_dir: PathBuf,
+        pid: Option<u32>,
+    },
+
     #[error("Arcella Wasmtime error: {0}")]
     ArcellaWasmtimeError (#[from] ArcellaWasmtimeError),    
 
@@ -0,0 +1,122 @@
+// arcella/arcella/src/lock/mod.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Single-instance lock of the daemon.
+//!
+//! Only one daemon may run against a `base_dir`. On startup the daemon takes an
+//! exclusive lock on `base_dir/arcella.lock` and writes its PID to
+//! `base_dir/arcella.pid`. The lock is held for the lifetime of the process and
+//! is released by the operating system when the process exits, even if it
+//! crashes, so a held lock always means a live owner.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+use crate::error::{ArcellaError, Result as ArcellaResult};
+
+/// File that is locked by the running daemon.
+pub const LOCK_FILENAME: &str = "arcella.lock";
+
+/// File with the PID of the running daemon.
+pub const PID_FILENAME: &str = "arcella.pid";
+
+/// Exclusive ownership of a `base_dir`; released on drop.
+#[derive(Debug)]
+pub struct InstanceLock {
+    _file: File,
+    pid_path: PathBuf,
+}
+
+impl InstanceLock {
+    /// Takes the lock of `base_dir` and writes the pidfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaError::AlreadyRunning`] if another live daemon holds the
+    /// lock, or an I/O error if the lock file or the pidfile cannot be written.
+    pub fn acquire(base_dir: &Path) -> ArcellaResult<Self> {
+        std::fs::create_dir_all(base_dir)
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: base_dir.to_path_buf() })?;
+
+        let lock_path = base_dir.join(LOCK_FILENAME);
+        let pid_path = base_dir.join(PID_FILENAME);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: lock_path.clone() })?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(ArcellaError::AlreadyRunning {
+                    base_dir: base_dir.to_path_buf(),
+                    pid: read_pid(&pid_path),
+                });
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(ArcellaError::IoWithPath { source: e, path: lock_path });
+            }
+        }
+
+        // A pidfile left by a crashed daemon is simply replaced.
+        let tmp_path = pid_path.with_extension("pid.tmp");
+        std::fs::write(&tmp_path, format!("{}\n", std::process::id()))
+            .and_then(|_| std::fs::rename(&tmp_path, &pid_path))
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: pid_path.clone() })?;
+
+        tracing::debug!("Acquired instance lock {:?}", lock_path);
+        Ok(Self { _file: file, pid_path })
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // The lock file itself stays: removing it while locked would let a new
+        // daemon lock a fresh file while this one still runs.
+        if let Err(e) = std::fs::remove_file(&self.pid_path) {
+            tracing::warn!("Failed to remove pidfile {:?}: {}", self.pid_path, e);
+        }
+    }
+}
+
+/// Reads the PID of the daemon that holds the lock, if it is known.
+fn read_pid(pid_path: &Path) -> Option<u32> {
+    std::fs::read_to_string(pid_path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_second_daemon_is_refused() {
+        let temp_dir = TempDir::new().unwrap();
+        let pid_path = temp_dir.path().join(PID_FILENAME);
+
+        let lock = InstanceLock::acquire(temp_dir.path()).unwrap();
+        assert_eq!(read_pid(&pid_path), Some(std::process::id()));
+
+        match InstanceLock::acquire(temp_dir.path()) {
+            Err(ArcellaError::AlreadyRunning { pid, .. }) => assert_eq!(pid, Some(std::process::id())),
+            other => panic!("Expected AlreadyRunning, got {:?}", other),
+        }
+
+        drop(lock);
+        assert!(!pid_path.exists());
+
+        // A stale pidfile does not prevent the lock from being taken.
+        std::fs::write(&pid_path, "999999\n").unwrap();
+        let _lock = InstanceLock::acquire(temp_dir.path()).unwrap();
+        assert_eq!(read_pid(&pid_path), Some(std::process::id()));
+    }
+}
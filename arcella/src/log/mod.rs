@@ -15,17 +15,25 @@
 //! - **stderr** — for convenient debugging when running in foreground mode;
 //! - **In-memory ring buffer** — to expose recent logs via ALME (e.g., through the CLI).
 //!
-//! Configuration is read from `tracing.cfg` in Arcella’s config directory and allows:
+//! Configuration is read from `tracing.cfg` (`log.tracing_config`, by default in Arcella’s config directory) and allows:
 //! - Setting a global log level;
 //! - Configuring per-module or per-target log levels;
 //! - Enabling/disabling individual output channels;
 //! - Limiting the in-memory buffer size for ALME.
 //!
+//! Log levels can be changed at runtime with [`reload`]; the output channels are
+//! set up once by [`init`].
+//!
+//! Diagnostics reported before [`init`] (e.g. configuration warnings) are collected
+//! in an [`InitLog`]. They are replayed through `tracing` once logging is up, or
+//! written to stderr and `init.log` if the daemon fails to start.
+//!
 //! The system is thread-safe and uses non-blocking I/O for file writes.
 
 use std::collections::{VecDeque, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use time::OffsetDateTime;
 
@@ -36,12 +44,28 @@ use tracing_subscriber::{
     fmt,
     layer::SubscriberExt,
     registry::LookupSpan,
+    reload,
     util::SubscriberInitExt,
     Layer,
+    Registry,
 };
 
+use arcella_fs_utils::ConfigLoadWarning;
+use arcella_types::config::Value as TomlValue;
+use tracing::Level;
+
 use crate::error::{ArcellaError, Result as ArcellaResult};
-use crate::config::ArcellaConfig;
+use crate::config::{ArcellaConfig, ConfigDiff};
+
+/// File in the log directory that receives the startup diagnostics if the daemon
+/// fails to start.
+pub const INIT_LOG_FILENAME: &str = "init.log";
+
+/// Global log level in the Arcella configuration.
+const DEFAULT_LEVEL_KEY: &str = "arcella.log.default_level";
+
+/// Prefix of the per-target log levels in the Arcella configuration.
+const MODULE_LEVEL_PREFIX: &str = "arcella.log.modules.";
 
 // Global resources for logger
 
@@ -55,6 +79,14 @@ fn get_log_buffer() -> Option<&'static Arc<Mutex<VecDeque<String>>>> {
     LOG_BUFFER.get()
 }
 
+/// Handle to the active log filter and the settings it was built from.
+struct LogReload {
+    handle: reload::Handle<EnvFilter, Registry>,
+    config: Mutex<TracingConfig>,
+}
+
+static LOG_RELOAD: std::sync::OnceLock<LogReload> = std::sync::OnceLock::new();
+
 /// Initializes the global `tracing` subscriber based on the provided configuration.
 ///
 /// This function must be called exactly once during daemon startup. It:
@@ -84,9 +116,7 @@ pub fn init(config: &ArcellaConfig) -> ArcellaResult<Option<tracing_appender::no
 
     let mut file_guard: Option<tracing_appender::non_blocking::WorkerGuard> = None;
 
-    let tracing_cfg_path = config.config_dir.join("tracing.cfg");
-
-    let tracing_cfg = load_tracing_config(&tracing_cfg_path)?;
+    let tracing_cfg = load_effective_config(config)?;
 
     // Ensure log directory exists
     fs::create_dir_all(&config.log_dir)
@@ -98,27 +128,15 @@ pub fn init(config: &ArcellaConfig) -> ArcellaResult<Option<tracing_appender::no
         LOG_BUFFER.set(buffer).map_err(|_| ArcellaError::Internal("LOG_BUFFER already set".into()))?;
     }
 
-    // Build filter directives
-    let mut directives = vec![format!("arcella={}", tracing_cfg.default_level)];
-
-    // Override per-module levels
-    for (target, level) in &tracing_cfg.modules {
-        directives.push(format!("{}={}", target, level));
-    }
-
-    let filter = directives.join(",");
-
-    let env_filter = EnvFilter::try_new(filter)
-        .map_err(|e| ArcellaError::Config(format!("invalid log filter: {}", e)))?;
-
+    let (filter_layer, filter_handle) = reload::Layer::new(build_filter(&tracing_cfg)?);
 
     let mut layers = Vec::new();
 
     // 1. File layer
     if tracing_cfg.file {
 
-        // Use `never` rolling (single file: arcella.log)
-        let file_appender = tracing_appender::rolling::never(&config.log_dir, "arcella.log");
+        // Use `never` rolling (single file, `log.filename`)
+        let file_appender = tracing_appender::rolling::never(&config.log_dir, config.get_str("arcella.log.filename")?);
         let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
 
         let file_layer = if tracing_cfg.structured {
@@ -153,17 +171,191 @@ pub fn init(config: &ArcellaConfig) -> ArcellaResult<Option<tracing_appender::no
     }    
 
     let subscriber = tracing_subscriber::registry()
-        .with(layers)
-        .with(env_filter);
+        .with(filter_layer)
+        .with(layers);
 
     subscriber
         .try_init()
         .map_err(|e| ArcellaError::Internal(format!("failed to init tracing: {}", e)))?;
 
+    let _ = LOG_RELOAD.set(LogReload { handle: filter_handle, config: Mutex::new(tracing_cfg) });
 
     Ok(file_guard)
 }
 
+/// Diagnostics reported before the logging system is initialized.
+#[derive(Debug, Default)]
+pub struct InitLog {
+    entries: Vec<(OffsetDateTime, Level, String)>,
+}
+
+impl InitLog {
+    /// Records a diagnostic message.
+    pub fn push(&mut self, level: Level, message: impl Into<String>) {
+        self.entries.push((OffsetDateTime::now_utc(), level, message.into()));
+    }
+
+    /// Records the warnings collected while loading the configuration.
+    pub fn push_config_warnings(&mut self, warnings: Vec<ConfigLoadWarning>) {
+        for warning in warnings {
+            self.push(Level::WARN, format!("Configuration: {}", warning));
+        }
+    }
+
+    /// Emits the collected diagnostics through `tracing`; call after [`init`].
+    pub fn replay(self) {
+        for (_, level, message) in self.entries {
+            match level {
+                Level::ERROR => tracing::error!("{}", message),
+                Level::WARN => tracing::warn!("{}", message),
+                Level::INFO => tracing::info!("{}", message),
+                Level::DEBUG => tracing::debug!("{}", message),
+                Level::TRACE => tracing::trace!("{}", message),
+            }
+        }
+    }
+
+    /// Writes the collected diagnostics to stderr and, if `log_dir` is known, to
+    /// `log_dir/init.log`; used when the daemon fails before logging is up.
+    ///
+    /// Failing to write `init.log` is reported on stderr as well.
+    pub fn dump(&self, log_dir: Option<&Path>) {
+        for (_, level, message) in &self.entries {
+            eprintln!("{}: {}", level, message);
+        }
+
+        if let Some(log_dir) = log_dir {
+            let path = log_dir.join(INIT_LOG_FILENAME);
+            if let Err(e) = self.write_to(&path) {
+                eprintln!("Failed to write {:?}: {}", path, e);
+            }
+        }
+    }
+
+    /// Replaces `path` with the collected diagnostics, one line per entry.
+    fn write_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(path)?;
+        for (time, level, message) in &self.entries {
+            let time = time.format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_else(|_| "<invalid-timestamp>".to_string());
+            writeln!(file, "{} {} {}", time, level, message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Re-reads `tracing.cfg` and applies the changed log levels.
+///
+/// # Arguments
+///
+/// * `config` — the reloaded Arcella configuration; `tracing.cfg` is read from its
+///   `log.tracing_config`.
+///
+/// # Returns
+///
+/// The changed settings: `default_level` (unless it is set on the command line)
+/// and `modules` are applied immediately,
+/// while the output channels and the ALME buffer size require a restart.
+///
+/// # Errors
+///
+/// Returns an error if logging is not initialized, or if `tracing.cfg` is
+/// malformed; the current log levels are kept in that case.
+pub fn reload(config: &ArcellaConfig) -> ArcellaResult<ConfigDiff> {
+    let state = LOG_RELOAD.get()
+        .ok_or_else(|| ArcellaError::Internal("logging is not initialized".into()))?;
+
+    let new_cfg = load_effective_config(config)?;
+    let filter = build_filter(&new_cfg)?;
+
+    let mut current = state.config.lock().expect("Log config poisoned");
+    let mut diff = ConfigDiff::default();
+
+    if current.default_level != new_cfg.default_level {
+        diff.hot.push("log.default_level");
+    }
+    if current.modules != new_cfg.modules {
+        diff.hot.push("log.modules");
+    }
+    if !diff.hot.is_empty() {
+        state.handle
+            .reload(filter)
+            .map_err(|e| ArcellaError::Internal(format!("failed to reload log filter: {}", e)))?;
+        current.default_level = new_cfg.default_level;
+        current.modules = new_cfg.modules;
+    }
+
+    if current.structured != new_cfg.structured {
+        diff.restart.push("log.structured");
+    }
+    if current.stderr != new_cfg.stderr {
+        diff.restart.push("log.stderr");
+    }
+    if current.file != new_cfg.file {
+        diff.restart.push("log.file");
+    }
+    if current.alme_buffer_size != new_cfg.alme_buffer_size {
+        diff.restart.push("log.alme_buffer_size");
+    }
+
+    Ok(diff)
+}
+
+/// Loads `tracing.cfg` and applies the log levels of the Arcella configuration on top.
+///
+/// `arcella.log.default_level` and `arcella.log.modules.*` override the levels of
+/// `tracing.cfg` when they are set in a configuration file, the environment or with
+/// `--set`; the built-in defaults do not. `--log-level` overrides the global level last.
+///
+/// # Errors
+///
+/// Returns an error if `tracing.cfg` is malformed or a configured level is invalid.
+fn load_effective_config(config: &ArcellaConfig) -> ArcellaResult<TracingConfig> {
+    let mut tracing_cfg = load_tracing_config(&config.get_path("arcella.log.tracing_config")?)?;
+
+    for (key, (value, layer)) in &config.sources.values {
+        // Layer 0 holds the built-in defaults
+        if *layer == 0 {
+            continue;
+        }
+        let target = match key.strip_prefix(MODULE_LEVEL_PREFIX) {
+            Some(target) => Some(target),
+            None if key == DEFAULT_LEVEL_KEY => None,
+            None => continue,
+        };
+        let level = match value {
+            TomlValue::String(level) => level.parse::<LevelFilter>()
+                .map_err(|e| ArcellaError::Config(format!("{}: {}", key, e)))?,
+            _ => return Err(ArcellaError::Config(format!("{} must be a string", key))),
+        };
+        match target {
+            Some(target) => { tracing_cfg.modules.insert(target.to_string(), level); }
+            None => tracing_cfg.default_level = level,
+        }
+    }
+
+    if let Some(level) = config.log_level {
+        tracing_cfg.default_level = level;
+    }
+    Ok(tracing_cfg)
+}
+
+/// Builds the log filter: `default_level` for the `arcella` targets, overridden
+/// by the per-module levels.
+fn build_filter(tracing_cfg: &TracingConfig) -> ArcellaResult<EnvFilter> {
+    let mut directives = vec![format!("arcella={}", tracing_cfg.default_level)];
+
+    for (target, level) in &tracing_cfg.modules {
+        directives.push(format!("{}={}", target, level));
+    }
+
+    EnvFilter::try_new(directives.join(","))
+        .map_err(|e| ArcellaError::Config(format!("invalid log filter: {}", e)))
+}
+
 /// Loads tracing configuration from a TOML file.
 ///
 /// If the file does not exist, default settings are returned.
@@ -395,4 +587,42 @@ impl tracing::field::Visit for EventVisitor {
             self.fields.push(format!("{}={:?}", field.name(), value));
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_config_levels_override_tracing_cfg() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = ArcellaConfig::for_tests(temp_dir.path());
+        let tracing_cfg_path = config.get_path("arcella.log.tracing_config").unwrap();
+        fs::create_dir_all(tracing_cfg_path.parent().unwrap()).unwrap();
+        fs::write(
+            &tracing_cfg_path,
+            "default_level = \"warn\"\n[modules]\n\"arcella::runtime\" = \"info\"\n",
+        ).unwrap();
+
+        let level = |s: &str| TomlValue::String(s.to_string());
+        let values = &mut config.sources.values;
+        values.insert("arcella.log.default_level".into(), (level("debug"), 2));
+        values.insert("arcella.log.modules.arcella::alme".into(), (level("trace"), 0));
+        values.insert("arcella.log.modules.arcella::cache".into(), (level("error"), 1));
+
+        let tracing_cfg = load_effective_config(&config).unwrap();
+        assert_eq!(tracing_cfg.default_level, LevelFilter::DEBUG);
+        assert_eq!(tracing_cfg.modules.get("arcella::runtime"), Some(&LevelFilter::INFO));
+        assert_eq!(tracing_cfg.modules.get("arcella::cache"), Some(&LevelFilter::ERROR));
+        // Built-in defaults do not override tracing.cfg
+        assert_eq!(tracing_cfg.modules.get("arcella::alme"), None);
+
+        let filter = build_filter(&tracing_cfg).unwrap().to_string();
+        assert!(filter.contains("arcella=debug"), "{}", filter);
+        assert!(filter.contains("arcella::cache=error"), "{}", filter);
+
+        config.log_level = Some(LevelFilter::TRACE);
+        assert_eq!(load_effective_config(&config).unwrap().default_level, LevelFilter::TRACE);
+    }
+}
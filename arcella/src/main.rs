@@ -8,10 +8,18 @@
 // except according to those terms.
 
 use clap::Parser;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::os::unix::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::sync::Arc;
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::sync::RwLock;
-//use wasmtime::*;
-//use wasmtime_wasi::{p1, WasiCtxBuilder};
+use tokio::task::JoinHandle;
+use tracing::Level;
+use tracing_subscriber::filter::LevelFilter;
 use wat;
 
 mod alme;
@@ -20,100 +28,416 @@ mod config;
 mod storage;
 mod cache;
 mod manifest;
+mod reconciler;
 mod error;
+mod lock;
 mod log;
+mod worker;
 
 use error::{ArcellaError, Result as ArcellaResult};
 
 /// Arcella: Modular WebAssembly Runtime
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-struct Cli {}
+struct Cli {
+    /// Base directory (default: $ARCELLA_HOME, the installation directory or ~/.arcella)
+    #[arg(long, value_name = "DIR")]
+    base_dir: Option<PathBuf>,
+
+    /// Main configuration file (default: <base-dir>/config/arcella.toml)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Path of the ALME socket (overrides alme.socket.path)
+    #[arg(long, value_name = "PATH")]
+    socket: Option<PathBuf>,
+
+    /// Global log level: trace, debug, info, warn, error or off (overrides tracing.cfg)
+    #[arg(long, value_name = "LEVEL")]
+    log_level: Option<LevelFilter>,
+
+    /// Override a setting, e.g. arcella.cache.dir=/var/cache/arcella (repeatable;
+    /// takes precedence over ARCELLA__* environment variables and the config files)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = config::SettingOverride::from_arg)]
+    settings: Vec<config::SettingOverride>,
+
+    /// Stay attached to the terminal (default)
+    #[arg(long, conflicts_with = "daemonize")]
+    foreground: bool,
+
+    /// Detach from the terminal and run in the background
+    #[arg(long)]
+    daemonize: bool,
+
+    /// Load and validate the configuration, then exit
+    #[arg(long, conflicts_with = "daemonize")]
+    check_config: bool,
+}
+
+impl Cli {
+    fn overrides(&self) -> config::ConfigOverrides {
+        config::ConfigOverrides {
+            base_dir: self.base_dir.clone(),
+            config_file: self.config.clone(),
+            socket_path: self.socket.clone(),
+            log_level: self.log_level,
+            settings: self.settings.clone(),
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() -> ArcellaResult<()> {
 
+    let cli = Cli::parse();
+    if cli.daemonize && !cli.foreground {
+        return daemonize();
+    }
+
     // 1. Load configuration (e.g., paths, runtime options)
-    let _ = Cli::parse(); 
-    let (config_data, warning) = config::load().await?;
-    let config = Arc::new(config_data);
+    let overrides = cli.overrides();
+    if cli.check_config {
+        return check_config(&overrides).await;
+    }
+    // Diagnostics are buffered until logging is up, and dumped if startup fails
+    let mut init_log = log::InitLog::default();
+    let mut warnings = Vec::new();
+    let loaded = config::load(&overrides, &mut warnings).await;
+    init_log.push_config_warnings(warnings);
+    let config = match loaded {
+        Ok(config_data) => Arc::new(config_data),
+        Err(e) => {
+            init_log.push(Level::ERROR, format!("Failed to load configuration: {}", e));
+            init_log.dump(config::fallback_log_dir(&overrides).await.as_deref());
+            std::process::exit(1);
+        }
+    };
+
+    // 2. Make sure no other daemon runs against the same base_dir
+    let instance_lock = match lock::InstanceLock::acquire(&config.base_dir) {
+        Ok(instance_lock) => instance_lock,
+        Err(e) => {
+            init_log.push(Level::ERROR, e.to_string());
+            // The log directory belongs to the daemon that holds the lock
+            let log_dir = (!matches!(e, ArcellaError::AlreadyRunning { .. })).then_some(config.log_dir.as_path());
+            init_log.dump(log_dir);
+            std::process::exit(1);
+        }
+    };
 
-    // 2. Initialize logging (should be the first side effect)
-    let _log_guard = log::init(&config)?;
+    // 3. Initialize logging
+    let _log_guard = match log::init(&config) {
+        Ok(guard) => guard,
+        Err(e) => {
+            init_log.push(Level::ERROR, format!("Failed to initialize logging: {}", e));
+            init_log.dump(Some(&config.log_dir));
+            std::process::exit(1);
+        }
+    };
     tracing::info!("Starting up (v{})", env!("CARGO_PKG_VERSION"));
+    init_log.replay();
 
-    // 3. Initialize core subsystems: storage and module cache
+    // 4. Initialize core subsystems: storage and module cache
     let storage = Arc::new(storage::StorageManager::new(&config).await?);
     tracing::debug!("Initialize storage");
     let cache = Arc::new(cache::ModuleCache::new(&config).await?);
     tracing::debug!("Initialize cache");
 
-    let runtime = Arc::new(RwLock::new(
-        runtime::ArcellaRuntime::new(config.clone(), storage.clone(), cache.clone()).await?,
-    ));
+    // Detach stderr from a `--daemonize` parent before worker processes can inherit it
+    let mut ready_notifier = ReadyNotifier::take_stderr();
+
+    let runtime = runtime::ArcellaRuntime::new(config.clone(), storage.clone(), cache.clone()).await;
+    let runtime = match runtime {
+        Ok(runtime) => Arc::new(RwLock::new(runtime)),
+        Err(e) => {
+            tracing::error!("Failed to start the runtime: {}", e);
+            if let Some(notifier) = ready_notifier.take() {
+                notifier.failed(&format!("Failed to start the runtime: {}", e));
+            }
+            return Err(e);
+        }
+    };
     tracing::debug!("Initialize core runtime");
 
+    let supervisor = runtime::spawn_supervisor(runtime.clone());
+    let mut reconciler = spawn_reconciler(&runtime, &config);
+    let mut integrity_monitor = spawn_integrity_monitor(&runtime, &config);
+    let shutdown_request = runtime.read().await.shutdown_requested();
 
-    let alme_handle = alme::start(runtime.clone()).await?;
+    let alme_handle = match alme::start(runtime.clone(), &instance_lock).await {
+        Ok(alme_handle) => alme_handle,
+        Err(e) => {
+            tracing::error!("Failed to start the ALME server: {}", e);
+            if let Some(notifier) = ready_notifier.take() {
+                notifier.failed(&format!("Failed to start the ALME server: {}", e));
+            }
+            return Err(e);
+        }
+    };
     tracing::info!("Starting ALME server");
+    if let Some(notifier) = ready_notifier {
+        notifier.ready();
+    }
 
-    tokio::signal::ctrl_c().await?;
-    tracing::info!("Received Ctrl+C, shutting down...");
+    // 5. Serve until asked to stop: SIGINT/SIGTERM shut down, SIGHUP reloads the config
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sighup = signal(SignalKind::hangup())?;
+    loop {
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result?;
+                tracing::info!("Received Ctrl+C, shutting down...");
+                break;
+            }
+            _ = sigterm.recv() => {
+                tracing::info!("Received SIGTERM, shutting down...");
+                break;
+            }
+            _ = shutdown_request.notified() => {
+                tracing::info!("Shutting down on request of the runtime...");
+                break;
+            }
+            _ = sighup.recv() => {
+                tracing::info!("Received SIGHUP, reloading configuration");
+                reload_config(&overrides, &runtime, &cache, &mut reconciler, &mut integrity_monitor).await;
+            }
+        }
+    }
 
+    supervisor.abort();
+    if let Some(reconciler) = reconciler {
+        reconciler.abort();
+    }
+    if let Some(integrity_monitor) = integrity_monitor {
+        integrity_monitor.abort();
+    }
     runtime.write().await.shutdown().await?;
     alme_handle.shutdown().await?;
 
     tracing::info!("Shutting down");
-        
-    // Configure the engine
-    /*let mut config = Config::default();
-    config.wasm_backtrace_details(WasmBacktraceDetails::Enable);
-    config.wasm_multi_memory(false);
-    config.wasm_threads(false);
-    config.consume_fuel(true);
-
-    // Initialize the engine
-    let engine = Engine::new(&config)?;
-    let mut linker: Linker<p1::WasiP1Ctx> = Linker::new(&engine);
-
-    p1::add_to_linker_sync(&mut linker, |t| t)?;
-    let wasi_ctx = WasiCtxBuilder::new()
-        .inherit_stderr()
-        .inherit_stdout()
-        .build_p1();
-
-    let mut store = Store::new(&engine, wasi_ctx);
-    let _ = store.set_fuel(1_000_000);
-
-    // Load the module
-
-    let module_bytes = load_module_bytes(&cli.module)?;
-    let module = Module::from_binary(&engine, &module_bytes)
-        .map_err(|e| anyhow!("Failed to compile module: {}", e))?;
-
-    linker.module(&mut store, "default", &module)?;
-
-    match linker.get_default(&mut store, "default") {
-        Ok(func) => {
-            if let Err(e) = func.typed::<(), ()>(&store)?.call(&mut store, ()) {
-                if e.is::<Trap>() {
-                    eprintln!("WASM module exited with trap: {}", e);
-                } else {
-                    return Err(e.into());
-                }
-            }
-        }
-        Err(_) => {
-            eprintln!("No default function found — nothing to run.");
-        }
-    }*/
 
+    drop(instance_lock);
     drop(_log_guard);
 
     Ok(())
     
 }
 
+/// Environment variable that tells a daemon started by [`daemonize`] to report
+/// the outcome of its startup on stderr.
+const READY_NOTIFY_ENV: &str = "ARCELLA_READY_NOTIFY";
+
+/// Line a daemon started by [`daemonize`] writes once it serves ALME requests.
+const READY_MARKER: &str = "arcella: ready";
+
+/// Restarts the daemon as a background process detached from the terminal.
+///
+/// The daemon is started again with the same arguments in foreground mode, with
+/// stdin and stdout closed and in its own process group, so that it does not
+/// receive the terminal's signals. Its stderr is read until it reports that it
+/// serves ALME requests; if it exits before that, its output is printed and this
+/// process exits with the daemon's exit code.
+fn daemonize() -> ArcellaResult<()> {
+    let exe = std::env::current_exe()?;
+    let args = std::env::args_os().skip(1).filter(|arg| arg != "--daemonize");
+    let (reader, writer) = std::io::pipe()?;
+
+    // The command, and with it the write end of the pipe, is dropped right away,
+    // so the pipe closes when the daemon exits.
+    let mut child = Command::new(exe)
+        .args(args)
+        .arg("--foreground")
+        .env(READY_NOTIFY_ENV, "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(writer)
+        .process_group(0)
+        .spawn()?;
+
+    let mut output = String::new();
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        if line == READY_MARKER {
+            println!("Arcella daemon started in the background (pid {})", child.id());
+            return Ok(());
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    let status = child.wait()?;
+    eprint!("{}", output);
+    eprintln!("Arcella daemon failed to start ({})", status);
+    // A daemon that exits without becoming ready has failed, even with status 0.
+    std::process::exit(status.code().filter(|code| *code != 0).unwrap_or(1));
+}
+
+/// Reports the outcome of the startup to the process that ran [`daemonize`].
+struct ReadyNotifier(File);
+
+impl ReadyNotifier {
+    /// Takes over stderr as the channel to the parent process and points stderr
+    /// at `/dev/null`, so that neither later output nor the worker processes,
+    /// which inherit stderr, write into the pipe once the parent has exited.
+    ///
+    /// Returns `None` if the daemon was not started by [`daemonize`].
+    fn take_stderr() -> Option<Self> {
+        std::env::var_os(READY_NOTIFY_ENV)?;
+
+        let dev_null = File::options().write(true).open("/dev/null").ok()?;
+        // SAFETY: only file descriptors owned by this process are duplicated; the
+        // duplicate of stderr is owned by the returned `File` alone.
+        unsafe {
+            let fd = libc::fcntl(libc::STDERR_FILENO, libc::F_DUPFD_CLOEXEC, 3);
+            if fd < 0 {
+                return None;
+            }
+            libc::dup2(dev_null.as_raw_fd(), libc::STDERR_FILENO);
+            Some(Self(File::from_raw_fd(fd)))
+        }
+    }
+
+    /// Tells the parent process that the daemon has started.
+    fn ready(mut self) {
+        let _ = writeln!(self.0, "{}", READY_MARKER);
+    }
+
+    /// Tells the parent process why the daemon failed to start.
+    fn failed(mut self, error: &str) {
+        let _ = writeln!(self.0, "{}", error);
+    }
+}
+
+/// Loads the configuration and reports whether it is valid, without starting the daemon.
+///
+/// Prints every load warning, then every final setting with the file and layer
+/// that set it.
+async fn check_config(overrides: &config::ConfigOverrides) -> ArcellaResult<()> {
+    let mut warnings = Vec::new();
+    let loaded = config::load(overrides, &mut warnings).await;
+    for warning in &warnings {
+        println!("warning: {}", warning);
+    }
+    match loaded {
+        Ok(config) => {
+            for (layer, file) in config.sources.files.iter().enumerate() {
+                println!("layer {}: {}", layer, file.display());
+            }
+            for entry in config.sources.explain("") {
+                println!(
+                    "{} = {}  # layer {}: {}",
+                    entry.key,
+                    config::format_value(entry.value),
+                    entry.layer,
+                    entry.file.display()
+                );
+            }
+            println!("Configuration in {:?} is valid ({} warning(s))", config.base_dir, warnings.len());
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("Configuration is invalid: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Starts the deployments reconciler, unless it is disabled in the configuration.
+fn spawn_reconciler(
+    runtime: &Arc<RwLock<runtime::ArcellaRuntime>>,
+    config: &config::ArcellaConfig,
+) -> Option<JoinHandle<()>> {
+    config.reconcile_interval.map(|interval| {
+        reconciler::spawn_reconciler(runtime.clone(), config.deployments_dir.clone(), interval)
+    })
+}
+
+/// Starts the periodic integrity check of the configuration files, unless it is
+/// disabled in the configuration.
+fn spawn_integrity_monitor(
+    runtime: &Arc<RwLock<runtime::ArcellaRuntime>>,
+    config: &config::ArcellaConfig,
+) -> Option<JoinHandle<()>> {
+    config.integrity_check_interval.map(|interval| {
+        runtime::spawn_integrity_monitor(runtime.clone(), interval)
+    })
+}
+
+/// Reloads the configuration and applies the settings that can change at runtime.
+///
+/// The new configuration is compared with the running one: log levels, the cache
+/// size limit, the reconciler, the integrity check settings and the ALME request
+/// limits are applied, other
+/// changed settings are reported as requiring a restart. The reloaded files become
+/// the new integrity baseline. If the configuration cannot be loaded, the running
+/// one is kept.
+async fn reload_config(
+    overrides: &config::ConfigOverrides,
+    runtime: &Arc<RwLock<runtime::ArcellaRuntime>>,
+    cache: &cache::ModuleCache,
+    reconciler: &mut Option<JoinHandle<()>>,
+    integrity_monitor: &mut Option<JoinHandle<()>>,
+) {
+    let mut warnings = Vec::new();
+    let loaded = config::load(overrides, &mut warnings).await;
+    for warning in &warnings {
+        tracing::warn!("Configuration: {}", warning);
+    }
+    let new_config = match loaded {
+        Ok(new_config) => new_config,
+        Err(e) => {
+            tracing::error!("Failed to reload configuration, keeping the current one: {}", e);
+            return;
+        }
+    };
+
+    let current = runtime.read().await.config.clone();
+    let mut diff = current.diff(&new_config);
+    match log::reload(&new_config) {
+        Ok(log_diff) => diff.extend(log_diff),
+        Err(e) => tracing::error!("Failed to reload log settings: {}", e),
+    }
+
+    let applied = Arc::new(current.with_hot_settings(&new_config));
+    {
+        let mut runtime_guard = runtime.write().await;
+        runtime_guard.config = applied.clone();
+        if let Err(e) = runtime_guard.reset_integrity() {
+            tracing::error!("Failed to reset the configuration integrity check: {}", e);
+        }
+    }
+
+    if diff.is_empty() {
+        tracing::info!("Configuration reloaded, no changes");
+        return;
+    }
+
+    cache.set_max_size(applied.cache_max_size);
+
+    if applied.reconcile_interval != current.reconcile_interval
+        || applied.deployments_dir != current.deployments_dir
+    {
+        if let Some(reconciler) = reconciler.take() {
+            reconciler.abort();
+        }
+        *reconciler = spawn_reconciler(runtime, &applied);
+    }
+
+    if applied.integrity_check_interval != current.integrity_check_interval {
+        if let Some(integrity_monitor) = integrity_monitor.take() {
+            integrity_monitor.abort();
+        }
+        *integrity_monitor = spawn_integrity_monitor(runtime, &applied);
+    }
+
+    for key in &diff.hot {
+        tracing::info!("Applied new value of '{}'", key);
+    }
+    for key in &diff.restart {
+        tracing::warn!("Setting '{}' changed, restart the daemon to apply it", key);
+    }
+}
+
 /*fn load_module_bytes(path: &PathBuf) -> ArcellaResult<Vec<u8>> {
     let extension = path
         .extension()
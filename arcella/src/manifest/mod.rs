@@ -27,6 +27,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
+use std::time::Duration;
 use wasmtime::{
     Engine,
 };
@@ -113,6 +114,10 @@ pub struct DeploymentTemplate {
     /// Resource limits and requirements
     #[serde(default)]
     pub resources: ResourceRequirements,
+
+    /// What to do when an instance stops
+    #[serde(default)]
+    pub restart: RestartConfig,
 }
 
 impl Default for DeploymentTemplate {
@@ -125,6 +130,7 @@ impl Default for DeploymentTemplate {
             group: None,
             startup: StartupConfig::default(),
             resources: ResourceRequirements::default(),
+            restart: RestartConfig::default(),
         }
     }
 }
@@ -175,6 +181,8 @@ impl DeploymentTemplate {
             }
         }
 
+        self.restart.validate()?;
+
         Ok(())
     }
 }
@@ -270,6 +278,7 @@ impl DeploymentSpec {
         let r#async = self.overrides.r#async.unwrap_or(base.r#async);
         let startup = self.overrides.startup.clone().unwrap_or(base.startup);
         let resources = self.overrides.resources.clone().unwrap_or(base.resources);
+        let restart = self.overrides.restart.clone().unwrap_or(base.restart);
 
         let deployment = FullDeployment {
             module_id: self.module_id.clone(),
@@ -280,6 +289,7 @@ impl DeploymentSpec {
             r#async,
             startup,
             resources,
+            restart,
         };
 
         deployment.validate()?;
@@ -305,6 +315,9 @@ pub struct DeploymentOverrides {
     
     /// Override resource requirements
     pub resources: Option<ResourceRequirements>,
+
+    /// Override restart policy
+    pub restart: Option<RestartConfig>,
 }
 
 /// Complete deployment configuration ready for execution
@@ -318,6 +331,7 @@ pub struct FullDeployment {
     pub r#async: bool,
     pub startup: StartupConfig,
     pub resources: ResourceRequirements,
+    pub restart: RestartConfig,
 }
 
 impl FullDeployment {
@@ -334,6 +348,8 @@ impl FullDeployment {
             ).into());
         }
 
+        self.restart.validate()?;
+
         Ok(())
     }
 }
@@ -368,8 +384,8 @@ pub enum IsolationMode {
 
 /// Configuration for module lifecycle entry points.
 ///
-/// These hooks will be used in future versions (v0.3+) to invoke specific
-/// WIT functions when a module is started or stopped.
+/// The entry point is called when an instance starts; the shutdown hook is
+/// called on a resident component when the daemon shuts down gracefully.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct StartupConfig {
     /// Optional name of the WIT function to call on module start.
@@ -385,11 +401,18 @@ pub struct StartupConfig {
     #[serde(default)]
     pub shutdown: Option<String>,
 
-    /// Timeout in seconds for startup (0 = no timeout)
+    /// Timeout in seconds for startup and for the shutdown hook (0 = no timeout)
     #[serde(default)]
     pub timeout_seconds: Option<u32>,
 }
 
+impl StartupConfig {
+    /// Returns the startup timeout, or `None` if startup is not bounded.
+    pub fn timeout(&self) -> Option<Duration> {
+        seconds_to_timeout(self.timeout_seconds)
+    }
+}
+
 /// Resource requirements and limits
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct ResourceRequirements {
@@ -404,9 +427,18 @@ pub struct ResourceRequirements {
     /// CPU shares (relative weight)
     #[serde(default)]
     pub cpu_shares: Option<u32>,
+
+    /// Wall-clock limit in seconds for a single call into the module (0 = no timeout)
+    #[serde(default)]
+    pub call_timeout_seconds: Option<u32>,
 }
 
 impl ResourceRequirements {
+    /// Returns the per-call timeout, or `None` if calls are not bounded.
+    pub fn call_timeout(&self) -> Option<Duration> {
+        seconds_to_timeout(self.call_timeout_seconds)
+    }
+
     pub fn validate(&self) -> ArcellaResult<()> {
         if let Some(mem) = self.memory_mb {
             if mem == 0 {
@@ -426,6 +458,103 @@ impl ResourceRequirements {
     }
 }
 
+/// When a stopped instance is started again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Never restart; the final state stays visible in `instance:list`.
+    #[default]
+    Never,
+
+    /// Restart instances that failed or exited with a non-zero code.
+    OnFailure,
+
+    /// Restart instances whenever they stop.
+    Always,
+}
+
+/// Restart policy with retry limit and exponential backoff.
+///
+/// The n-th restart of an instance (counting from 0) is delayed by
+/// `backoff_seconds * 2^n`, capped at `max_backoff_seconds`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestartConfig {
+    /// When to restart
+    #[serde(default)]
+    pub policy: RestartPolicy,
+
+    /// Maximum number of restarts per instance (0 = unlimited)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Delay before the first restart, in seconds
+    #[serde(default = "default_backoff_seconds")]
+    pub backoff_seconds: u32,
+
+    /// Upper bound of the restart delay, in seconds
+    #[serde(default = "default_max_backoff_seconds")]
+    pub max_backoff_seconds: u32,
+}
+
+fn default_max_retries() -> u32 { 5 }
+fn default_backoff_seconds() -> u32 { 1 }
+fn default_max_backoff_seconds() -> u32 { 60 }
+
+impl Default for RestartConfig {
+    fn default() -> Self {
+        Self {
+            policy: RestartPolicy::default(),
+            max_retries: default_max_retries(),
+            backoff_seconds: default_backoff_seconds(),
+            max_backoff_seconds: default_max_backoff_seconds(),
+        }
+    }
+}
+
+impl RestartConfig {
+    pub fn validate(&self) -> ArcellaResult<()> {
+        if self.max_backoff_seconds < self.backoff_seconds {
+            return Err(ArcellaWasmtimeError::Manifest(
+                "max_backoff_seconds must not be less than backoff_seconds".into()
+            ).into());
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if an instance that stopped with the given outcome should be
+    /// restarted after `restarts` previous restarts.
+    ///
+    /// # Arguments
+    ///
+    /// * `exit_code` — Exit code of the instance, or `None` if it failed.
+    /// * `restarts` — Number of times the instance was already restarted.
+    pub fn should_restart(&self, exit_code: Option<i32>, restarts: u32) -> bool {
+        if self.max_retries != 0 && restarts >= self.max_retries {
+            return false;
+        }
+
+        match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => exit_code != Some(0),
+            RestartPolicy::Always => true,
+        }
+    }
+
+    /// Returns the delay before restarting an instance for the `restarts + 1`-th time.
+    pub fn backoff(&self, restarts: u32) -> Duration {
+        let factor = 1u64.checked_shl(restarts).unwrap_or(u64::MAX);
+        let seconds = u64::from(self.backoff_seconds)
+            .saturating_mul(factor)
+            .min(u64::from(self.max_backoff_seconds));
+        Duration::from_secs(seconds)
+    }
+}
+
+/// Converts an optional timeout in seconds, where `0` means "no timeout".
+fn seconds_to_timeout(seconds: Option<u32>) -> Option<Duration> {
+    seconds.filter(|s| *s > 0).map(|s| Duration::from_secs(u64::from(s)))
+}
+
 // ========================
 // 5. BUNDLE MANAGEMENT
 // ========================
@@ -466,6 +595,41 @@ impl ComponentBundle {
 
     }
 
+    /// Loads the bundle of an installed module.
+    ///
+    /// The manifests are read from `bundle_dir` (`modules_dir/<id>/`), while the
+    /// binary is the content-addressed blob at `wasm_path`. Since the blob's file
+    /// name carries no identity, a component without `component.toml` takes its
+    /// `name@version` from `module_id`.
+    pub fn from_stored(
+        engine: &Engine,
+        bundle_dir: &Path,
+        module_id: &str,
+        wasm_path: &Path,
+    ) -> ArcellaResult<Self> {
+        let component = match load_component_manifest_from_toml(&bundle_dir.join("component.toml"))? {
+            Some(manifest) => manifest,
+            None => manifest::component_manifest_from_wasm_with_id(engine, wasm_path, module_id)?,
+        };
+
+        let template_path = bundle_dir.join("deployment-template.toml");
+        let template = if template_path.exists() {
+            Some(DeploymentTemplate::from_file(&template_path)?)
+        } else {
+            None
+        };
+
+        let bundle = Self {
+            component,
+            template,
+            wasm_path: wasm_path.to_path_buf(),
+        };
+
+        bundle.validate()?;
+
+        Ok(bundle)
+    }
+
     /// Validates the entire bundle for consistency
     pub fn validate(&self) -> ArcellaResult<()> {
         self.component.validate()?;
@@ -569,6 +733,7 @@ mod tests {
             group: Some("default".to_string()),
             startup: StartupConfig::default(),
             resources: ResourceRequirements::default(),
+            restart: RestartConfig::default(),
         };
 
         let spec = DeploymentSpec {
@@ -595,6 +760,7 @@ mod tests {
             r#async: true,
             startup: StartupConfig::default(),
             resources: ResourceRequirements::default(),
+            restart: RestartConfig::default(),
         };
 
         assert!(deployment.validate().is_err());
@@ -643,5 +809,42 @@ mod tests {
         let result = load_component_manifest_from_toml(fake_path).unwrap();
         assert!(result.is_none());
     }       
+
+    #[test]
+    fn test_restart_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("deployment-template.toml");
+        fs::write(&path, r#"
+            [deployment]
+            isolation = "worker"
+            trusted = false
+            async = false
+
+            [deployment.restart]
+            policy = "on-failure"
+            max_retries = 3
+            backoff_seconds = 2
+            max_backoff_seconds = 10
+        "#).unwrap();
+
+        let restart = DeploymentTemplate::from_file(&path).unwrap().restart;
+        assert_eq!(restart.policy, RestartPolicy::OnFailure);
+        assert!(restart.should_restart(None, 0));
+        assert!(restart.should_restart(Some(1), 2));
+        assert!(!restart.should_restart(Some(0), 0));
+        assert!(!restart.should_restart(Some(1), 3));
+
+        assert_eq!(restart.backoff(0).as_secs(), 2);
+        assert_eq!(restart.backoff(1).as_secs(), 4);
+        assert_eq!(restart.backoff(3).as_secs(), 10);
+        assert_eq!(restart.backoff(100).as_secs(), 10);
+
+        let always = RestartConfig { policy: RestartPolicy::Always, max_retries: 0, ..Default::default() };
+        assert!(always.should_restart(Some(0), 1000));
+        assert!(!RestartConfig::default().should_restart(None, 0));
+
+        let invalid = RestartConfig { backoff_seconds: 10, max_backoff_seconds: 1, ..Default::default() };
+        assert!(invalid.validate().is_err());
+    }
     
 }
\ No newline at end of file
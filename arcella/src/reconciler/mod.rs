@@ -0,0 +1,366 @@
+// arcella/arcella/src/reconciler/mod.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Declarative deployments from `*.deployment.toml` files.
+//!
+//! The reconciler treats the deployments directory (`arcella.deployments.dir`) as
+//! the desired state: every `*.deployment.toml` file holds one [`DeploymentSpec`].
+//! A pass compares the files with the runtime's deployments ([`diff`]) and applies
+//! the resulting changes ([`apply`]). Deployments created from files are marked as
+//! managed; only managed deployments are removed when their file disappears, so
+//! deployments applied by hand over ALME are left alone.
+//!
+//! [`spawn_reconciler`] runs a pass every `reconcile_interval_seconds`; the ALME
+//! command `deployment:diff` shows the changes of the next pass without applying them.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::error::{ArcellaError, Result as ArcellaResult};
+use crate::manifest::DeploymentSpec;
+use crate::runtime::{prepare_module, ArcellaRuntime, DeploymentKey};
+
+/// Suffix of the files read by the reconciler.
+const DEPLOYMENT_FILE_SUFFIX: &str = ".deployment.toml";
+
+/// Deployments declared in the deployments directory.
+#[derive(Debug, Default)]
+pub struct DesiredDeployments {
+    /// Valid specs with the file that declares them, sorted by file name.
+    pub specs: Vec<(PathBuf, DeploymentSpec)>,
+
+    /// Files that could not be used, with the reason.
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// A change needed to bring the runtime in line with the deployment files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeploymentChange {
+    /// The deployment does not exist yet.
+    Create { source: PathBuf, spec: DeploymentSpec },
+
+    /// The deployment exists with a different configuration; its instances are restarted.
+    Update { source: PathBuf, spec: DeploymentSpec },
+
+    /// Only the replica count differs.
+    Scale { source: PathBuf, spec: DeploymentSpec, from: u32 },
+
+    /// A deployment applied by hand matches a file and becomes managed by it.
+    Adopt { source: PathBuf, spec: DeploymentSpec },
+
+    /// A managed deployment whose file was removed.
+    Undeploy { key: DeploymentKey },
+}
+
+impl DeploymentChange {
+    /// Returns the key of the affected deployment.
+    pub fn key(&self) -> DeploymentKey {
+        match self {
+            Self::Create { spec, .. }
+            | Self::Update { spec, .. }
+            | Self::Scale { spec, .. }
+            | Self::Adopt { spec, .. } => DeploymentKey::new(&spec.module_id, &spec.group),
+            Self::Undeploy { key } => key.clone(),
+        }
+    }
+
+    /// Returns the name of the change, as shown by `deployment:diff`.
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::Create { .. } => "create",
+            Self::Update { .. } => "update",
+            Self::Scale { .. } => "scale",
+            Self::Adopt { .. } => "adopt",
+            Self::Undeploy { .. } => "undeploy",
+        }
+    }
+}
+
+/// Reads all `*.deployment.toml` files of `dir`.
+///
+/// Files that cannot be parsed, or that declare a deployment already declared by
+/// another file, are reported in [`DesiredDeployments::errors`].
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read.
+pub fn load_desired(dir: &Path) -> ArcellaResult<DesiredDeployments> {
+    let read_dir = std::fs::read_dir(dir)
+        .map_err(|e| ArcellaError::IoWithPath { source: e, path: dir.to_path_buf() })?;
+
+    let mut files: Vec<PathBuf> = read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with(DEPLOYMENT_FILE_SUFFIX))
+        })
+        .collect();
+    files.sort();
+
+    let mut desired = DesiredDeployments::default();
+    let mut declared_by: HashMap<DeploymentKey, PathBuf> = HashMap::new();
+    for path in files {
+        let spec = match DeploymentSpec::from_file(&path) {
+            Ok(spec) => spec,
+            Err(e) => {
+                desired.errors.push((path, e.to_string()));
+                continue;
+            }
+        };
+
+        let key = DeploymentKey::new(&spec.module_id, &spec.group);
+        if let Some(first) = declared_by.get(&key) {
+            let message = format!("Deployment {} is already declared in {:?}", key, first);
+            desired.errors.push((path, message));
+            continue;
+        }
+
+        declared_by.insert(key, path.clone());
+        desired.specs.push((path, spec));
+    }
+
+    Ok(desired)
+}
+
+/// Computes the changes that bring the runtime in line with `desired`.
+///
+/// Stopped deployments stay stopped; their spec is still kept up to date.
+/// If any file could not be read, no deployment is undeployed, since the
+/// unreadable file may be the one declaring it.
+pub fn diff(runtime: &ArcellaRuntime, desired: &DesiredDeployments) -> Vec<DeploymentChange> {
+    let mut changes = Vec::new();
+    let mut declared = HashSet::new();
+
+    for (source, spec) in &desired.specs {
+        let key = DeploymentKey::new(&spec.module_id, &spec.group);
+        let source = source.clone();
+        let spec = spec.clone();
+        declared.insert(key.clone());
+
+        let Ok(current) = runtime.get_deployment(&key) else {
+            changes.push(DeploymentChange::Create { source, spec });
+            continue;
+        };
+
+        if current.spec == spec {
+            if !current.managed {
+                changes.push(DeploymentChange::Adopt { source, spec });
+            }
+        } else if same_except_replicas(&current.spec, &spec) {
+            changes.push(DeploymentChange::Scale { source, spec, from: current.spec.replicas });
+        } else {
+            changes.push(DeploymentChange::Update { source, spec });
+        }
+    }
+
+    if desired.errors.is_empty() {
+        for deployment in runtime.list_deployments() {
+            let key = deployment.key();
+            if deployment.managed && !declared.contains(&key) {
+                changes.push(DeploymentChange::Undeploy { key });
+            }
+        }
+        for saved in runtime.unrestored_deployments() {
+            let key = saved.key();
+            if saved.managed && !declared.contains(&key) {
+                changes.push(DeploymentChange::Undeploy { key });
+            }
+        }
+    }
+
+    changes
+}
+
+/// Returns `true` if two specs differ at most in their replica count.
+fn same_except_replicas(a: &DeploymentSpec, b: &DeploymentSpec) -> bool {
+    DeploymentSpec { replicas: b.replicas, ..a.clone() } == *b
+}
+
+/// Applies changes computed by [`diff`].
+///
+/// Every change is applied independently; a failing change (e.g. the module is
+/// not installed yet) does not prevent the others.
+///
+/// # Returns
+///
+/// The outcome of every change, in order.
+pub fn apply(
+    runtime: &mut ArcellaRuntime,
+    changes: Vec<DeploymentChange>,
+) -> Vec<(DeploymentChange, ArcellaResult<()>)> {
+    changes
+        .into_iter()
+        .map(|change| {
+            let result = match &change {
+                DeploymentChange::Create { spec, .. }
+                | DeploymentChange::Update { spec, .. }
+                | DeploymentChange::Scale { spec, .. }
+                | DeploymentChange::Adopt { spec, .. } => {
+                    runtime.apply_managed_deployment(spec.clone()).map(|_| ())
+                }
+                DeploymentChange::Undeploy { key } => runtime.undeploy(key),
+            };
+            (change, result)
+        })
+        .collect()
+}
+
+/// Runs one reconciliation pass over `dir`.
+///
+/// Failures are logged. To keep the log readable, a failure that repeats on the
+/// next pass is logged at debug level only; `last_errors` carries the failures
+/// of the previous pass.
+async fn reconcile(
+    runtime: &RwLock<ArcellaRuntime>,
+    dir: &Path,
+    last_errors: &mut HashMap<String, String>,
+) {
+    let mut errors = HashMap::new();
+
+    let desired = match load_desired(dir) {
+        Ok(desired) => desired,
+        Err(e) => {
+            errors.insert(dir.display().to_string(), e.to_string());
+            log_errors(&errors, last_errors);
+            *last_errors = errors;
+            return;
+        }
+    };
+    for (path, error) in &desired.errors {
+        errors.insert(path.display().to_string(), error.clone());
+    }
+
+    // Compile the modules to start before taking the write lock
+    let modules: HashSet<String> = desired.specs.iter().map(|(_, spec)| spec.module_id.clone()).collect();
+    for module_id in &modules {
+        prepare_module(runtime, module_id).await;
+    }
+
+    let mut runtime = runtime.write().await;
+    let changes = diff(&runtime, &desired);
+    for (change, result) in apply(&mut runtime, changes) {
+        match result {
+            Ok(()) => tracing::info!("Reconciler: {} {}", change.action(), change.key()),
+            Err(e) => {
+                errors.insert(change.key().to_string(), format!("cannot {}: {}", change.action(), e));
+            }
+        }
+    }
+
+    log_errors(&errors, last_errors);
+    *last_errors = errors;
+}
+
+/// Logs the reconciliation failures that are new or changed since the previous pass.
+fn log_errors(errors: &HashMap<String, String>, last_errors: &HashMap<String, String>) {
+    for (subject, error) in errors {
+        if last_errors.get(subject) == Some(error) {
+            tracing::debug!("Reconciler: {}: {}", subject, error);
+        } else {
+            tracing::warn!("Reconciler: {}: {}", subject, error);
+        }
+    }
+}
+
+/// Starts the background task that reconciles the deployments directory
+/// every `interval`.
+pub fn spawn_reconciler(
+    runtime: Arc<RwLock<ArcellaRuntime>>,
+    dir: PathBuf,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tracing::info!("Reconciling deployments from {:?} every {:?}", dir, interval);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last_errors = HashMap::new();
+        loop {
+            ticker.tick().await;
+            reconcile(&runtime, &dir, &mut last_errors).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ArcellaConfig;
+    use tempfile::TempDir;
+
+    fn write_spec(dir: &Path, file_name: &str, group: &str, replicas: u32) {
+        let content = format!(
+            "[deployment]\nmodule_id = \"hello@0.1.0\"\ngroup = \"{}\"\nreplicas = {}\n",
+            group, replicas
+        );
+        std::fs::write(dir.join(file_name), content).unwrap();
+    }
+
+    fn actions(changes: &[DeploymentChange]) -> Vec<(&'static str, String)> {
+        changes.iter().map(|c| (c.action(), c.key().to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_converges_to_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let config = Arc::new(ArcellaConfig::for_tests(temp_dir.path()));
+        let dir = config.deployments_dir.clone();
+        let mut runtime = ArcellaRuntime::new_for_tests(config).await.unwrap();
+
+        let wasm_path = source_dir.path().join("hello@0.1.0.wasm");
+        std::fs::write(&wasm_path, wat::parse_str("(component)").unwrap()).unwrap();
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+
+        // A deployment applied by hand is neither touched nor removed.
+        runtime.apply_deployment(DeploymentSpec {
+            module_id: "hello@0.1.0".into(),
+            group: "manual".into(),
+            replicas: 1,
+            overrides: Default::default(),
+        }).unwrap();
+
+        write_spec(&dir, "web.deployment.toml", "web", 2);
+        write_spec(&dir, "batch.deployment.toml", "batch", 1);
+        std::fs::write(dir.join("notes.toml"), "ignored").unwrap();
+
+        let desired = load_desired(&dir).unwrap();
+        let changes = diff(&runtime, &desired);
+        assert_eq!(actions(&changes), vec![
+            ("create", "hello@0.1.0/batch".to_string()),
+            ("create", "hello@0.1.0/web".to_string()),
+        ]);
+        assert!(apply(&mut runtime, changes).iter().all(|(_, r)| r.is_ok()));
+        assert!(diff(&runtime, &load_desired(&dir).unwrap()).is_empty());
+
+        write_spec(&dir, "web.deployment.toml", "web", 3);
+        std::fs::remove_file(dir.join("batch.deployment.toml")).unwrap();
+        let changes = diff(&runtime, &load_desired(&dir).unwrap());
+        assert_eq!(actions(&changes), vec![
+            ("scale", "hello@0.1.0/web".to_string()),
+            ("undeploy", "hello@0.1.0/batch".to_string()),
+        ]);
+        apply(&mut runtime, changes);
+        assert_eq!(runtime.get_deployment(&DeploymentKey::new("hello@0.1.0", "web")).unwrap().resolved.replicas, 3);
+        assert!(runtime.get_deployment(&DeploymentKey::new("hello@0.1.0", "batch")).is_err());
+        assert!(runtime.get_deployment(&DeploymentKey::new("hello@0.1.0", "manual")).is_ok());
+
+        // A broken file blocks undeploys, but not the other changes.
+        std::fs::write(dir.join("broken.deployment.toml"), "not toml [").unwrap();
+        std::fs::remove_file(dir.join("web.deployment.toml")).unwrap();
+        let desired = load_desired(&dir).unwrap();
+        assert_eq!(desired.errors.len(), 1);
+        assert!(diff(&runtime, &desired).is_empty());
+    }
+}
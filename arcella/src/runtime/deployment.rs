@@ -0,0 +1,73 @@
+// arcella/arcella/src/runtime/deployment.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Registry entries for deployments of installed modules.
+
+use std::fmt;
+use time::OffsetDateTime;
+
+use crate::manifest::{DeploymentSpec, FullDeployment};
+
+/// Unique key of a deployment: a module may be deployed once per group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeploymentKey {
+    /// Deployed module ID (`name@version`).
+    pub module_id: String,
+
+    /// Target group of the deployment.
+    pub group: String,
+}
+
+impl DeploymentKey {
+    pub fn new(module_id: impl Into<String>, group: impl Into<String>) -> Self {
+        Self {
+            module_id: module_id.into(),
+            group: group.into(),
+        }
+    }
+}
+
+impl fmt::Display for DeploymentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.module_id, self.group)
+    }
+}
+
+/// A deployment registered in the runtime.
+///
+/// Keeps the specification submitted by the administrator together with the
+/// resolved configuration produced by [`DeploymentSpec::create_deployment`].
+#[derive(Debug, Clone)]
+pub struct Deployment {
+    /// Specification as submitted via `deployment:apply`.
+    pub spec: DeploymentSpec,
+
+    /// Specification merged with the module's deployment template.
+    pub resolved: FullDeployment,
+
+    /// Time the deployment was first created.
+    pub created_at: OffsetDateTime,
+
+    /// Time the deployment was last applied or scaled.
+    pub updated_at: OffsetDateTime,
+
+    /// Whether the deployment is stopped: it stays registered, but runs no instances.
+    pub stopped: bool,
+
+    /// Whether the deployment is owned by a `*.deployment.toml` file, i.e. is
+    /// updated and removed by the deployment reconciler.
+    pub managed: bool,
+}
+
+impl Deployment {
+    /// Returns the registry key of this deployment.
+    pub fn key(&self) -> DeploymentKey {
+        DeploymentKey::new(&self.spec.module_id, &self.spec.group)
+    }
+}
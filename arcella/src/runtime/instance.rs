@@ -0,0 +1,275 @@
+// arcella/arcella/src/runtime/instance.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Running replicas of deployments.
+//!
+//! Every replica of a deployment is an [`Instance`]: a tokio task that drives the
+//! module's entry point, plus a shared status that the task updates as it goes
+//! through the `Starting -> Running -> Exited | Failed` lifecycle.
+//!
+//! Instances with `main` isolation run in the daemon process: async deployments
+//! directly on the daemon's tokio runtime, sync deployments on a dedicated blocking
+//! thread, so that long guest calls cannot starve the runtime workers. Instances
+//! with `worker` isolation run in an `arcella-worker` process (see
+//! [`crate::worker::WorkerManager`]); the daemon only mirrors their status.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use time::OffsetDateTime;
+use tokio::sync::oneshot;
+use wasmtime::Engine;
+
+use arcella_wasmtime::executor::{run_executable, Executable, InstanceOptions};
+
+use super::DeploymentKey;
+
+/// Lifecycle state of an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceState {
+    /// The module is being instantiated.
+    Starting,
+
+    /// The entry point is executing.
+    Running,
+
+    /// The entry point finished with the given exit code.
+    Exited { code: i32 },
+
+    /// Instantiation failed or guest code trapped.
+    Failed { reason: String },
+}
+
+impl InstanceState {
+    /// Returns `true` once the instance has stopped executing.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Exited { .. } | Self::Failed { .. })
+    }
+}
+
+impl fmt::Display for InstanceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Starting => write!(f, "starting"),
+            Self::Running => write!(f, "running"),
+            Self::Exited { .. } => write!(f, "exited"),
+            Self::Failed { .. } => write!(f, "failed"),
+        }
+    }
+}
+
+/// Point-in-time view of an instance's status.
+#[derive(Debug, Clone)]
+pub struct InstanceStatus {
+    pub state: InstanceState,
+    pub started_at: OffsetDateTime,
+    pub finished_at: Option<OffsetDateTime>,
+}
+
+impl InstanceStatus {
+    /// Creates a status that enters `state` now.
+    pub fn new(state: InstanceState) -> Self {
+        let now = OffsetDateTime::now_utc();
+        let finished_at = state.is_finished().then_some(now);
+        Self { state, started_at: now, finished_at }
+    }
+
+    /// Moves to a new state, recording the finish time for final states.
+    pub fn set_state(&mut self, state: InstanceState) {
+        if state.is_finished() {
+            self.finished_at = Some(OffsetDateTime::now_utc());
+        }
+        self.state = state;
+    }
+}
+
+/// A single replica of a deployment.
+pub struct Instance {
+    /// Unique instance ID: `<module_id>/<group>/<replica>`.
+    pub id: String,
+
+    /// Deployment this instance belongs to.
+    pub deployment: DeploymentKey,
+
+    /// Replica index within the deployment (`0..replicas`).
+    pub replica: u32,
+
+    /// Number of times this replica was restarted by the supervisor.
+    pub restarts: u32,
+
+    status: Arc<Mutex<InstanceStatus>>,
+    stop: Option<StopHandle>,
+    shutdown: Option<StopHandle>,
+}
+
+/// Stops (or asks to shut down) the execution behind an [`Instance`].
+type StopHandle = Box<dyn FnOnce() + Send + Sync>;
+
+impl Instance {
+    /// Builds the instance ID of a deployment replica.
+    pub fn make_id(deployment: &DeploymentKey, replica: u32) -> String {
+        format!("{}/{}", deployment, replica)
+    }
+
+    /// Spawns an instance of compiled code.
+    ///
+    /// The instance starts in [`InstanceState::Starting`], switches to
+    /// [`InstanceState::Running`] once the module is instantiated, and ends as
+    /// [`InstanceState::Exited`] or [`InstanceState::Failed`]. Resident components
+    /// stay `Running` until the instance is stopped or shut down with
+    /// [`Instance::begin_shutdown`].
+    ///
+    /// # Arguments
+    ///
+    /// * `is_async` — Run on the tokio runtime (`true`) or on a dedicated blocking thread.
+    pub fn spawn(
+        deployment: DeploymentKey,
+        replica: u32,
+        engine: Engine,
+        executable: Executable,
+        options: InstanceOptions,
+        is_async: bool,
+    ) -> Self {
+        let id = Self::make_id(&deployment, replica);
+        let status = Arc::new(Mutex::new(InstanceStatus::new(InstanceState::Starting)));
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        let task_status = status.clone();
+        let task_id = id.clone();
+        let run = async move {
+            tokio::select! {
+                state = drive(&engine, &executable, &options, &task_status, &task_id, shutdown_rx) => {
+                    set_state(&task_status, state);
+                }
+                // Fires on an explicit stop and when the instance is dropped.
+                _ = stop_rx => {
+                    tracing::debug!("Instance {} cancelled", task_id);
+                }
+            }
+        };
+
+        if is_async {
+            tokio::spawn(run);
+        } else {
+            let handle = tokio::runtime::Handle::current();
+            tokio::task::spawn_blocking(move || handle.block_on(run));
+        }
+
+        let stop: StopHandle = Box::new(move || {
+            let _ = stop_tx.send(());
+        });
+        let shutdown: StopHandle = Box::new(move || {
+            let _ = shutdown_tx.send(());
+        });
+        Self { id, deployment, replica, restarts: 0, status, stop: Some(stop), shutdown: Some(shutdown) }
+    }
+
+    /// Wraps an instance that is executed elsewhere (e.g. in a worker process).
+    ///
+    /// `status` is kept up to date by the owner of the execution. `stop` is called
+    /// once, when the instance is stopped or dropped; `shutdown` is called at most
+    /// once, by [`Instance::begin_shutdown`].
+    pub fn attach(
+        deployment: DeploymentKey,
+        replica: u32,
+        status: Arc<Mutex<InstanceStatus>>,
+        stop: impl FnOnce() + Send + Sync + 'static,
+        shutdown: impl FnOnce() + Send + Sync + 'static,
+    ) -> Self {
+        let id = Self::make_id(&deployment, replica);
+        Self {
+            id, deployment, replica, restarts: 0, status,
+            stop: Some(Box::new(stop)),
+            shutdown: Some(Box::new(shutdown)),
+        }
+    }
+
+    /// Registers an instance that failed before it could be spawned
+    /// (e.g. the module could not be compiled).
+    pub fn failed(deployment: DeploymentKey, replica: u32, reason: String) -> Self {
+        let id = Self::make_id(&deployment, replica);
+        tracing::warn!("Instance {} failed: {}", id, reason);
+        let status = Arc::new(Mutex::new(InstanceStatus::new(InstanceState::Failed { reason })));
+
+        Self { id, deployment, replica, restarts: 0, status, stop: None, shutdown: None }
+    }
+
+    /// Returns a snapshot of the instance status.
+    pub fn status(&self) -> InstanceStatus {
+        self.status.lock().expect("Instance status poisoned").clone()
+    }
+
+    /// Asks the instance to shut down gracefully.
+    ///
+    /// A resident component gets its shutdown hook called (if it has one) and then
+    /// exits; any other instance is cancelled at the next yield to the host. The
+    /// outcome is reported through [`Instance::status`]; call [`Instance::stop`]
+    /// to give up waiting for it.
+    pub fn begin_shutdown(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown();
+        }
+    }
+
+    /// Stops the instance by cancelling its task.
+    ///
+    /// Cancellation takes effect the next time the guest yields to the host;
+    /// the store and all guest resources are dropped at that point.
+    pub fn stop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            if !self.status().state.is_finished() {
+                tracing::info!("Stopped instance {}", self.id);
+            }
+            stop();
+        }
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Instantiates and runs the executable, returning the final state.
+async fn drive(
+    engine: &Engine,
+    executable: &Executable,
+    options: &InstanceOptions,
+    status: &Mutex<InstanceStatus>,
+    id: &str,
+    shutdown_rx: oneshot::Receiver<()>,
+) -> InstanceState {
+    let on_running = || {
+        set_state(status, InstanceState::Running);
+        tracing::info!("Instance {} is running", id);
+    };
+
+    // A dropped sender (the instance is being stopped) is not a shutdown request.
+    let shutdown = async {
+        if shutdown_rx.await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    match run_executable(engine, executable, options, on_running, shutdown).await {
+        Ok(code) => {
+            tracing::info!("Instance {} exited with code {}", id, code);
+            InstanceState::Exited { code }
+        }
+        Err(e) => {
+            tracing::warn!("Instance {} failed: {}", id, e);
+            InstanceState::Failed { reason: e.to_string() }
+        }
+    }
+}
+
+fn set_state(status: &Mutex<InstanceStatus>, state: InstanceState) {
+    status.lock().expect("Instance status poisoned").set_state(state);
+}
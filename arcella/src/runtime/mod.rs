@@ -8,22 +8,38 @@
 // except according to those terms.
 
 use std::{
-    collections::HashMap,
-    path::{PathBuf},
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant}
 };
 use time::OffsetDateTime;
-use tokio::sync::{RwLock, broadcast};
+use tokio::sync::{Notify, RwLock};
+use wasmtime::Engine;
 
-use arcella_types::{
-    manifest::ComponentManifest,
+use arcella_types::manifest::ComponentManifest;
+use arcella_wasmtime::{
+    engine::{create_engine, EpochTicker},
+    executor::{InstanceOptions, ResourceLimits},
 };
 
 use crate::{storage, cache};
-use crate::config::ArcellaConfig;
+use crate::cache::LoadedModule;
+use crate::storage::ModuleRecord;
+use crate::config::{ArcellaConfig, IntegrityAction, IntegrityChecker};
 use crate::error::{ArcellaError, Result as ArcellaResult};
+use crate::manifest::{DeploymentSpec, FullDeployment, IsolationMode};
+use crate::worker::{WorkerManager, WorkerSettings};
 
+pub mod deployment;
+pub mod instance;
+pub mod module;
+pub mod state;
+
+pub use deployment::{Deployment, DeploymentKey};
+pub use instance::{Instance, InstanceState};
+pub use module::{InstalledModule, PreparedModule};
+pub use state::{DeploymentState, RuntimeState};
 
 struct ArcellaRuntimeEnvironment {
     pub pid: u32,
@@ -31,6 +47,23 @@ struct ArcellaRuntimeEnvironment {
     pub start_utc: OffsetDateTime,
 }
 
+/// How long an instance may take to shut down when its deployment does not set
+/// `startup.timeout_seconds`.
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Extra time for worker processes to report the final state of their instances.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(1);
+
+/// How a running instance ended during a graceful shutdown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    /// The instance finished on its own (possibly with a failed shutdown hook).
+    Finished(InstanceState),
+
+    /// The instance did not finish in time and was cancelled.
+    Killed,
+}
+
 pub struct ArcellaRuntimeStatus {
     pub pid: u32,
     pub start_time: OffsetDateTime,
@@ -41,10 +74,22 @@ pub struct ArcellaRuntime {
     pub config: Arc<ArcellaConfig>,
     pub storage: Arc<storage::StorageManager>,
     pub cache: Arc<cache::ModuleCache>,
-    pub environment: Arc<RwLock<ArcellaRuntimeEnvironment>>,
-    //pub modules: HashMap<String, ModuleManifest>, // key = name@version
-    // Позже: instances, engine и т.д.
-
+    environment: Arc<RwLock<ArcellaRuntimeEnvironment>>,
+    pub engine: Engine,
+    pub modules: HashMap<String, InstalledModule>, // key = name@version
+    pub deployments: HashMap<DeploymentKey, Deployment>,
+    /// Saved deployments that could not be restored on startup. They are kept in
+    /// `state.json` until they are applied again or undeployed.
+    unrestored: HashMap<DeploymentKey, DeploymentState>,
+    pub instances: HashMap<String, Instance>, // key = instance ID
+    /// Installed modules compiled through the module cache, by module ID.
+    compiled: HashMap<String, LoadedModule>,
+    pub workers: WorkerManager,
+    integrity: IntegrityChecker,
+    /// Last detected change of a configuration file, if the files are still changed.
+    integrity_violation: Option<String>,
+    shutdown_request: Arc<Notify>,
+    _epoch_ticker: EpochTicker,
 }
 
 impl ArcellaRuntime{
@@ -60,31 +105,183 @@ impl ArcellaRuntime{
             start_utc: OffsetDateTime::now_utc(),
         };
 
-        let runtime = Self {
+        // Lets guests of `main` isolation yield, so they cannot stall the tokio executor.
+        let engine = create_engine()?;
+        let epoch_ticker = EpochTicker::start(&engine);
+        let integrity = IntegrityChecker::new(config.integrity_check_paths.clone())?;
+        let workers = WorkerManager::new(config.get_section::<WorkerSettings>("arcella.workers")?);
+
+        let mut runtime = Self {
             config,
             storage,
             cache,
             environment: Arc::new(RwLock::new(env)),
-            //modules: HashMap::new(),
+            engine,
+            modules: HashMap::new(),
+            deployments: HashMap::new(),
+            unrestored: HashMap::new(),
+            instances: HashMap::new(),
+            compiled: HashMap::new(),
+            workers,
+            integrity,
+            integrity_violation: None,
+            shutdown_request: Arc::new(Notify::new()),
+            _epoch_ticker: epoch_ticker,
         };
 
+        runtime.load_installed_modules().await?;
+        runtime.restore_state().await;
+
         Ok(runtime)
     }
 
+    /// Shuts the runtime down gracefully.
+    ///
+    /// Deployments are stopped in reverse dependency order: a deployment whose
+    /// module imports an interface exported by another deployment's module is
+    /// stopped first. Every running instance of a wave is asked to shut down (a
+    /// resident component gets its `startup.shutdown` export called) and is given
+    /// `startup.timeout_seconds` to finish; instances that are still running after
+    /// that are killed. Finally, the worker processes are terminated and the
+    /// desired state is flushed to disk.
     pub async fn shutdown(&mut self) -> ArcellaResult<()> {
-        // To be added stopping modules, instances, and the engine
+        for wave in self.shutdown_order() {
+            self.shutdown_wave(&wave).await;
+        }
+
+        // Dropping an instance cancels its task
+        self.instances.clear();
+        self.workers.shutdown().await;
+        self.persist_state();
         Ok(())
     }
 
+    /// Groups the deployments into waves that can be stopped together.
+    ///
+    /// Each wave contains the deployments that no remaining deployment depends
+    /// on. Deployments with cyclic dependencies are stopped together in the last
+    /// wave.
+    fn shutdown_order(&self) -> Vec<Vec<DeploymentKey>> {
+        let exports_of = |key: &DeploymentKey| -> HashSet<&String> {
+            self.modules.get(&key.module_id)
+                .map(|module| module.manifest.exports.keys().collect())
+                .unwrap_or_default()
+        };
+        let imports_of = |key: &DeploymentKey| -> HashSet<&String> {
+            self.modules.get(&key.module_id)
+                .map(|module| module.manifest.imports.keys().collect())
+                .unwrap_or_default()
+        };
+
+        // dependencies[d] = deployments that `d` imports interfaces from
+        let keys: Vec<DeploymentKey> = self.deployments.keys().cloned().collect();
+        let dependencies: HashMap<&DeploymentKey, Vec<&DeploymentKey>> = keys.iter()
+            .map(|key| {
+                let imports = imports_of(key);
+                let deps = keys.iter()
+                    .filter(|other| *other != key && !imports.is_disjoint(&exports_of(other)))
+                    .collect();
+                (key, deps)
+            })
+            .collect();
+
+        let mut remaining: HashSet<&DeploymentKey> = keys.iter().collect();
+        let mut waves = Vec::new();
+        while !remaining.is_empty() {
+            let depended_on: HashSet<&DeploymentKey> = remaining.iter()
+                .flat_map(|key| dependencies[key].iter().copied())
+                .filter(|dep| remaining.contains(dep))
+                .collect();
+            let mut wave: Vec<DeploymentKey> = remaining.iter()
+                .filter(|key| !depended_on.contains(*key))
+                .map(|key| (*key).clone())
+                .collect();
+
+            if wave.is_empty() {
+                wave = remaining.iter().map(|key| (*key).clone()).collect();
+                wave.sort();
+                tracing::warn!(
+                    "Cyclic dependencies between deployments {}, stopping them together",
+                    wave.iter().map(|key| key.to_string()).collect::<Vec<_>>().join(", ")
+                );
+            }
+            wave.sort();
+            for key in &wave {
+                remaining.remove(key);
+            }
+            waves.push(wave);
+        }
+
+        waves
+    }
+
+    /// Shuts down the running instances of the given deployments and waits for
+    /// them to finish, killing those that do not finish in time.
+    ///
+    /// # Returns
+    ///
+    /// The outcome for every instance that was still running, sorted by instance ID.
+    async fn shutdown_wave(&mut self, wave: &[DeploymentKey]) -> Vec<(String, ShutdownOutcome)> {
+        let timeout = wave.iter()
+            .filter_map(|key| self.deployments.get(key))
+            .map(|deployment| deployment.resolved.startup.timeout().unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT))
+            .max()
+            .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
+        let deadline = Instant::now() + timeout + SHUTDOWN_GRACE;
+
+        let mut running: Vec<String> = self.instances.values_mut()
+            .filter(|instance| wave.contains(&instance.deployment))
+            .filter(|instance| !instance.status().state.is_finished())
+            .map(|instance| {
+                instance.begin_shutdown();
+                instance.id.clone()
+            })
+            .collect();
+        running.sort();
+
+        while Instant::now() < deadline
+            && running.iter().any(|id| !self.instances[id].status().state.is_finished())
+        {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let mut outcomes = Vec::new();
+        for id in running {
+            let Some(instance) = self.instances.remove(&id) else { continue };
+            let outcome = match instance.status().state {
+                state if state.is_finished() => ShutdownOutcome::Finished(state),
+                _ => ShutdownOutcome::Killed,
+            };
+            match &outcome {
+                ShutdownOutcome::Finished(InstanceState::Exited { code }) => {
+                    tracing::info!("Instance {} shut down (exit code {})", id, code);
+                }
+                ShutdownOutcome::Finished(InstanceState::Failed { reason }) => {
+                    tracing::warn!("Instance {} failed during shutdown: {}", id, reason);
+                }
+                ShutdownOutcome::Finished(_) => {}
+                ShutdownOutcome::Killed => {
+                    tracing::warn!("Instance {} did not shut down within {:?}, killing it", id, timeout);
+                }
+            }
+            // Dropping the instance cancels it if it is still running.
+            drop(instance);
+            outcomes.push((id, outcome));
+        }
+        self.instances.retain(|_, instance| !wave.contains(&instance.deployment));
+
+        outcomes
+    }
+
     pub fn status(&self) -> ArcellaResult<ArcellaRuntimeStatus> {
 
         let env = self.environment.try_read().expect("Runtime environment poisoned");
 
-        return Ok(ArcellaRuntimeStatus {
+        Ok(ArcellaRuntimeStatus {
             pid: env.pid,
             start_time: env.start_utc,
             uptime: self.uptime(),
-        });
+        })
 
     }
 
@@ -93,21 +290,697 @@ impl ArcellaRuntime{
         env.start_instant.elapsed()
     }
 
+    /// Rescans `modules_dir` and registers every installed module.
+    ///
+    /// Modules that fail to load (tampered or missing blob, invalid manifest, directory
+    /// name that does not match the module ID) are skipped with a warning, so that a
+    /// single broken module does not prevent the daemon from starting.
+    async fn load_installed_modules(&mut self) -> ArcellaResult<()> {
+        for record in self.storage.list_stored_modules().await? {
+            let module_id = record.module_id.clone();
+            let module = match self.load_stored_module(&record).await {
+                Ok(module) => module,
+                Err(e) => {
+                    tracing::warn!("Skipping installed module {}: {}", module_id, e);
+                    continue;
+                }
+            };
+
+            if module.id() != module_id {
+                tracing::warn!(
+                    "Skipping installed module {}: manifest declares ID {}",
+                    module_id, module.id()
+                );
+                continue;
+            }
+
+            tracing::info!("Loaded installed module {}", module_id);
+            self.modules.insert(module_id, module);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the blob of a stored module and loads it.
+    async fn load_stored_module(&self, record: &ModuleRecord) -> ArcellaResult<InstalledModule> {
+        let wasm_path = self.storage.verify_module(record).await?;
+        let module_dir = self.storage.module_dir(&record.module_id);
+        InstalledModule::load(&self.engine, &module_dir, record, &wasm_path)
+    }
+
+    /// Installs a module like [`install_module`], but validates it in place.
+    #[cfg(test)]
     pub async fn install_module_from_path(
         &mut self,
-        wasm_path: &PathBuf,
-    ) -> ArcellaResult<()> {
-        /*let component = ComponentManifest::from_component_toml(wasm_path)?
-            .ok_or_else(|| ArcellaError::Manifest("component.toml required for WASI modules".into()))?;
-        component.validate()?;*/
+        wasm_path: &Path,
+    ) -> ArcellaResult<InstalledModule> {
+        let prepared = PreparedModule::from_wasm_path(&self.engine, wasm_path)?;
+        self.install_prepared(prepared).await
+    }
+
+    /// Installs a module validated by [`PreparedModule::from_wasm_path`].
+    ///
+    /// The `.wasm` file is added to the content-addressed blob store, and the
+    /// optional `component.toml` and `deployment-template.toml` next to it are
+    /// copied into `modules_dir/<id>/`. The registry entry is built from the
+    /// prepared manifests, so the stored blob is not parsed again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a module with the same ID is already installed, or if
+    /// the files cannot be copied.
+    pub async fn install_prepared(
+        &mut self,
+        prepared: PreparedModule,
+    ) -> ArcellaResult<InstalledModule> {
+        let module_id = prepared.id();
+        let wasm_path = prepared.bundle.wasm_path.clone();
+
+        if self.modules.contains_key(&module_id) {
+            return Err(ArcellaError::ModuleAlreadyInstalled(module_id));
+        }
+
+        let record = self.storage.store_module(&module_id, &wasm_path).await?;
+
+        let stored = match self.storage.verify_module(&record).await {
+            Ok(blob_path) => {
+                let module_dir = self.storage.module_dir(&module_id);
+                InstalledModule::from_prepared(prepared, &module_dir, &record, &blob_path)
+            }
+            Err(e) => Err(e),
+        };
+        let module = match stored {
+            Ok(module) => module,
+            Err(e) => {
+                if let Err(cleanup_err) = self.storage.remove_module(&module_id).await {
+                    tracing::error!("Failed to clean up module {}: {}", module_id, cleanup_err);
+                }
+                return Err(e);
+            }
+        };
+
+        tracing::info!("Installed module {} from {:?}", module_id, wasm_path);
+        self.modules.insert(module_id, module.clone());
+        self.persist_state();
+
+        Ok(module)
+    }
+
+    /// Returns all installed modules sorted by ID.
+    pub fn list_modules(&self) -> Vec<&InstalledModule> {
+        let mut modules: Vec<_> = self.modules.values().collect();
+        modules.sort_by_key(|m| m.id());
+        modules
+    }
+
+    /// Returns an installed module by its `name@version` ID.
+    pub fn get_module(&self, module_id: &str) -> ArcellaResult<&InstalledModule> {
+        self.modules
+            .get(module_id)
+            .ok_or_else(|| ArcellaError::ModuleNotFound(module_id.to_string()))
+    }
+
+    /// Removes an installed module from the registry and deletes its files.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaError::ModuleNotFound`] if the module is not installed,
+    /// [`ArcellaError::ModuleInUse`] if it still has deployments,
+    /// or an I/O error if its directory cannot be removed.
+    pub async fn remove_module(&mut self, module_id: &str) -> ArcellaResult<InstalledModule> {
+        if !ComponentManifest::validate_module_id(module_id) {
+            return Err(ArcellaError::ModuleNotFound(module_id.to_string()));
+        }
+
+        let in_use = self.deployments.keys().filter(|k| k.module_id == module_id).count();
+        if in_use > 0 {
+            return Err(ArcellaError::ModuleInUse(module_id.to_string(), in_use));
+        }
+
+        let module = self.modules
+            .remove(module_id)
+            .ok_or_else(|| ArcellaError::ModuleNotFound(module_id.to_string()))?;
+
+        if let Err(e) = self.storage.remove_module(module_id).await {
+            self.modules.insert(module_id.to_string(), module);
+            return Err(e);
+        }
+
+        self.compiled.remove(module_id);
+        tracing::info!("Removed module {}", module_id);
+        self.persist_state();
+        Ok(module)
+    }
+
+    /// Creates or updates a deployment of an installed module.
+    ///
+    /// The specification is validated, merged with the module's deployment template
+    /// via [`DeploymentSpec::create_deployment`] and stored under its
+    /// `(module_id, group)` key. Applying a spec for an existing key replaces the
+    /// previous configuration and keeps the original creation time.
+    ///
+    /// Instances are then reconciled with the new replica count. If anything other
+    /// than the replica count changed, the existing instances are restarted.
+    /// Re-applying a stopped deployment updates it but keeps it stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaError::ModuleNotFound`] if the module is not installed,
+    /// or a manifest error if the spec or the resolved deployment is invalid
+    /// (e.g. more than one replica with `main` isolation).
+    pub fn apply_deployment(&mut self, spec: DeploymentSpec) -> ArcellaResult<&Deployment> {
+        if let Some(violation) = self.deployments_refused_by() {
+            return Err(ArcellaError::Config(format!(
+                "Deployments are refused until the configuration is reloaded: {}", violation
+            )));
+        }
+        spec.validate()?;
+
+        let module = self.get_module(&spec.module_id)?;
+        let resolved = spec.create_deployment(module.template.as_ref())?;
+
+        let key = DeploymentKey::new(&spec.module_id, &spec.group);
+        let now = OffsetDateTime::now_utc();
+        // A deployment that could not be restored is taken over with its saved state.
+        let previous = self.deployments.get(&key)
+            .map(DeploymentState::from)
+            .or_else(|| self.unrestored.get(&key).cloned());
+        let created_at = previous.as_ref().map_or(now, |d| d.created_at);
+        let stopped = previous.as_ref().is_some_and(|d| d.stopped);
+        let managed = previous.as_ref().is_some_and(|d| d.managed);
+
+        let restart = self.deployments
+            .get(&key)
+            .is_some_and(|d| !same_except_replicas(&d.resolved, &resolved));
+        if restart {
+            tracing::info!("Deployment {} changed, restarting its instances", key);
+            self.instances.retain(|_, i| i.deployment != key);
+            self.retire_idle_workers();
+        }
+
+        tracing::info!("Applied deployment {} ({} replicas)", key, resolved.replicas);
+        let deployment = Deployment { spec, resolved, created_at, updated_at: now, stopped, managed };
+        self.deployments.insert(key.clone(), deployment);
+        self.unrestored.remove(&key);
+        self.reconcile_instances(&key);
+        self.persist_state();
+
+        Ok(&self.deployments[&key])
+    }
+
+    /// Checks that the loaded configuration files are unchanged, and reacts to a
+    /// change as configured by `integrity.action`.
+    ///
+    /// A violation is reported once when it is first detected. With
+    /// [`IntegrityAction::RefuseDeployments`], [`Self::apply_deployment`] fails
+    /// while the violation lasts; with [`IntegrityAction::Shutdown`], the daemon is
+    /// asked to shut down (see [`Self::shutdown_requested`]).
+    ///
+    /// # Returns
+    ///
+    /// The description of the violation, or `None` if all files are unchanged.
+    pub async fn check_integrity(&mut self) -> Option<String> {
+        let violation = match self.integrity.check().await {
+            Ok(()) => {
+                self.integrity_violation = None;
+                return None;
+            }
+            Err(e) => e.to_string(),
+        };
+
+        if self.integrity_violation.as_ref() != Some(&violation) {
+            match self.config.integrity_action {
+                IntegrityAction::Warn => tracing::warn!("{}", violation),
+                IntegrityAction::RefuseDeployments => {
+                    tracing::warn!("{}; deployments are refused until the configuration is reloaded", violation)
+                }
+                IntegrityAction::Shutdown => {
+                    tracing::error!("{}; shutting down", violation);
+                    self.shutdown_request.notify_one();
+                }
+            }
+        }
+        self.integrity_violation = Some(violation.clone());
+        Some(violation)
+    }
+
+    /// Takes the files of the current configuration as the new integrity baseline
+    /// and clears a detected violation; called after the configuration is reloaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if a configuration file cannot be read; the previous
+    /// baseline is kept in that case.
+    pub fn reset_integrity(&mut self) -> ArcellaResult<()> {
+        self.integrity = IntegrityChecker::new(self.config.integrity_check_paths.clone())?;
+        self.integrity_violation = None;
+        Ok(())
+    }
+
+    /// Returns the configuration files monitored by [`Self::check_integrity`].
+    pub fn integrity_paths(&self) -> &[PathBuf] {
+        self.integrity.paths()
+    }
+
+    /// Returns the violation that makes [`Self::apply_deployment`] fail, if any.
+    pub fn deployments_refused_by(&self) -> Option<&str> {
+        match self.config.integrity_action {
+            IntegrityAction::RefuseDeployments => self.integrity_violation.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the notification that fires when the runtime asks the daemon to shut down.
+    pub fn shutdown_requested(&self) -> Arc<Notify> {
+        self.shutdown_request.clone()
+    }
+
+    /// Applies a deployment on behalf of a `*.deployment.toml` file and marks it
+    /// as managed by the deployment reconciler.
+    ///
+    /// See [`Self::apply_deployment`] for the validation rules.
+    pub fn apply_managed_deployment(&mut self, spec: DeploymentSpec) -> ArcellaResult<&Deployment> {
+        let key = DeploymentKey::new(&spec.module_id, &spec.group);
+        self.apply_deployment(spec)?;
+
+        let deployment = self.deployments.get_mut(&key).expect("deployment was just applied");
+        if !deployment.managed {
+            deployment.managed = true;
+            self.persist_state();
+        }
+
+        Ok(&self.deployments[&key])
+    }
+
+    /// Returns all deployments sorted by module ID and group.
+    pub fn list_deployments(&self) -> Vec<&Deployment> {
+        let mut deployments: Vec<_> = self.deployments.values().collect();
+        deployments.sort_by_key(|d| d.key());
+        deployments
+    }
+
+    /// Returns a deployment by its key.
+    pub fn get_deployment(&self, key: &DeploymentKey) -> ArcellaResult<&Deployment> {
+        self.deployments
+            .get(key)
+            .ok_or_else(|| ArcellaError::DeploymentNotFound(key.to_string()))
+    }
 
-        //let deployment = DeploymentProfile::from_file(wasm_path)?;
-        //deployment.validate()?;
+    /// Changes the number of replicas of an existing deployment.
+    ///
+    /// The deployment is re-resolved against the module's template, so all
+    /// validation rules of [`Self::apply_deployment`] apply.
+    pub fn scale_deployment(&mut self, key: &DeploymentKey, replicas: u32) -> ArcellaResult<&Deployment> {
+        let mut spec = self.get_deployment(key)?.spec.clone();
+        spec.replicas = replicas;
+        self.apply_deployment(spec)
+    }
+
+    /// Removes a deployment from the registry.
+    ///
+    /// A saved deployment that could not be restored on startup is removed from
+    /// the persisted state as well.
+    pub fn undeploy(&mut self, key: &DeploymentKey) -> ArcellaResult<()> {
+        if self.deployments.remove(key).is_none() {
+            self.unrestored
+                .remove(key)
+                .ok_or_else(|| ArcellaError::DeploymentNotFound(key.to_string()))?;
+        }
 
+        self.instances.retain(|_, i| i.deployment != *key);
+        self.retire_idle_workers();
 
+        tracing::info!("Undeployed {}", key);
+        self.persist_state();
         Ok(())
     }
 
+    /// Returns the saved deployments that could not be restored on startup,
+    /// sorted by module ID and group.
+    pub fn unrestored_deployments(&self) -> Vec<&DeploymentState> {
+        let mut deployments: Vec<_> = self.unrestored.values().collect();
+        deployments.sort_by_key(|d| d.key());
+        deployments
+    }
+
+    /// Stops all instances of a deployment and keeps it stopped, including across
+    /// daemon restarts, until [`Self::start_deployment`] is called.
+    pub fn stop_deployment(&mut self, key: &DeploymentKey) -> ArcellaResult<&Deployment> {
+        self.set_stopped(key, true)
+    }
+
+    /// Starts the instances of a stopped deployment.
+    pub fn start_deployment(&mut self, key: &DeploymentKey) -> ArcellaResult<&Deployment> {
+        self.set_stopped(key, false)
+    }
+
+    fn set_stopped(&mut self, key: &DeploymentKey, stopped: bool) -> ArcellaResult<&Deployment> {
+        let deployment = self.deployments
+            .get_mut(key)
+            .ok_or_else(|| ArcellaError::DeploymentNotFound(key.to_string()))?;
+
+        if deployment.stopped != stopped {
+            deployment.stopped = stopped;
+            deployment.updated_at = OffsetDateTime::now_utc();
+            tracing::info!("{} deployment {}", if stopped { "Stopped" } else { "Started" }, key);
+            self.reconcile_instances(key);
+            self.persist_state();
+        }
+
+        Ok(&self.deployments[key])
+    }
+
+    /// Returns a snapshot of the desired state: installed modules and deployments,
+    /// including the saved deployments that could not be restored.
+    pub fn desired_state(&self) -> RuntimeState {
+        let mut modules: Vec<String> = self.modules.keys().cloned().collect();
+        modules.sort();
+
+        let mut deployments: Vec<DeploymentState> = self.list_deployments().into_iter().map(Into::into).collect();
+        deployments.extend(self.unrestored.values().cloned());
+        deployments.sort_by_key(|d| d.key());
+
+        RuntimeState {
+            version: state::STATE_VERSION,
+            modules,
+            deployments,
+        }
+    }
+
+    /// Writes the desired state to `base_dir/state.json`.
+    ///
+    /// Called after every change of modules or deployments. A failed write is
+    /// logged and does not undo the change.
+    fn persist_state(&self) {
+        let path = self.storage.state_path();
+        if let Err(e) = self.desired_state().save(&path) {
+            tracing::error!("Failed to persist runtime state to {:?}: {}", path, e);
+        }
+    }
+
+    /// Restores the deployments recorded in `base_dir/state.json` and starts
+    /// their instances.
+    ///
+    /// Deployments whose module is no longer installed, or which no longer pass
+    /// validation, are not started; they are logged and kept in the saved state
+    /// until they are applied again or undeployed. An unreadable state file is moved
+    /// aside to `state.json.bad`, so that it is not overwritten by the next change.
+    ///
+    /// The modules of the deployments to start are compiled on a blocking thread
+    /// first.
+    async fn restore_state(&mut self) {
+        let path = self.storage.state_path();
+        let state = match RuntimeState::load(&path) {
+            Ok(Some(state)) => state,
+            Ok(None) => return,
+            Err(e) => {
+                let bad_path = path.with_extension("json.bad");
+                tracing::error!("Cannot read runtime state {:?}, moving it to {:?}: {}", path, bad_path, e);
+                if let Err(e) = std::fs::rename(&path, &bad_path) {
+                    tracing::error!("Failed to move {:?}: {}", path, e);
+                }
+                return;
+            }
+        };
+
+        for module_id in &state.modules {
+            if !self.modules.contains_key(module_id) {
+                tracing::warn!("Module {} from the saved state is not available", module_id);
+            }
+        }
+
+        for saved in state.deployments.iter().filter(|saved| !saved.stopped) {
+            if let Some(job) = self.compile_job(&saved.spec.module_id) {
+                let compiled = job.run().await;
+                self.add_compiled(compiled);
+            }
+        }
+
+        for saved in state.deployments {
+            let key = DeploymentKey::new(&saved.spec.module_id, &saved.spec.group);
+            let resolved = saved.spec
+                .validate()
+                .and_then(|_| self.get_module(&saved.spec.module_id))
+                .and_then(|module| saved.spec.create_deployment(module.template.as_ref()));
+            let resolved = match resolved {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    tracing::warn!("Cannot restore deployment {}, keeping it in the saved state: {}", key, e);
+                    self.unrestored.insert(key, saved);
+                    continue;
+                }
+            };
+
+            tracing::info!(
+                "Restored deployment {} ({} replicas{})",
+                key, resolved.replicas, if saved.stopped { ", stopped" } else { "" }
+            );
+            self.deployments.insert(key.clone(), Deployment {
+                spec: saved.spec,
+                resolved,
+                created_at: saved.created_at,
+                updated_at: saved.updated_at,
+                stopped: saved.stopped,
+                managed: saved.managed,
+            });
+            self.reconcile_instances(&key);
+        }
+    }
+
+    /// Brings the instances of a deployment in line with its replica count.
+    ///
+    /// Replicas with an index beyond the replica count are stopped and removed.
+    /// Missing replicas are spawned: in the daemon for `main` isolation, or in the
+    /// worker process of the deployment's group for `worker` isolation. Finished
+    /// instances are kept as they are, so that their exit status remains visible.
+    /// Removed and stopped deployments have no instances.
+    fn reconcile_instances(&mut self, key: &DeploymentKey) {
+        let Some(deployment) = self.deployments.get(key).filter(|d| !d.stopped) else {
+            self.instances.retain(|_, i| i.deployment != *key);
+            self.retire_idle_workers();
+            return;
+        };
+        let resolved = deployment.resolved.clone();
+
+        self.instances.retain(|_, i| i.deployment != *key || i.replica < resolved.replicas);
+
+        let missing: Vec<u32> = (0..resolved.replicas)
+            .filter(|r| !self.instances.contains_key(&Instance::make_id(key, *r)))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        self.spawn_instances(key, &resolved, &missing);
+    }
+
+    /// Restarts stopped instances according to the restart policy of their deployment.
+    ///
+    /// An instance is due for a restart once its policy allows it (see
+    /// [`crate::manifest::RestartConfig::should_restart`]) and the backoff delay
+    /// since it stopped has elapsed. Instances of a crashed worker process are
+    /// reported as failed, so they are restarted in a fresh worker.
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the restarted instances.
+    pub fn supervise(&mut self) -> Vec<String> {
+        self.workers.reap_exited();
+
+        let now = OffsetDateTime::now_utc();
+        let mut due = Vec::new();
+        for instance in self.instances.values() {
+            let status = instance.status();
+            let (exit_code, finished_at) = match (&status.state, status.finished_at) {
+                (InstanceState::Exited { code }, Some(at)) => (Some(*code), at),
+                (InstanceState::Failed { .. }, Some(at)) => (None, at),
+                _ => continue,
+            };
+
+            let Some(deployment) = self.deployments.get(&instance.deployment) else {
+                continue;
+            };
+            let restart = &deployment.resolved.restart;
+            if restart.should_restart(exit_code, instance.restarts)
+                && now - finished_at >= restart.backoff(instance.restarts)
+            {
+                due.push((instance.deployment.clone(), instance.replica, instance.restarts + 1));
+            }
+        }
+
+        let mut restarted = Vec::new();
+        for (key, replica, restarts) in due {
+            let id = Instance::make_id(&key, replica);
+            tracing::info!("Restarting instance {} (restart #{})", id, restarts);
+
+            self.instances.remove(&id);
+            let resolved = self.deployments[&key].resolved.clone();
+            self.spawn_instances(&key, &resolved, &[replica]);
+            if let Some(instance) = self.instances.get_mut(&id) {
+                instance.restarts = restarts;
+            }
+            restarted.push(id);
+        }
+
+        restarted
+    }
+
+    /// Spawns replicas of a deployment according to its isolation mode.
+    fn spawn_instances(&mut self, key: &DeploymentKey, resolved: &FullDeployment, replicas: &[u32]) {
+        match resolved.isolation {
+            IsolationMode::Main => self.spawn_local_instances(key, resolved, replicas),
+            IsolationMode::Worker => self.spawn_worker_instances(key, resolved, replicas),
+        }
+    }
+
+    /// Spawns replicas inside the daemon process.
+    fn spawn_local_instances(&mut self, key: &DeploymentKey, resolved: &FullDeployment, replicas: &[u32]) {
+        let options = InstanceOptions {
+            entrypoint: resolved.startup.entrypoint.clone(),
+            args: vec![key.module_id.clone()],
+            trusted: resolved.trusted,
+            limits: self.effective_limits(key).unwrap_or_default(),
+            startup_timeout: resolved.startup.timeout(),
+            call_timeout: resolved.resources.call_timeout(),
+            shutdown: resolved.startup.shutdown.clone(),
+            shutdown_timeout: resolved.startup.timeout(),
+            ..Default::default()
+        };
+
+        let loaded = self.loaded_module(&key.module_id);
+        for &replica in replicas {
+            let instance = match &loaded {
+                Ok(loaded) => Instance::spawn(
+                    key.clone(),
+                    replica,
+                    self.engine.clone(),
+                    loaded.executable.clone(),
+                    options.clone(),
+                    resolved.r#async,
+                ),
+                Err(e) => Instance::failed(key.clone(), replica, e.to_string()),
+            };
+            self.instances.insert(instance.id.clone(), instance);
+        }
+    }
+
+    /// Starts replicas in the worker process of the deployment's group.
+    fn spawn_worker_instances(&mut self, key: &DeploymentKey, resolved: &FullDeployment, replicas: &[u32]) {
+        let artifact_path = match self.loaded_module(&key.module_id) {
+            Ok(loaded) => loaded.artifact_path,
+            Err(e) => {
+                tracing::warn!("Module cache unavailable for {}: {}", key.module_id, e);
+                None
+            }
+        };
+        for &replica in replicas {
+            let started = match (self.modules.get(&key.module_id), self.effective_limits(key)) {
+                (Some(module), Ok(limits)) => {
+                    self.workers.start_instance(key, replica, module, resolved, limits, artifact_path.clone())
+                }
+                (None, _) => Err(ArcellaError::ModuleNotFound(key.module_id.clone())),
+                (_, Err(e)) => Err(e),
+            };
+            let instance = started
+                .unwrap_or_else(|e| Instance::failed(key.clone(), replica, e.to_string()));
+            self.instances.insert(instance.id.clone(), instance);
+        }
+    }
+
+    /// Shuts down the worker processes of groups without worker-isolated instances.
+    fn retire_idle_workers(&mut self) {
+        let groups_in_use: HashSet<&str> = self.instances
+            .values()
+            .filter(|i| {
+                self.deployments
+                    .get(&i.deployment)
+                    .is_some_and(|d| d.resolved.isolation == IsolationMode::Worker)
+            })
+            .map(|i| i.deployment.group.as_str())
+            .collect();
+
+        self.workers.retire_idle(&groups_in_use);
+    }
+
+    /// Computes the resource limits applied to the instances of a deployment.
+    ///
+    /// The memory limit is `resources.memory_mb` of the deployment, capped by
+    /// `capabilities.resources.memory_max` of the module manifest. The fuel budget
+    /// is `resources.fuel` of the deployment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the deployment or its module does not exist.
+    pub fn effective_limits(&self, key: &DeploymentKey) -> ArcellaResult<ResourceLimits> {
+        let resources = &self.get_deployment(key)?.resolved.resources;
+        let module = self.get_module(&key.module_id)?;
+
+        let deployment_memory = resources.memory_mb.map(|mb| u64::from(mb) * 1024 * 1024);
+        let memory_bytes = match (deployment_memory, module.manifest.capabilities.resources.memory_max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        Ok(ResourceLimits { memory_bytes, fuel: resources.fuel })
+    }
+
+    /// Returns an instance by its ID.
+    pub fn get_instance(&self, instance_id: &str) -> ArcellaResult<&Instance> {
+        self.instances
+            .get(instance_id)
+            .ok_or_else(|| ArcellaError::InstanceNotFound(instance_id.to_string()))
+    }
+
+    /// Returns an installed module compiled for execution.
+    ///
+    /// Modules are normally compiled off the runtime lock by [`prepare_module`];
+    /// a module that was not prepared is compiled here, blocking the caller.
+    fn loaded_module(&mut self, module_id: &str) -> ArcellaResult<LoadedModule> {
+        if let Some(loaded) = self.compiled.get(module_id) {
+            return Ok(loaded.clone());
+        }
+
+        let module = self.get_module(module_id)?;
+        tracing::debug!("Module {} was not prepared, compiling it in place", module_id);
+        let loaded = self.cache
+            .load(&self.engine, module_id, &module.wasm_path, &module.blob)
+            .map_err(|e| ArcellaError::RuntimeError(format!("Failed to compile {}: {}", module_id, e)))?;
+        self.compiled.insert(module_id.to_string(), loaded.clone());
+        Ok(loaded)
+    }
+
+    /// Returns the job that compiles an installed module, or `None` if the module
+    /// is already compiled or not installed.
+    fn compile_job(&self, module_id: &str) -> Option<CompileJob> {
+        if self.compiled.contains_key(module_id) {
+            return None;
+        }
+        let module = self.modules.get(module_id)?;
+        Some(CompileJob {
+            module_id: module_id.to_string(),
+            wasm_path: module.wasm_path.clone(),
+            blob: module.blob.clone(),
+            engine: self.engine.clone(),
+            cache: self.cache.clone(),
+        })
+    }
+
+    /// Records the result of a [`CompileJob`], unless the module was removed or
+    /// replaced in the meantime.
+    fn add_compiled(&mut self, compiled: Option<(String, PathBuf, LoadedModule)>) {
+        let Some((module_id, wasm_path, loaded)) = compiled else { return };
+        if self.modules.get(&module_id).is_some_and(|module| module.wasm_path == wasm_path) {
+            self.compiled.entry(module_id).or_insert(loaded);
+        }
+    }
+
+    /// Returns all instances sorted by ID.
+    pub fn list_instances(&self) -> Vec<&Instance> {
+        let mut instances: Vec<_> = self.instances.values().collect();
+        instances.sort_by(|a, b| (&a.deployment, a.replica).cmp(&(&b.deployment, b.replica)));
+        instances
+    }
+
     #[cfg(test)]
     pub async fn new_for_tests(config: Arc<ArcellaConfig>) -> ArcellaResult<Self> {
 
@@ -119,3 +992,892 @@ impl ArcellaRuntime{
     }
 
 }
+
+/// Compilation of an installed module, run without holding the runtime lock.
+struct CompileJob {
+    module_id: String,
+    wasm_path: PathBuf,
+    blob: String,
+    engine: Engine,
+    cache: Arc<cache::ModuleCache>,
+}
+
+impl CompileJob {
+    /// Compiles the module through the module cache on a blocking thread.
+    ///
+    /// # Returns
+    ///
+    /// The module ID, the compiled `.wasm` file and the loaded module, or `None`
+    /// if compilation failed; the failure is reported again when an instance of
+    /// the module is started.
+    async fn run(self) -> Option<(String, PathBuf, LoadedModule)> {
+        let Self { module_id, wasm_path, blob, engine, cache } = self;
+        let (id, path) = (module_id.clone(), wasm_path.clone());
+        match tokio::task::spawn_blocking(move || cache.load(&engine, &id, &path, &blob)).await {
+            Ok(Ok(loaded)) => Some((module_id, wasm_path, loaded)),
+            Ok(Err(e)) => {
+                tracing::debug!("Failed to compile {}: {}", module_id, e);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Compilation of {} did not complete: {}", module_id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Compiles an installed module through the module cache on a blocking thread,
+/// so that starting its instances does not compile while the runtime lock is held.
+///
+/// Call before taking the write lock for an operation that may start instances
+/// of the module (applying, scaling or starting a deployment). Does nothing if
+/// the module is already compiled or not installed.
+pub async fn prepare_module(runtime: &RwLock<ArcellaRuntime>, module_id: &str) {
+    let Some(job) = runtime.read().await.compile_job(module_id) else { return };
+    let compiled = job.run().await;
+    runtime.write().await.add_compiled(compiled);
+}
+
+/// Installs a module from a `.wasm` file into `modules_dir`.
+///
+/// The bundle is validated with [`PreparedModule::from_wasm_path`] before anything
+/// is written. Since that may compile the module, it runs on a blocking thread
+/// before the runtime's write lock is taken; the module is then stored by
+/// [`ArcellaRuntime::install_prepared`].
+///
+/// # Arguments
+///
+/// * `wasm_path` — Path to the `.wasm` file to install.
+///
+/// # Returns
+///
+/// The registry entry of the newly installed module.
+///
+/// # Errors
+///
+/// Returns an error if the bundle is invalid, if a module with the same ID is
+/// already installed, or if the files cannot be copied.
+pub async fn install_module(
+    runtime: &RwLock<ArcellaRuntime>,
+    wasm_path: &Path,
+) -> ArcellaResult<InstalledModule> {
+    let engine = runtime.read().await.engine.clone();
+    let path = wasm_path.to_path_buf();
+    let prepared = tokio::task::spawn_blocking(move || PreparedModule::from_wasm_path(&engine, &path))
+        .await
+        .map_err(|e| ArcellaError::RuntimeError(format!("Validation of {:?} did not complete: {}", wasm_path, e)))??;
+
+    runtime.write().await.install_prepared(prepared).await
+}
+
+/// Interval between two passes of the instance supervisor.
+const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Starts the background task that periodically calls [`ArcellaRuntime::supervise`].
+pub fn spawn_supervisor(runtime: Arc<RwLock<ArcellaRuntime>>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SUPERVISOR_INTERVAL);
+        loop {
+            interval.tick().await;
+            runtime.write().await.supervise();
+        }
+    })
+}
+
+/// Starts the background task that periodically calls [`ArcellaRuntime::check_integrity`].
+pub fn spawn_integrity_monitor(
+    runtime: Arc<RwLock<ArcellaRuntime>>,
+    period: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        // The first tick completes immediately; the files were just loaded.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            runtime.write().await.check_integrity().await;
+        }
+    })
+}
+
+/// Returns `true` if two resolved deployments differ at most in their replica count.
+fn same_except_replicas(a: &FullDeployment, b: &FullDeployment) -> bool {
+    FullDeployment { replicas: b.replicas, ..a.clone() } == *b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{RestartConfig, RestartPolicy};
+    use arcella_types::spec::ComponentItemSpec;
+    use arcella_wasmtime::executor::WasmKind;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn write_component(dir: &Path, file_name: &str) -> PathBuf {
+        let wasm = wat::parse_str("(component)").unwrap();
+        let path = dir.join(file_name);
+        std::fs::write(&path, wasm).unwrap();
+        path
+    }
+
+    async fn create_runtime(base_dir: &Path) -> ArcellaRuntime {
+        let config = Arc::new(ArcellaConfig::for_tests(base_dir));
+        ArcellaRuntime::new_for_tests(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_install_module_and_rescan() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        let module = runtime.install_module_from_path(&wasm_path).await.unwrap();
+
+        assert_eq!(module.id(), "hello@0.1.0");
+        assert!(module.wasm_path.starts_with(&runtime.config.modules_dir));
+        assert!(module.wasm_path.is_file());
+        assert_eq!(runtime.list_modules().len(), 1);
+
+        // A new runtime over the same base directory picks the module up again.
+        let runtime = create_runtime(temp_dir.path()).await;
+        assert!(runtime.get_module("hello@0.1.0").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_install_module_off_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (component
+              (core module $m (func (export "run")))
+              (core instance $i (instantiate $m))
+              (func (export "run") (canon lift (core func $i "run"))))
+        "#).unwrap();
+        let wasm_path = source_dir.path().join("hello@0.1.0.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+
+        let runtime = RwLock::new(create_runtime(temp_dir.path()).await);
+        let module = install_module(&runtime, &wasm_path).await.unwrap();
+        assert_eq!(module.id(), "hello@0.1.0");
+        assert_eq!(module.kind, WasmKind::Component);
+        assert!(module.manifest.exports.contains_key("run"));
+        assert_eq!(module.wasm_path, runtime.read().await.storage.blob_path(&module.blob));
+
+        let err = install_module(&runtime, &wasm_path).await.unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleAlreadyInstalled(id) if id == "hello@0.1.0"));
+
+        // The entry built from the prepared bundle matches the one loaded on restart.
+        let restarted = create_runtime(temp_dir.path()).await;
+        let reloaded = restarted.get_module("hello@0.1.0").unwrap();
+        assert_eq!(reloaded.manifest, module.manifest);
+        assert_eq!((&reloaded.wasm_path, &reloaded.blob), (&module.wasm_path, &module.blob));
+    }
+
+    #[tokio::test]
+    async fn test_install_module_twice_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+
+        let err = runtime.install_module_from_path(&wasm_path).await.unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleAlreadyInstalled(id) if id == "hello@0.1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_remove_module() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        let module_dir = runtime.storage.module_dir("hello@0.1.0");
+        assert!(module_dir.is_dir());
+
+        runtime.remove_module("hello@0.1.0").await.unwrap();
+        assert!(!module_dir.exists());
+        assert!(runtime.get_module("hello@0.1.0").is_err());
+
+        let err = runtime.remove_module("hello@0.1.0").await.unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleNotFound(_)));
+    }
+
+    fn spec(module_id: &str, group: &str, replicas: u32) -> DeploymentSpec {
+        DeploymentSpec {
+            module_id: module_id.to_string(),
+            group: group.to_string(),
+            replicas,
+            overrides: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deployment_lifecycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+
+        let err = runtime.apply_deployment(spec("hello@0.1.0", "web", 2)).unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleNotFound(_)));
+
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.apply_deployment(spec("hello@0.1.0", "web", 2)).unwrap();
+        runtime.apply_deployment(spec("hello@0.1.0", "batch", 1)).unwrap();
+        assert_eq!(runtime.list_deployments().len(), 2);
+
+        let key = DeploymentKey::new("hello@0.1.0", "web");
+        let deployment = runtime.scale_deployment(&key, 5).unwrap();
+        assert_eq!(deployment.resolved.replicas, 5);
+        assert!(runtime.scale_deployment(&key, 0).is_err());
+
+        let err = runtime.remove_module("hello@0.1.0").await.unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleInUse(_, 2)));
+
+        runtime.undeploy(&key).unwrap();
+        runtime.undeploy(&DeploymentKey::new("hello@0.1.0", "batch")).unwrap();
+        assert!(runtime.undeploy(&key).is_err());
+        runtime.remove_module("hello@0.1.0").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_integrity_violation_refuses_deployments() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+        let config_path = temp_dir.path().join("arcella.toml");
+        std::fs::write(&config_path, "[log]\n").unwrap();
+
+        let mut config = ArcellaConfig::for_tests(temp_dir.path());
+        config.integrity_check_paths = vec![config_path.clone()];
+        config.integrity_action = IntegrityAction::RefuseDeployments;
+        let mut runtime = ArcellaRuntime::new_for_tests(Arc::new(config)).await.unwrap();
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+
+        assert_eq!(runtime.check_integrity().await, None);
+        runtime.apply_deployment(spec("hello@0.1.0", "web", 1)).unwrap();
+
+        let modified = std::time::SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options().write(true).open(&config_path).unwrap().set_modified(modified).unwrap();
+        assert!(runtime.check_integrity().await.is_some());
+        let err = runtime.apply_deployment(spec("hello@0.1.0", "batch", 1)).unwrap_err();
+        assert!(err.to_string().contains("refused"));
+
+        // Reloading the configuration takes the modified file as the new baseline.
+        runtime.reset_integrity().unwrap();
+        assert_eq!(runtime.check_integrity().await, None);
+        runtime.apply_deployment(spec("hello@0.1.0", "batch", 1)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_state_is_restored_after_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let web = DeploymentKey::new("hello@0.1.0", "web");
+        let batch = DeploymentKey::new("hello@0.1.0", "batch");
+        let created_at = {
+            let mut runtime = create_runtime(temp_dir.path()).await;
+            runtime.install_module_from_path(&wasm_path).await.unwrap();
+            runtime.apply_deployment(spec("hello@0.1.0", "web", 3)).unwrap();
+            runtime.apply_deployment(spec("hello@0.1.0", "batch", 1)).unwrap();
+            runtime.stop_deployment(&batch).unwrap();
+            assert_eq!(runtime.list_instances().len(), 3);
+            runtime.shutdown().await.unwrap();
+            runtime.get_deployment(&web).unwrap().created_at
+        };
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        assert_eq!(runtime.list_deployments().len(), 2);
+        let deployment = runtime.get_deployment(&web).unwrap();
+        assert_eq!((deployment.resolved.replicas, deployment.stopped), (3, false));
+        assert_eq!(deployment.created_at, created_at);
+        assert!(runtime.get_deployment(&batch).unwrap().stopped);
+        assert_eq!(runtime.list_instances().len(), 3);
+
+        runtime.start_deployment(&batch).unwrap();
+        assert_eq!(runtime.list_instances().len(), 4);
+        runtime.undeploy(&web).unwrap();
+
+        let runtime = create_runtime(temp_dir.path()).await;
+        assert_eq!(runtime.desired_state().deployments.len(), 1);
+        assert_eq!(runtime.list_instances().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unrestorable_deployment_is_kept_in_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let web = DeploymentKey::new("hello@0.1.0", "web");
+        let blob_path = {
+            let mut runtime = create_runtime(temp_dir.path()).await;
+            let module = runtime.install_module_from_path(&wasm_path).await.unwrap();
+            runtime.apply_deployment(spec("hello@0.1.0", "web", 2)).unwrap();
+            runtime.shutdown().await.unwrap();
+            module.wasm_path
+        };
+        std::fs::remove_file(&blob_path).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        assert!(runtime.get_deployment(&web).is_err());
+        assert_eq!(runtime.unrestored_deployments().len(), 1);
+        runtime.shutdown().await.unwrap();
+
+        let state = RuntimeState::load(&runtime.storage.state_path()).unwrap().unwrap();
+        assert_eq!(state.deployments.len(), 1);
+        assert_eq!(state.deployments[0].key(), web);
+        assert_eq!(state.deployments[0].spec.replicas, 2);
+
+        // The entry stays until an operator undeploys it.
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.undeploy(&web).unwrap();
+        assert!(runtime.undeploy(&web).is_err());
+        let state = RuntimeState::load(&runtime.storage.state_path()).unwrap().unwrap();
+        assert!(state.deployments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prepared_module_is_not_compiled_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        let runtime = RwLock::new(runtime);
+
+        prepare_module(&runtime, "hello@0.1.0").await;
+        prepare_module(&runtime, "missing@0.1.0").await;
+        let mut runtime = runtime.into_inner();
+        assert!(runtime.compiled.contains_key("hello@0.1.0"));
+        let lookups = |runtime: &ArcellaRuntime| {
+            let stats = runtime.cache.stats().unwrap();
+            stats.hits + stats.misses
+        };
+        let before = lookups(&runtime);
+
+        runtime.apply_deployment(spec("hello@0.1.0", "web", 2)).unwrap();
+        assert_eq!(lookups(&runtime), before);
+
+        runtime.remove_module("hello@0.1.0").await.unwrap_err();
+        runtime.undeploy(&DeploymentKey::new("hello@0.1.0", "web")).unwrap();
+        runtime.remove_module("hello@0.1.0").await.unwrap();
+        assert!(runtime.compiled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_modified_blob_is_not_compiled() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        let module = runtime.install_module_from_path(&wasm_path).await.unwrap();
+        std::fs::remove_file(&module.wasm_path).unwrap();
+        std::fs::write(&module.wasm_path, b"\0asm-tampered").unwrap();
+        let runtime = RwLock::new(runtime);
+
+        prepare_module(&runtime, "hello@0.1.0").await;
+        let mut runtime = runtime.into_inner();
+        assert!(runtime.compiled.is_empty());
+        let err = runtime.loaded_module("hello@0.1.0").err().unwrap();
+        assert!(err.to_string().contains("corrupted"), "{}", err);
+    }
+
+    async fn wait_until_finished(runtime: &ArcellaRuntime) {
+        for _ in 0..200 {
+            if runtime.instances.values().all(|i| i.status().state.is_finished()) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("instances did not finish in time");
+    }
+
+    #[tokio::test]
+    async fn test_core_module_instances() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+              (memory (export "memory") 1)
+              (func (export "_start") (call $exit (i32.const 7)))
+              (func (export "crash") unreachable))
+        "#).unwrap();
+        // A core module without `component.toml` takes its ID from the file name.
+        let wasm_path = source_dir.path().join("exit@0.1.0.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        let module = runtime.install_module_from_path(&wasm_path).await.unwrap();
+        assert_eq!(module.kind, WasmKind::CoreModule);
+        assert!(module.manifest.exports.contains_key("crash"));
+
+        runtime.apply_deployment(spec("exit@0.1.0", "batch", 2)).unwrap();
+        assert_eq!(runtime.list_instances().len(), 2);
+        wait_until_finished(&runtime).await;
+        for instance in runtime.list_instances() {
+            assert_eq!(instance.status().state, InstanceState::Exited { code: 7 });
+        }
+
+        // Changing the entry point restarts the replicas with the new configuration.
+        let mut crash = spec("exit@0.1.0", "batch", 1);
+        crash.overrides.startup = Some(crate::manifest::StartupConfig {
+            entrypoint: Some("crash".into()),
+            ..Default::default()
+        });
+        runtime.apply_deployment(crash).unwrap();
+        assert_eq!(runtime.list_instances().len(), 1);
+        wait_until_finished(&runtime).await;
+        let state = runtime.list_instances()[0].status().state;
+        assert!(matches!(state, InstanceState::Failed { reason } if reason.contains("unreachable")));
+
+        runtime.undeploy(&DeploymentKey::new("exit@0.1.0", "batch")).unwrap();
+        assert!(runtime.instances.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_worker_process_per_group() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.apply_deployment(spec("hello@0.1.0", "web", 2)).unwrap();
+        runtime.apply_deployment(spec("hello@0.1.0", "batch", 1)).unwrap();
+
+        // A reactor component stays resident in its worker.
+        for _ in 0..200 {
+            if runtime.instances.values().all(|i| i.status().state == InstanceState::Running) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(runtime.instances.values().all(|i| i.status().state == InstanceState::Running));
+
+        let workers = runtime.workers.status();
+        assert_eq!(workers.len(), 2);
+        assert_eq!((workers[0].group.as_str(), workers[0].instances), ("batch", 1));
+        assert_eq!((workers[1].group.as_str(), workers[1].instances), ("web", 2));
+        assert!(workers.iter().all(|w| w.alive && w.healthy && w.pid.is_some()));
+
+        runtime.undeploy(&DeploymentKey::new("hello@0.1.0", "web")).unwrap();
+        let workers = runtime.workers.status();
+        assert_eq!(workers.len(), 1);
+        assert_eq!(workers[0].group, "batch");
+
+        runtime.shutdown().await.unwrap();
+        assert!(runtime.workers.status().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reinstalled_module_is_installed_again_in_worker() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let write_exit = |code: i32| {
+            let wasm = wat::parse_str(format!(r#"
+                (module
+                  (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+                  (memory (export "memory") 1)
+                  (func (export "_start") (call $exit (i32.const {}))))
+            "#, code)).unwrap();
+            let wasm_path = source_dir.path().join("exit.wasm");
+            std::fs::write(&wasm_path, wasm).unwrap();
+            wasm_path
+        };
+        std::fs::write(
+            source_dir.path().join("component.toml"),
+            "[component]\nname = \"exit\"\nversion = \"0.1.0\"\n",
+        ).unwrap();
+        let hello_dir = TempDir::new().unwrap();
+        let hello_path = write_component(hello_dir.path(), "hello@0.1.0.wasm");
+
+        // The resident `hello` keeps the worker of the group alive throughout.
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&hello_path).await.unwrap();
+        runtime.apply_deployment(spec("hello@0.1.0", "web", 1)).unwrap();
+        runtime.install_module_from_path(&write_exit(7)).await.unwrap();
+        runtime.apply_deployment(spec("exit@0.1.0", "web", 1)).unwrap();
+        let id = Instance::make_id(&DeploymentKey::new("exit@0.1.0", "web"), 0);
+        wait_until_finished_instance(&runtime, &id).await;
+        assert_eq!(runtime.instances[&id].status().state, InstanceState::Exited { code: 7 });
+        let pid = runtime.workers.status()[0].pid;
+
+        runtime.undeploy(&DeploymentKey::new("exit@0.1.0", "web")).unwrap();
+        runtime.remove_module("exit@0.1.0").await.unwrap();
+        runtime.install_module_from_path(&write_exit(9)).await.unwrap();
+        runtime.apply_deployment(spec("exit@0.1.0", "web", 1)).unwrap();
+        wait_until_finished_instance(&runtime, &id).await;
+        assert_eq!(runtime.workers.status()[0].pid, pid);
+        assert_eq!(runtime.instances[&id].status().state, InstanceState::Exited { code: 9 });
+
+        runtime.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_worker_rejects_modified_blob_without_artifact() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let exit = |code: i32| wat::parse_str(format!(r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+              (memory (export "memory") 1)
+              (func (export "_start") (call $exit (i32.const {}))))
+        "#, code)).unwrap();
+        let wasm_path = source_dir.path().join("exit.wasm");
+        std::fs::write(&wasm_path, exit(7)).unwrap();
+        std::fs::write(
+            source_dir.path().join("component.toml"),
+            "[component]\nname = \"exit\"\nversion = \"0.1.0\"\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        let module = runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.loaded_module("exit@0.1.0").unwrap();
+
+        // The blob is modified after the daemon verified it, and the artifact is gone.
+        runtime.cache.clear().unwrap();
+        std::fs::remove_file(&module.wasm_path).unwrap();
+        std::fs::write(&module.wasm_path, exit(9)).unwrap();
+
+        runtime.apply_deployment(spec("exit@0.1.0", "batch", 1)).unwrap();
+        let id = Instance::make_id(&DeploymentKey::new("exit@0.1.0", "batch"), 0);
+        wait_until_finished_instance(&runtime, &id).await;
+        let state = runtime.instances[&id].status().state;
+        assert!(matches!(state, InstanceState::Failed { .. }), "{:?}", state);
+
+        runtime.shutdown().await.unwrap();
+    }
+
+    async fn wait_until_finished_instance(runtime: &ArcellaRuntime, id: &str) {
+        for _ in 0..200 {
+            if runtime.instances[id].status().state.is_finished() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("instance {} did not finish in time", id);
+    }
+
+    fn restart_spec(module_id: &str, group: &str, policy: RestartPolicy, max_retries: u32) -> DeploymentSpec {
+        let mut spec = spec(module_id, group, 1);
+        spec.overrides.restart = Some(RestartConfig {
+            policy,
+            max_retries,
+            backoff_seconds: 0,
+            max_backoff_seconds: 0,
+        });
+        spec
+    }
+
+    #[tokio::test]
+    async fn test_restart_on_failure_with_max_retries() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+              (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+              (memory (export "memory") 1)
+              (func (export "_start") (call $exit (i32.const 7))))
+        "#).unwrap();
+        let wasm_path = source_dir.path().join("exit.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+        std::fs::write(
+            source_dir.path().join("component.toml"),
+            "[component]\nname = \"exit\"\nversion = \"0.1.0\"\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.apply_deployment(restart_spec("exit@0.1.0", "batch", RestartPolicy::OnFailure, 2)).unwrap();
+
+        let id = Instance::make_id(&DeploymentKey::new("exit@0.1.0", "batch"), 0);
+        for expected_restarts in 1..=2 {
+            wait_until_finished(&runtime).await;
+            assert_eq!(runtime.supervise(), vec![id.clone()]);
+            assert_eq!(runtime.instances[&id].restarts, expected_restarts);
+        }
+
+        // The retry budget is exhausted: the last exit status stays visible.
+        wait_until_finished(&runtime).await;
+        assert!(runtime.supervise().is_empty());
+        assert_eq!(runtime.instances[&id].status().state, InstanceState::Exited { code: 7 });
+    }
+
+    #[tokio::test]
+    async fn test_crashed_worker_is_respawned() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.apply_deployment(restart_spec("hello@0.1.0", "web", RestartPolicy::Always, 0)).unwrap();
+
+        let id = Instance::make_id(&DeploymentKey::new("hello@0.1.0", "web"), 0);
+        let wait_for = |runtime: &ArcellaRuntime, check: fn(&InstanceState) -> bool| {
+            let status = runtime.instances[&id].status();
+            check(&status.state)
+        };
+        for _ in 0..200 {
+            if wait_for(&runtime, |s| *s == InstanceState::Running) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let pid = runtime.workers.status()[0].pid.unwrap();
+
+        std::process::Command::new("kill").args(["-9", &pid.to_string()]).status().unwrap();
+        for _ in 0..200 {
+            if wait_for(&runtime, InstanceState::is_finished) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let state = runtime.instances[&id].status().state;
+        assert!(matches!(state, InstanceState::Failed { reason } if reason.contains("worker process exited")));
+
+        assert_eq!(runtime.supervise(), vec![id.clone()]);
+        assert_eq!(runtime.instances[&id].restarts, 1);
+        let workers = runtime.workers.status();
+        assert_eq!(workers.len(), 1);
+        assert_ne!(workers[0].pid.unwrap(), pid);
+
+        for _ in 0..200 {
+            if wait_for(&runtime, |s| *s == InstanceState::Running) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(runtime.instances[&id].status().state, InstanceState::Running);
+        runtime.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resource_limits_are_enforced() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "_start") (loop $l (br $l))))
+        "#).unwrap();
+        let wasm_path = source_dir.path().join("spin.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+        std::fs::write(
+            source_dir.path().join("component.toml"),
+            "[component]\nname = \"spin\"\nversion = \"0.1.0\"\n\
+             [component.capabilities.resources]\nmemory_max = 1048576\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+
+        let mut spec = spec("spin@0.1.0", "batch", 1);
+        spec.overrides.resources = Some(crate::manifest::ResourceRequirements {
+            memory_mb: Some(4),
+            fuel: Some(100_000),
+            ..Default::default()
+        });
+        runtime.apply_deployment(spec).unwrap();
+
+        let key = DeploymentKey::new("spin@0.1.0", "batch");
+        let limits = runtime.effective_limits(&key).unwrap();
+        assert_eq!(limits, ResourceLimits { memory_bytes: Some(1048576), fuel: Some(100_000) });
+
+        wait_until_finished(&runtime).await;
+        let state = runtime.get_instance(&Instance::make_id(&key, 0)).unwrap().status().state;
+        assert!(matches!(state, InstanceState::Failed { reason } if reason.contains("out of fuel")));
+    }
+
+    #[tokio::test]
+    async fn test_call_timeout_interrupts_main_isolation_guest() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"(module (func (export "_start") (loop $l (br $l))))"#).unwrap();
+        let wasm_path = source_dir.path().join("spin.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+        std::fs::write(
+            source_dir.path().join("component.toml"),
+            "[component]\nname = \"spin\"\nversion = \"0.1.0\"\n",
+        ).unwrap();
+        std::fs::write(
+            source_dir.path().join("deployment-template.toml"),
+            "[deployment]\nisolation = \"main\"\ntrusted = true\nasync = true\n\
+             [deployment.resources]\ncall_timeout_seconds = 1\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.apply_deployment(spec("spin@0.1.0", "core", 1)).unwrap();
+
+        // The guest spins on the test's single-threaded runtime, yet timers keep firing.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let id = Instance::make_id(&DeploymentKey::new("spin@0.1.0", "core"), 0);
+        assert_eq!(runtime.instances[&id].status().state, InstanceState::Running);
+
+        wait_until_finished(&runtime).await;
+        let state = runtime.instances[&id].status().state;
+        assert!(matches!(state, InstanceState::Failed { reason } if reason.contains("timed out")));
+    }
+
+    #[tokio::test]
+    async fn test_main_isolation_deployment_single_replica() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm_path = write_component(source_dir.path(), "hello@0.1.0.wasm");
+        std::fs::write(
+            source_dir.path().join("deployment-template.toml"),
+            "[deployment]\nisolation = \"main\"\ntrusted = true\nasync = true\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+
+        assert!(runtime.apply_deployment(spec("hello@0.1.0", "core", 2)).is_err());
+        runtime.apply_deployment(spec("hello@0.1.0", "core", 1)).unwrap();
+        assert!(runtime.scale_deployment(&DeploymentKey::new("hello@0.1.0", "core"), 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_trusted_component_stays_running_until_undeployed() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (component
+              (core module $m (func (export "start")))
+              (core instance $i (instantiate $m))
+              (func (export "start") (canon lift (core func $i "start"))))
+        "#).unwrap();
+        let wasm_path = source_dir.path().join("svc@0.1.0.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+        std::fs::write(
+            source_dir.path().join("deployment-template.toml"),
+            "[deployment]\nisolation = \"main\"\ntrusted = true\nasync = true\n\
+             [deployment.startup]\nentrypoint = \"start\"\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        let module = runtime.install_module_from_path(&wasm_path).await.unwrap();
+        assert_eq!(module.kind, WasmKind::Component);
+
+        runtime.apply_deployment(spec("svc@0.1.0", "core", 1)).unwrap();
+        let id = Instance::make_id(&DeploymentKey::new("svc@0.1.0", "core"), 0);
+        for _ in 0..200 {
+            if runtime.instances[&id].status().state == InstanceState::Running {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // The entry point has returned, but the instance is resident.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(runtime.instances[&id].status().state, InstanceState::Running);
+
+        runtime.undeploy(&DeploymentKey::new("svc@0.1.0", "core")).unwrap();
+        assert!(runtime.instances.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_order_follows_dependencies() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        for id in ["app@0.1.0", "db@0.1.0", "log@0.1.0"] {
+            let wasm_path = write_component(source_dir.path(), &format!("{}.wasm", id));
+            runtime.install_module_from_path(&wasm_path).await.unwrap();
+        }
+
+        // app -> db -> log
+        let interface = |name: &str| (name.to_string(), ComponentItemSpec::CoreFunc(String::new()));
+        fn manifest<'a>(runtime: &'a mut ArcellaRuntime, id: &str) -> &'a mut ComponentManifest {
+            &mut runtime.modules.get_mut(id).unwrap().manifest
+        }
+        manifest(&mut runtime, "app@0.1.0").imports.extend([interface("test:db/query")]);
+        manifest(&mut runtime, "db@0.1.0").exports.extend([interface("test:db/query")]);
+        manifest(&mut runtime, "db@0.1.0").imports.extend([interface("test:log/write")]);
+        manifest(&mut runtime, "log@0.1.0").exports.extend([interface("test:log/write")]);
+
+        for id in ["app@0.1.0", "db@0.1.0", "log@0.1.0"] {
+            runtime.apply_deployment(spec(id, "core", 1)).unwrap();
+        }
+        let key = |id: &str| DeploymentKey::new(id, "core");
+        assert_eq!(
+            runtime.shutdown_order(),
+            vec![vec![key("app@0.1.0")], vec![key("db@0.1.0")], vec![key("log@0.1.0")]]
+        );
+
+        // A cycle is stopped in a single wave after its dependents.
+        manifest(&mut runtime, "log@0.1.0").imports.extend([interface("test:db/query")]);
+        assert_eq!(
+            runtime.shutdown_order(),
+            vec![vec![key("app@0.1.0")], vec![key("db@0.1.0"), key("log@0.1.0")]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_calls_shutdown_hook() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let wasm = wat::parse_str(r#"
+            (component
+              (core module $m
+                (func (export "start"))
+                (func (export "stop"))
+                (func (export "fail") unreachable))
+              (core instance $i (instantiate $m))
+              (func (export "start") (canon lift (core func $i "start")))
+              (func (export "stop") (canon lift (core func $i "stop")))
+              (func (export "fail") (canon lift (core func $i "fail"))))
+        "#).unwrap();
+        let wasm_path = source_dir.path().join("svc@0.1.0.wasm");
+        std::fs::write(&wasm_path, wasm).unwrap();
+        std::fs::write(
+            source_dir.path().join("deployment-template.toml"),
+            "[deployment]\nisolation = \"main\"\ntrusted = true\nasync = true\n\
+             [deployment.startup]\nentrypoint = \"start\"\nshutdown = \"stop\"\ntimeout_seconds = 1\n",
+        ).unwrap();
+
+        let mut runtime = create_runtime(temp_dir.path()).await;
+        runtime.install_module_from_path(&wasm_path).await.unwrap();
+        runtime.apply_deployment(spec("svc@0.1.0", "core", 1)).unwrap();
+        let mut failing = spec("svc@0.1.0", "edge", 1);
+        failing.overrides.startup = Some(crate::manifest::StartupConfig {
+            entrypoint: Some("start".into()),
+            shutdown: Some("fail".into()),
+            timeout_seconds: Some(1),
+        });
+        runtime.apply_deployment(failing).unwrap();
+
+        for _ in 0..200 {
+            if runtime.instances.values().all(|i| i.status().state == InstanceState::Running) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let keys = [DeploymentKey::new("svc@0.1.0", "core"), DeploymentKey::new("svc@0.1.0", "edge")];
+        let outcomes = runtime.shutdown_wave(&keys).await;
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].1, ShutdownOutcome::Finished(InstanceState::Exited { code: 0 }));
+        assert!(matches!(
+            &outcomes[1].1,
+            ShutdownOutcome::Finished(InstanceState::Failed { reason }) if reason.contains("trap")
+        ));
+        assert!(runtime.instances.is_empty());
+
+        runtime.shutdown().await.unwrap();
+        assert!(runtime.storage.state_path().exists());
+    }
+}
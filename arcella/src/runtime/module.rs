@@ -0,0 +1,133 @@
+// arcella/arcella/src/runtime/module.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Registry entries for installed modules.
+
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use wasmtime::Engine;
+
+use arcella_types::manifest::ComponentManifest;
+use arcella_wasmtime::executor::{detect_wasm_kind, WasmKind};
+
+use crate::error::{ArcellaError, Result as ArcellaResult};
+use crate::manifest::{ComponentBundle, DeploymentTemplate};
+use crate::storage::{ModuleRecord, MODULE_RECORD_FILENAME};
+
+/// A module that has been installed into `modules_dir` and registered in the runtime.
+///
+/// Installed modules are only registered, not started. They are keyed by their
+/// canonical `name@version` identifier (see [`ComponentManifest::id`]).
+#[derive(Debug, Clone)]
+pub struct InstalledModule {
+    /// Component manifest (from `component.toml` or extracted from the `.wasm`).
+    pub manifest: ComponentManifest,
+
+    /// Deployment template shipped with the module, if any.
+    pub template: Option<DeploymentTemplate>,
+
+    /// Path of the module's content-addressed blob in `modules_dir/blobs/`.
+    pub wasm_path: PathBuf,
+
+    /// SHA-256 of the binary (hex), as recorded in `module.json`.
+    pub blob: String,
+
+    /// Whether the binary is a core module or a component.
+    pub kind: WasmKind,
+
+    /// Time the module was installed.
+    pub installed_at: OffsetDateTime,
+}
+
+impl InstalledModule {
+    /// Loads an installed module from its storage record.
+    ///
+    /// The bundle (manifest and optional deployment template) is read from
+    /// `module_dir`, the binary from the verified blob at `wasm_path`, and the
+    /// installation time is taken from the mtime of `module.json`.
+    pub fn load(
+        engine: &Engine,
+        module_dir: &Path,
+        record: &ModuleRecord,
+        wasm_path: &Path,
+    ) -> ArcellaResult<Self> {
+        let bundle = ComponentBundle::from_stored(engine, module_dir, &record.module_id, wasm_path)?;
+        let kind = read_wasm_kind(wasm_path)?;
+        Self::from_prepared(PreparedModule { bundle, kind }, module_dir, record, wasm_path)
+    }
+
+    /// Creates the registry entry of a module that was just stored from
+    /// `prepared`, without parsing its blob at `wasm_path` again.
+    pub fn from_prepared(
+        prepared: PreparedModule,
+        module_dir: &Path,
+        record: &ModuleRecord,
+        wasm_path: &Path,
+    ) -> ArcellaResult<Self> {
+        let record_path = module_dir.join(MODULE_RECORD_FILENAME);
+        let installed_at = std::fs::metadata(&record_path)
+            .and_then(|m| m.modified())
+            .map(OffsetDateTime::from)
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: record_path })?;
+
+        Ok(Self {
+            manifest: prepared.bundle.component,
+            template: prepared.bundle.template,
+            wasm_path: wasm_path.to_path_buf(),
+            blob: record.blob.clone(),
+            kind: prepared.kind,
+            installed_at,
+        })
+    }
+
+    /// Returns the canonical module identifier: `name@version`.
+    pub fn id(&self) -> String {
+        self.manifest.id()
+    }
+}
+
+/// A `.wasm` file validated for installation, with its manifests.
+///
+/// Extracting the manifest of a module without `component.toml` compiles it, so
+/// modules are prepared off the runtime lock (see [`super::install_module`]).
+#[derive(Debug, Clone)]
+pub struct PreparedModule {
+    /// Manifests and source path of the module.
+    pub bundle: ComponentBundle,
+
+    /// Whether the binary is a core module or a component.
+    pub kind: WasmKind,
+}
+
+impl PreparedModule {
+    /// Validates the bundle of the `.wasm` file at `wasm_path`.
+    ///
+    /// This compiles and does file I/O; call it from a blocking context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the bundle is invalid.
+    pub fn from_wasm_path(engine: &Engine, wasm_path: &Path) -> ArcellaResult<Self> {
+        let bundle = ComponentBundle::from_wasm_path(engine, wasm_path)?;
+        let kind = read_wasm_kind(wasm_path)?;
+        Ok(Self { bundle, kind })
+    }
+
+    /// Returns the canonical module identifier: `name@version`.
+    pub fn id(&self) -> String {
+        self.bundle.component.id()
+    }
+}
+
+/// Reads a `.wasm` file and tells whether it is a core module or a component.
+fn read_wasm_kind(wasm_path: &Path) -> ArcellaResult<WasmKind> {
+    let bytes = std::fs::read(wasm_path)
+        .map_err(|e| ArcellaError::IoWithPath { source: e, path: wasm_path.to_path_buf() })?;
+    Ok(detect_wasm_kind(&bytes)?)
+}
@@ -0,0 +1,165 @@
+// arcella/arcella/src/runtime/state.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Durable desired state of the runtime.
+//!
+//! The runtime records its installed modules and deployments (with their replica
+//! counts and started/stopped state) in `base_dir/state.json` after every change,
+//! and restores them on startup. The file is always replaced atomically, so a
+//! crash in the middle of a write leaves the previous state intact.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use crate::error::{ArcellaError, Result as ArcellaResult};
+use crate::manifest::DeploymentSpec;
+
+use super::{Deployment, DeploymentKey};
+
+/// Version of the state file format written by this build.
+pub const STATE_VERSION: u32 = 1;
+
+/// Snapshot of the desired state of the runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeState {
+    /// Format version of the file.
+    pub version: u32,
+
+    /// IDs of the installed modules.
+    #[serde(default)]
+    pub modules: Vec<String>,
+
+    /// Applied deployments.
+    #[serde(default)]
+    pub deployments: Vec<DeploymentState>,
+}
+
+/// Persisted form of a [`Deployment`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentState {
+    /// Specification as submitted via `deployment:apply`.
+    pub spec: DeploymentSpec,
+
+    /// Whether the deployment was stopped with `deployment:stop`.
+    #[serde(default)]
+    pub stopped: bool,
+
+    /// Whether the deployment is owned by a `*.deployment.toml` file.
+    #[serde(default)]
+    pub managed: bool,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl From<&Deployment> for DeploymentState {
+    fn from(deployment: &Deployment) -> Self {
+        Self {
+            spec: deployment.spec.clone(),
+            stopped: deployment.stopped,
+            managed: deployment.managed,
+            created_at: deployment.created_at,
+            updated_at: deployment.updated_at,
+        }
+    }
+}
+
+impl DeploymentState {
+    /// Returns the `(module_id, group)` key of the deployment.
+    pub fn key(&self) -> DeploymentKey {
+        DeploymentKey::new(&self.spec.module_id, &self.spec.group)
+    }
+}
+
+impl RuntimeState {
+    /// Reads the state file.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the file does not exist (e.g. on the first start).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or if it was
+    /// written by a newer, incompatible version of Arcella.
+    pub fn load(path: &Path) -> ArcellaResult<Option<Self>> {
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(ArcellaError::IoWithPath { source: e, path: path.to_path_buf() }),
+        };
+
+        let state: Self = serde_json::from_slice(&content)?;
+        if state.version > STATE_VERSION {
+            return Err(ArcellaError::Internal(format!(
+                "State file {:?} has unsupported version {}", path, state.version
+            )));
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Writes the state file atomically: the content is written to a temporary
+    /// file, flushed to disk and renamed over the previous state.
+    pub fn save(&self, path: &Path) -> ArcellaResult<()> {
+        let content = serde_json::to_vec_pretty(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+
+        let result = std::fs::File::create(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(&content)?;
+                file.sync_all()
+            })
+            .and_then(|_| std::fs::rename(&tmp_path, path));
+
+        result.map_err(|e| ArcellaError::IoWithPath { source: e, path: path.to_path_buf() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_state_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+        assert_eq!(RuntimeState::load(&path).unwrap(), None);
+
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let state = RuntimeState {
+            version: STATE_VERSION,
+            modules: vec!["hello@0.1.0".into()],
+            deployments: vec![DeploymentState {
+                spec: DeploymentSpec {
+                    module_id: "hello@0.1.0".into(),
+                    group: "web".into(),
+                    replicas: 2,
+                    overrides: Default::default(),
+                },
+                stopped: true,
+                managed: false,
+                created_at: now,
+                updated_at: now,
+            }],
+        };
+        state.save(&path).unwrap();
+        assert_eq!(RuntimeState::load(&path).unwrap(), Some(state));
+        assert!(!path.with_extension("json.tmp").exists());
+
+        std::fs::write(&path, r#"{"version": 99}"#).unwrap();
+        assert!(RuntimeState::load(&path).is_err());
+    }
+}
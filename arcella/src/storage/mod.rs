@@ -7,17 +7,64 @@
 // This file may not be copied, modified, or distributed
 // except according to those terms.
 
+//! On-disk storage of the daemon: the base directory layout and installed modules.
+//!
+//! Installed module binaries are stored content-addressed in
+//! `modules_dir/blobs/<sha256>.wasm`, so that identical binaries installed under
+//! different IDs share one blob. Every module has a directory `modules_dir/<id>/`
+//! with its manifests and a `module.json` record that references the blob by hash.
+//! Blobs are verified against that hash when a module is loaded, and blobs that no
+//! module references any more are removed by [`StorageManager::gc`].
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::ArcellaConfig;
 use crate::error::{ArcellaError, Result as ArcellaResult};
 
+/// Manifest files that are copied together with a module's `.wasm` file.
+const BUNDLE_MANIFEST_FILES: [&str; 2] = ["component.toml", "deployment-template.toml"];
+
+/// Subdirectory of `modules_dir` that holds the content-addressed blobs.
+const BLOBS_DIR: &str = "blobs";
+
+/// Name of the runtime state file inside `base_dir`.
+const STATE_FILENAME: &str = "state.json";
+
+/// Name of the per-module metadata file inside `modules_dir/<id>/`.
+pub const MODULE_RECORD_FILENAME: &str = "module.json";
+
+/// Metadata of an installed module, stored as `modules_dir/<id>/module.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleRecord {
+    /// Canonical `name@version` module ID.
+    pub module_id: String,
+
+    /// Hex-encoded SHA-256 of the `.wasm` binary; names the blob in `modules_dir/blobs/`.
+    pub blob: String,
+
+    /// Size of the binary in bytes.
+    pub size: u64,
+}
+
+/// Outcome of a [`StorageManager::gc`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GcReport {
+    /// Hashes of the removed blobs.
+    pub removed: Vec<String>,
+
+    /// Total size of the removed blobs in bytes.
+    pub freed_bytes: u64,
+}
+
 pub struct StorageManager {
     pub base_dir: PathBuf,
     pub config_dir: PathBuf,
     pub modules_dir: PathBuf,
     pub cache_dir: PathBuf,
+    pub deployments_dir: PathBuf,
 }
 
 impl StorageManager {
@@ -29,12 +76,14 @@ impl StorageManager {
         let config_dir = config.config_dir.clone();//.unwrap_or_else(|| base_dir.join("config"));
         let modules_dir = config.modules_dir.clone();//.unwrap_or_else(|| base_dir.join("modules"));
         let cache_dir = config.cache_dir.clone();//.unwrap_or_else(|| base_dir.join("cache"));
+        let deployments_dir = config.deployments_dir.clone();
 
         let manager = Self {
             base_dir,
             config_dir,
             modules_dir,
             cache_dir,
+            deployments_dir,
         };
 
         manager.ensure_directories().await?;
@@ -66,14 +115,281 @@ impl StorageManager {
             tracing::info!("Created modules directory: {:?}", self.modules_dir);
         }
 
+        let blobs_dir = self.blobs_dir();
+        if !blobs_dir.exists() {
+            tokio::fs::create_dir_all(&blobs_dir).await?;
+            tracing::info!("Created blobs directory: {:?}", blobs_dir);
+        }
+
         if !self.cache_dir.exists() {
             tokio::fs::create_dir_all(&self.cache_dir).await?;
             tracing::info!("Created cache directory: {:?}", self.cache_dir);
         }
 
+        if !self.deployments_dir.exists() {
+            tokio::fs::create_dir_all(&self.deployments_dir).await?;
+            tracing::info!("Created deployments directory: {:?}", self.deployments_dir);
+        }
+
         Ok(())
     } 
 
+    /// Returns the path of the file with the runtime's desired state.
+    pub fn state_path(&self) -> PathBuf {
+        self.base_dir.join(STATE_FILENAME)
+    }
+
+    /// Returns the directory that holds the content-addressed module blobs.
+    pub fn blobs_dir(&self) -> PathBuf {
+        self.modules_dir.join(BLOBS_DIR)
+    }
+
+    /// Returns the path of the blob with the given SHA-256 hash.
+    pub fn blob_path(&self, blob: &str) -> PathBuf {
+        self.blobs_dir().join(format!("{}.wasm", blob))
+    }
+
+    /// Returns the directory that holds the manifests and record of an installed module.
+    ///
+    /// The layout is `modules_dir/<id>/`, where `<id>` is the canonical
+    /// `name@version` identifier of the module.
+    pub fn module_dir(&self, module_id: &str) -> PathBuf {
+        self.modules_dir.join(module_id)
+    }
+
+    /// Stores a module bundle.
+    ///
+    /// The `.wasm` file is added to the blob store (unless an identical blob is
+    /// already there), the optional `component.toml` and `deployment-template.toml`
+    /// files located next to `wasm_path` are copied into `modules_dir/<id>/`, and a
+    /// `module.json` record referencing the blob is written last.
+    ///
+    /// # Returns
+    ///
+    /// The record of the stored module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module directory already exists or if any file
+    /// cannot be written. On failure the partially written module directory is
+    /// removed; a newly added blob is left for [`Self::gc`].
+    pub async fn store_module(&self, module_id: &str, wasm_path: &Path) -> ArcellaResult<ModuleRecord> {
+        let module_dir = self.module_dir(module_id);
+        if module_dir.exists() {
+            return Err(ArcellaError::ModuleAlreadyInstalled(module_id.to_string()));
+        }
+
+        let bytes = tokio::fs::read(wasm_path).await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: wasm_path.to_path_buf() })?;
+        let record = ModuleRecord {
+            module_id: module_id.to_string(),
+            blob: self.store_blob(&bytes).await?,
+            size: bytes.len() as u64,
+        };
+
+        tokio::fs::create_dir_all(&module_dir).await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: module_dir.clone() })?;
+
+        let result = async {
+            copy_bundle_manifests(wasm_path, &module_dir).await?;
+            write_record(&module_dir, &record).await
+        }.await;
+
+        if let Err(e) = result {
+            if let Err(cleanup_err) = tokio::fs::remove_dir_all(&module_dir).await {
+                tracing::error!("Failed to clean up module directory {:?}: {}", module_dir, cleanup_err);
+            }
+            return Err(e);
+        }
+
+        tracing::debug!("Stored module {} in {:?} (blob {})", module_id, module_dir, record.blob);
+        Ok(record)
+    }
+
+    /// Lists the modules stored in `modules_dir`.
+    ///
+    /// Every subdirectory with a readable `module.json` record is treated as an
+    /// installed module. Other entries are skipped with a warning.
+    ///
+    /// # Returns
+    ///
+    /// The module records sorted by module ID.
+    pub async fn list_stored_modules(&self) -> ArcellaResult<Vec<ModuleRecord>> {
+        let mut entries = tokio::fs::read_dir(&self.modules_dir).await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: self.modules_dir.clone() })?;
+
+        let mut records = Vec::new();
+        while let Some(entry) = entries.next_entry().await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: self.modules_dir.clone() })?
+        {
+            let module_dir = entry.path();
+            if !module_dir.is_dir() || entry.file_name() == BLOBS_DIR {
+                continue;
+            }
+
+            let record_path = module_dir.join(MODULE_RECORD_FILENAME);
+            if !record_path.is_file() {
+                tracing::warn!("Skipping module directory without {}: {:?}", MODULE_RECORD_FILENAME, module_dir);
+                continue;
+            }
+
+            match read_record(&record_path).await {
+                Ok(record) => records.push(record),
+                Err(e) => tracing::warn!("Skipping module directory {:?}: {}", module_dir, e),
+            }
+        }
+
+        records.sort_by(|a, b| a.module_id.cmp(&b.module_id));
+        Ok(records)
+    }
+
+    /// Checks that the blob of a module is present and matches the recorded hash.
+    ///
+    /// # Returns
+    ///
+    /// The path of the verified blob.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArcellaError::ModuleCorrupted`] if the blob was modified, or an
+    /// I/O error if it cannot be read.
+    pub async fn verify_module(&self, record: &ModuleRecord) -> ArcellaResult<PathBuf> {
+        let blob_path = self.blob_path(&record.blob);
+        let bytes = tokio::fs::read(&blob_path).await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: blob_path.clone() })?;
+
+        let actual = sha256_hex(&bytes);
+        if actual != record.blob {
+            return Err(ArcellaError::ModuleCorrupted {
+                module_id: record.module_id.clone(),
+                expected: record.blob.clone(),
+                actual,
+            });
+        }
+
+        Ok(blob_path)
+    }
+
+    /// Removes the directory of an installed module with all its files.
+    ///
+    /// The blob is kept, since other modules may share it; unreferenced blobs
+    /// are removed by [`Self::gc`].
+    pub async fn remove_module(&self, module_id: &str) -> ArcellaResult<()> {
+        let module_dir = self.module_dir(module_id);
+        tokio::fs::remove_dir_all(&module_dir).await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: module_dir.clone() })?;
+        tracing::debug!("Removed module directory {:?}", module_dir);
+        Ok(())
+    }
+
+    /// Removes the blobs that are not referenced by any installed module,
+    /// together with leftovers of interrupted writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `modules_dir` cannot be scanned. Blobs that cannot be
+    /// removed are skipped with a warning.
+    pub async fn gc(&self) -> ArcellaResult<GcReport> {
+        let referenced: std::collections::HashSet<String> = self.list_stored_modules().await?
+            .into_iter()
+            .map(|record| record.blob)
+            .collect();
+
+        let blobs_dir = self.blobs_dir();
+        let mut entries = tokio::fs::read_dir(&blobs_dir).await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: blobs_dir.clone() })?;
+
+        let mut report = GcReport::default();
+        while let Some(entry) = entries.next_entry().await
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: blobs_dir.clone() })?
+        {
+            let path = entry.path();
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if referenced.contains(&stem) && path.extension().is_some_and(|e| e == "wasm") {
+                continue;
+            }
+
+            let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {
+                    tracing::debug!("Removed unreferenced blob {:?}", path);
+                    report.freed_bytes += size;
+                    report.removed.push(stem);
+                }
+                Err(e) => tracing::warn!("Failed to remove blob {:?}: {}", path, e),
+            }
+        }
+
+        report.removed.sort();
+        tracing::info!(
+            "Storage GC removed {} blob(s), freed {} bytes",
+            report.removed.len(), report.freed_bytes
+        );
+        Ok(report)
+    }
+
+    /// Adds a binary to the blob store and returns its hash.
+    ///
+    /// An existing blob with the same hash is reused if it is intact, and
+    /// replaced otherwise. Blobs are written through a temporary file, so a
+    /// blob never appears partially written.
+    async fn store_blob(&self, bytes: &[u8]) -> ArcellaResult<String> {
+        let blob = sha256_hex(bytes);
+        let blob_path = self.blob_path(&blob);
+
+        if let Ok(existing) = tokio::fs::read(&blob_path).await {
+            if sha256_hex(&existing) == blob {
+                tracing::debug!("Reusing blob {}", blob);
+                return Ok(blob);
+            }
+            tracing::warn!("Replacing corrupted blob {:?}", blob_path);
+        }
+
+        let tmp_path = blob_path.with_extension("tmp");
+        let result = async {
+            tokio::fs::write(&tmp_path, bytes).await?;
+            tokio::fs::rename(&tmp_path, &blob_path).await
+        }.await;
+        result.map_err(|e| ArcellaError::IoWithPath { source: e, path: blob_path.clone() })?;
+
+        Ok(blob)
+    }
+
+}
+
+/// Returns the hex-encoded SHA-256 of `bytes`.
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Copies the optional manifests located next to `wasm_path` into `module_dir`.
+async fn copy_bundle_manifests(wasm_path: &Path, module_dir: &Path) -> ArcellaResult<()> {
+    for file_name in BUNDLE_MANIFEST_FILES {
+        let source = wasm_path.with_file_name(file_name);
+        if source.is_file() {
+            tokio::fs::copy(&source, module_dir.join(file_name)).await
+                .map_err(|e| ArcellaError::IoWithPath { source: e, path: source.clone() })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the `module.json` record of a module.
+async fn write_record(module_dir: &Path, record: &ModuleRecord) -> ArcellaResult<()> {
+    let path = module_dir.join(MODULE_RECORD_FILENAME);
+    let content = serde_json::to_vec_pretty(record)?;
+    tokio::fs::write(&path, content).await
+        .map_err(|e| ArcellaError::IoWithPath { source: e, path })
+}
+
+/// Reads the `module.json` record of a module.
+async fn read_record(path: &Path) -> ArcellaResult<ModuleRecord> {
+    let content = tokio::fs::read(path).await
+        .map_err(|e| ArcellaError::IoWithPath { source: e, path: path.to_path_buf() })?;
+    Ok(serde_json::from_slice(&content)?)
 }
 
 
@@ -82,6 +398,72 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_store_list_remove_module() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let config = Arc::new(ArcellaConfig::for_tests(temp_dir.path()));
+        let storage = StorageManager::new(&config).await.unwrap();
+
+        let wasm_path = source_dir.path().join("original.wasm");
+        std::fs::write(&wasm_path, b"\0asm").unwrap();
+        std::fs::write(source_dir.path().join("component.toml"), "[component]").unwrap();
+
+        let record = storage.store_module("hello@0.1.0", &wasm_path).await.unwrap();
+        assert_eq!(record.blob, sha256_hex(b"\0asm"));
+        assert_eq!(record.size, 4);
+        let module_dir = config.modules_dir.join("hello@0.1.0");
+        assert!(module_dir.join("component.toml").is_file());
+        assert!(module_dir.join(MODULE_RECORD_FILENAME).is_file());
+        assert!(!module_dir.join("deployment-template.toml").exists());
+        assert_eq!(
+            storage.verify_module(&record).await.unwrap(),
+            config.modules_dir.join("blobs").join(format!("{}.wasm", record.blob)),
+        );
+
+        let err = storage.store_module("hello@0.1.0", &wasm_path).await.unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleAlreadyInstalled(_)));
+
+        // Directories without a module record are ignored.
+        std::fs::create_dir(config.modules_dir.join("junk")).unwrap();
+        let modules = storage.list_stored_modules().await.unwrap();
+        assert_eq!(modules, vec![record]);
+
+        storage.remove_module("hello@0.1.0").await.unwrap();
+        assert!(!module_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_blobs_are_deduplicated_verified_and_collected() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let config = Arc::new(ArcellaConfig::for_tests(temp_dir.path()));
+        let storage = StorageManager::new(&config).await.unwrap();
+
+        let wasm_path = source_dir.path().join("module.wasm");
+        std::fs::write(&wasm_path, b"\0asm-shared").unwrap();
+
+        let a = storage.store_module("a@0.1.0", &wasm_path).await.unwrap();
+        let b = storage.store_module("b@0.1.0", &wasm_path).await.unwrap();
+        assert_eq!(a.blob, b.blob);
+        assert_eq!(std::fs::read_dir(storage.blobs_dir()).unwrap().count(), 1);
+
+        // Nothing to collect while a module references the blob.
+        storage.remove_module("a@0.1.0").await.unwrap();
+        assert!(storage.gc().await.unwrap().removed.is_empty());
+
+        // A modified blob is detected.
+        std::fs::write(storage.blob_path(&b.blob), b"\0asm-tampered").unwrap();
+        let err = storage.verify_module(&b).await.unwrap_err();
+        assert!(matches!(err, ArcellaError::ModuleCorrupted { module_id, .. } if module_id == "b@0.1.0"));
+
+        storage.remove_module("b@0.1.0").await.unwrap();
+        let report = storage.gc().await.unwrap();
+        assert_eq!(report.removed, vec![b.blob.clone()]);
+        assert_eq!(report.freed_bytes, b"\0asm-tampered".len() as u64);
+        assert!(!storage.blob_path(&b.blob).exists());
+    }
+
     /*#[tokio::test]
     async fn test_storage_manager_creates_dirs() {
         let temp_dir = TempDir::new().unwrap();
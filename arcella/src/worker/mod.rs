@@ -0,0 +1,522 @@
+// arcella/arcella/src/worker/mod.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Management of `arcella-worker` processes.
+//!
+//! Instances with `isolation = "worker"` run outside the daemon. The
+//! [`WorkerManager`] keeps one `arcella-worker` process per worker group
+//! ([`FullDeployment::group`]) and talks to it over the process's stdin/stdout
+//! using the line-delimited JSON protocol of [`arcella_types::worker`].
+//!
+//! For every process the manager runs three tasks:
+//!
+//! - a writer, which serializes [`WorkerRequest`]s to the worker's stdin;
+//! - a reader, which applies [`WorkerEvent`]s to the status of the group's instances;
+//! - a heartbeat, which pings the worker periodically to track its health.
+//!
+//! When a worker process exits unexpectedly, all of its unfinished instances are
+//! marked as failed.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use serde::Deserialize;
+use time::OffsetDateTime;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, ChildStdin, ChildStdout, Command},
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+use arcella_types::worker::{WorkerEvent, WorkerInstanceState, WorkerRequest};
+use arcella_wasmtime::executor::ResourceLimits;
+
+use crate::error::{ArcellaError, Result as ArcellaResult};
+use crate::manifest::FullDeployment;
+use crate::runtime::instance::InstanceStatus;
+use crate::runtime::{DeploymentKey, Instance, InstanceState, InstalledModule};
+
+/// File name of the worker executable.
+const WORKER_BINARY: &str = "arcella-worker";
+
+/// Settings of the worker processes, read from the `[workers]` section of the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct WorkerSettings {
+    /// Seconds between two health checks of a worker.
+    pub heartbeat_interval_seconds: u64,
+
+    /// Seconds a worker gets to exit after a shutdown request before it is killed.
+    pub shutdown_timeout_seconds: u64,
+}
+
+impl Default for WorkerSettings {
+    fn default() -> Self {
+        Self { heartbeat_interval_seconds: 5, shutdown_timeout_seconds: 5 }
+    }
+}
+
+impl WorkerSettings {
+    /// Interval between two health checks of a worker (at least one second).
+    fn heartbeat_interval(&self) -> Duration {
+        Duration::from_secs(self.heartbeat_interval_seconds.max(1))
+    }
+
+    /// Time a worker gets to exit after a shutdown request before it is killed.
+    fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.shutdown_timeout_seconds)
+    }
+}
+
+type StatusMap = Mutex<HashMap<String, Arc<Mutex<InstanceStatus>>>>;
+
+/// Point-in-time view of a worker process.
+#[derive(Debug, Clone)]
+pub struct WorkerStatus {
+    pub group: String,
+    pub pid: Option<u32>,
+    pub started_at: OffsetDateTime,
+    pub uptime: Duration,
+
+    /// Whether the process is still connected.
+    pub alive: bool,
+
+    /// Whether the worker answered a health check recently.
+    pub healthy: bool,
+
+    /// Number of instances currently assigned to the worker.
+    pub instances: usize,
+}
+
+/// State shared between a worker handle and its background tasks.
+struct WorkerShared {
+    group: String,
+    alive: AtomicBool,
+    last_seen: Mutex<Instant>,
+    instances: StatusMap,
+}
+
+impl WorkerShared {
+    fn touch(&self) {
+        *self.last_seen.lock().expect("Worker state poisoned") = Instant::now();
+    }
+
+    fn set_instance_state(&self, instance_id: &str, state: InstanceState) {
+        let instances = self.instances.lock().expect("Worker state poisoned");
+        if let Some(status) = instances.get(instance_id) {
+            status.lock().expect("Instance status poisoned").set_state(state);
+        }
+    }
+
+    /// Marks every unfinished instance of the worker as failed.
+    fn fail_all(&self, reason: &str) {
+        let instances = self.instances.lock().expect("Worker state poisoned");
+        for status in instances.values() {
+            let mut status = status.lock().expect("Instance status poisoned");
+            if !status.state.is_finished() {
+                status.set_state(InstanceState::Failed { reason: reason.to_string() });
+            }
+        }
+    }
+}
+
+/// A running `arcella-worker` process serving one group.
+struct WorkerProcess {
+    pid: Option<u32>,
+    started_at: OffsetDateTime,
+    start_instant: Instant,
+    child: Child,
+    requests: mpsc::UnboundedSender<WorkerRequest>,
+    /// Modules installed in the worker, with the blob each was installed from.
+    installed: HashMap<String, String>,
+    shared: Arc<WorkerShared>,
+    tasks: Vec<JoinHandle<()>>,
+    settings: WorkerSettings,
+}
+
+impl WorkerProcess {
+    /// Spawns a worker process for `group` and starts its background tasks.
+    fn spawn(worker_path: &Path, group: &str, settings: WorkerSettings) -> ArcellaResult<Self> {
+        let mut child = Command::new(worker_path)
+            .arg("--group")
+            .arg(group)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ArcellaError::IoWithPath { source: e, path: worker_path.to_path_buf() })?;
+
+        let stdin = child.stdin.take().expect("Worker stdin is piped");
+        let stdout = child.stdout.take().expect("Worker stdout is piped");
+        let pid = child.id();
+
+        let shared = Arc::new(WorkerShared {
+            group: group.to_string(),
+            alive: AtomicBool::new(true),
+            last_seen: Mutex::new(Instant::now()),
+            instances: Mutex::new(HashMap::new()),
+        });
+        let (requests, requests_rx) = mpsc::unbounded_channel();
+
+        let tasks = vec![
+            tokio::spawn(write_requests(stdin, requests_rx)),
+            tokio::spawn(read_events(stdout, shared.clone())),
+            tokio::spawn(heartbeat(requests.clone(), settings.heartbeat_interval())),
+        ];
+
+        tracing::info!("Started worker for group '{}' (pid {:?})", group, pid);
+
+        Ok(Self {
+            pid,
+            started_at: OffsetDateTime::now_utc(),
+            start_instant: Instant::now(),
+            child,
+            requests,
+            installed: HashMap::new(),
+            shared,
+            tasks,
+            settings,
+        })
+    }
+
+    fn send(&self, request: WorkerRequest) -> ArcellaResult<()> {
+        self.requests.send(request).map_err(|_| {
+            ArcellaError::RuntimeError(format!("Worker for group '{}' is not running", self.shared.group))
+        })
+    }
+
+    fn is_alive(&self) -> bool {
+        self.shared.alive.load(Ordering::SeqCst)
+    }
+
+    fn status(&self) -> WorkerStatus {
+        let last_seen = *self.shared.last_seen.lock().expect("Worker state poisoned");
+        let alive = self.is_alive();
+        let instances = self.shared.instances.lock().expect("Worker state poisoned").len();
+
+        WorkerStatus {
+            group: self.shared.group.clone(),
+            pid: self.pid,
+            started_at: self.started_at,
+            uptime: self.start_instant.elapsed(),
+            alive,
+            healthy: alive && last_seen.elapsed() < self.settings.heartbeat_interval() * 3,
+            instances,
+        }
+    }
+
+    /// Asks the worker to exit and waits for it, killing it after
+    /// [`WorkerSettings::shutdown_timeout_seconds`].
+    async fn shutdown(mut self) {
+        let _ = self.requests.send(WorkerRequest::Shutdown);
+
+        match tokio::time::timeout(self.settings.shutdown_timeout(), self.child.wait()).await {
+            Ok(Ok(status)) => {
+                tracing::info!("Worker for group '{}' exited ({})", self.shared.group, status);
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to wait for worker '{}': {}", self.shared.group, e);
+            }
+            Err(_) => {
+                tracing::warn!("Worker for group '{}' did not exit in time, killing it", self.shared.group);
+                if let Err(e) = self.child.kill().await {
+                    tracing::error!("Failed to kill worker '{}': {}", self.shared.group, e);
+                }
+            }
+        }
+
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Spawns and supervises the worker processes of the daemon.
+pub struct WorkerManager {
+    worker_path: Option<PathBuf>,
+    workers: HashMap<String, WorkerProcess>,
+
+    /// Sequence number that makes the worker-side ID of every started instance unique,
+    /// so that late events of a stopped run cannot affect its restarted replica.
+    next_run: u64,
+
+    settings: WorkerSettings,
+}
+
+impl WorkerManager {
+    /// Creates a manager that runs the `arcella-worker` executable installed next
+    /// to the daemon.
+    pub fn new(settings: WorkerSettings) -> Self {
+        let worker_path = find_worker_binary();
+        if worker_path.is_none() {
+            tracing::warn!("{} executable not found; worker isolation is unavailable", WORKER_BINARY);
+        }
+
+        Self { worker_path, workers: HashMap::new(), next_run: 0, settings }
+    }
+
+    /// Starts a replica of a deployment in the worker process of its group.
+    ///
+    /// `limits` are enforced by the worker on the instance's store; the startup and
+    /// call timeouts are taken from the deployment. If `artifact_path` is given, the
+    /// worker loads the precompiled artifact instead of compiling the module.
+    ///
+    /// The worker process is spawned on first use, or respawned if the previous
+    /// one has exited. The module is installed in the worker before the first
+    /// instance of it is started, and installed again if it was reinstalled
+    /// from a different binary since.
+    ///
+    /// # Returns
+    ///
+    /// An [`Instance`] whose status mirrors the events reported by the worker.
+    /// Stopping or dropping the instance stops it in the worker.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the worker executable cannot be found or spawned.
+    pub fn start_instance(
+        &mut self,
+        key: &DeploymentKey,
+        replica: u32,
+        module: &InstalledModule,
+        deployment: &FullDeployment,
+        limits: ResourceLimits,
+        artifact_path: Option<PathBuf>,
+    ) -> ArcellaResult<Instance> {
+        self.next_run += 1;
+        let run_id = format!("{}#{}", Instance::make_id(key, replica), self.next_run);
+
+        let worker = self.worker_for(&deployment.group)?;
+
+        let module_id = module.id();
+        if worker.installed.get(&module_id) != Some(&module.blob) {
+            worker.send(WorkerRequest::Install {
+                module_id: module_id.clone(),
+                wasm_path: module.wasm_path.clone(),
+                blob: module.blob.clone(),
+                artifact_path,
+            })?;
+            worker.installed.insert(module_id.clone(), module.blob.clone());
+        }
+
+        let status = Arc::new(Mutex::new(InstanceStatus::new(InstanceState::Starting)));
+        worker.shared.instances
+            .lock()
+            .expect("Worker state poisoned")
+            .insert(run_id.clone(), status.clone());
+
+        worker.send(WorkerRequest::Start {
+            instance_id: run_id.clone(),
+            module_id,
+            entrypoint: deployment.startup.entrypoint.clone(),
+            args: vec![key.module_id.clone()],
+            r#async: deployment.r#async,
+            memory_limit_bytes: limits.memory_bytes,
+            fuel: limits.fuel,
+            startup_timeout_ms: deployment.startup.timeout().map(|t| t.as_millis() as u64),
+            call_timeout_ms: deployment.resources.call_timeout().map(|t| t.as_millis() as u64),
+            shutdown: deployment.startup.shutdown.clone(),
+            shutdown_timeout_ms: deployment.startup.timeout().map(|t| t.as_millis() as u64),
+        })?;
+
+        // A graceful shutdown keeps the status registered, so that the final
+        // state reported by the worker is still mirrored.
+        let requests = worker.requests.clone();
+        let shutdown_id = run_id.clone();
+        let shutdown = move || {
+            let _ = requests.send(WorkerRequest::Stop { instance_id: shutdown_id, graceful: true });
+        };
+
+        let requests = worker.requests.clone();
+        let shared = worker.shared.clone();
+        let stop = move || {
+            shared.instances.lock().expect("Worker state poisoned").remove(&run_id);
+            let _ = requests.send(WorkerRequest::Stop { instance_id: run_id, graceful: false });
+        };
+
+        Ok(Instance::attach(key.clone(), replica, status, stop, shutdown))
+    }
+
+    /// Returns the status of all worker processes sorted by group.
+    pub fn status(&self) -> Vec<WorkerStatus> {
+        let mut workers: Vec<_> = self.workers.values().map(WorkerProcess::status).collect();
+        workers.sort_by(|a, b| a.group.cmp(&b.group));
+        workers
+    }
+
+    /// Forgets worker processes that have exited.
+    ///
+    /// The instances of such a worker were already marked as failed when its
+    /// output closed; a new process is spawned once one of them is restarted.
+    pub fn reap_exited(&mut self) {
+        self.workers.retain(|group, worker| {
+            if worker.is_alive() {
+                return true;
+            }
+            match worker.child.try_wait() {
+                Ok(Some(status)) => tracing::warn!("Worker for group '{}' exited ({})", group, status),
+                _ => tracing::warn!("Worker for group '{}' disconnected", group),
+            }
+            false
+        });
+    }
+
+    /// Shuts down the workers of groups that no longer have instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `groups_in_use` — Groups that still have worker-isolated instances.
+    pub fn retire_idle(&mut self, groups_in_use: &HashSet<&str>) {
+        let idle: Vec<String> = self.workers
+            .keys()
+            .filter(|group| !groups_in_use.contains(group.as_str()))
+            .cloned()
+            .collect();
+
+        for group in idle {
+            if let Some(worker) = self.workers.remove(&group) {
+                tracing::info!("Retiring idle worker for group '{}'", group);
+                tokio::spawn(worker.shutdown());
+            }
+        }
+    }
+
+    /// Shuts down all worker processes and waits for them to exit.
+    pub async fn shutdown(&mut self) {
+        let workers: Vec<_> = self.workers.drain().map(|(_, w)| w.shutdown()).collect();
+        futures::future::join_all(workers).await;
+    }
+
+    /// Returns the live worker of a group, spawning it if needed.
+    fn worker_for(&mut self, group: &str) -> ArcellaResult<&mut WorkerProcess> {
+        let Some(worker_path) = &self.worker_path else {
+            return Err(ArcellaError::RuntimeError(format!(
+                "{} executable not found", WORKER_BINARY
+            )));
+        };
+
+        if self.workers.get(group).is_some_and(|w| !w.is_alive()) {
+            tracing::warn!("Worker for group '{}' has exited, respawning it", group);
+            self.workers.remove(group);
+        }
+
+        if !self.workers.contains_key(group) {
+            let worker = WorkerProcess::spawn(worker_path, group, self.settings)?;
+            self.workers.insert(group.to_string(), worker);
+        }
+
+        Ok(self.workers.get_mut(group).expect("Worker was just inserted"))
+    }
+}
+
+/// Writes requests to the worker's stdin, one JSON document per line.
+async fn write_requests(mut stdin: ChildStdin, mut requests: mpsc::UnboundedReceiver<WorkerRequest>) {
+    while let Some(request) = requests.recv().await {
+        let mut line = match serde_json::to_vec(&request) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize worker request: {}", e);
+                continue;
+            }
+        };
+        line.push(b'\n');
+
+        if let Err(e) = stdin.write_all(&line).await {
+            tracing::debug!("Worker stdin closed: {}", e);
+            break;
+        }
+        let _ = stdin.flush().await;
+    }
+}
+
+/// Reads events from the worker's stdout until the process exits.
+async fn read_events(stdout: ChildStdout, shared: Arc<WorkerShared>) {
+    let mut lines = BufReader::new(stdout).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let event = match serde_json::from_str::<WorkerEvent>(&line) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("Invalid message from worker '{}': {}", shared.group, e);
+                continue;
+            }
+        };
+        shared.touch();
+
+        match event {
+            WorkerEvent::Ready { pid } => {
+                tracing::debug!("Worker for group '{}' is ready (pid {})", shared.group, pid);
+            }
+            WorkerEvent::Installed { module_id } => {
+                tracing::debug!("Worker '{}' installed {}", shared.group, module_id);
+            }
+            WorkerEvent::Instance { instance_id, state } => {
+                let state = match state {
+                    WorkerInstanceState::Running => {
+                        tracing::info!("Instance {} is running", instance_id);
+                        InstanceState::Running
+                    }
+                    WorkerInstanceState::Exited { code } => {
+                        tracing::info!("Instance {} exited with code {}", instance_id, code);
+                        InstanceState::Exited { code }
+                    }
+                    WorkerInstanceState::Failed { reason } => {
+                        tracing::warn!("Instance {} failed: {}", instance_id, reason);
+                        InstanceState::Failed { reason }
+                    }
+                };
+                shared.set_instance_state(&instance_id, state);
+            }
+            WorkerEvent::Pong => {}
+            WorkerEvent::Error { message } => {
+                tracing::warn!("Worker '{}': {}", shared.group, message);
+            }
+        }
+    }
+
+    shared.alive.store(false, Ordering::SeqCst);
+    shared.fail_all("worker process exited");
+}
+
+/// Pings the worker every `period` until its request channel closes.
+async fn heartbeat(requests: mpsc::UnboundedSender<WorkerRequest>, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+        if requests.send(WorkerRequest::Ping).is_err() {
+            break;
+        }
+    }
+}
+
+/// Locates the `arcella-worker` executable next to the current executable.
+///
+/// Test binaries live in `target/<profile>/deps`, one level below the
+/// executables, so the parent directory is searched as well.
+fn find_worker_binary() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    let file_name = format!("{}{}", WORKER_BINARY, std::env::consts::EXE_SUFFIX);
+
+    let found = [Some(dir), dir.parent()]
+        .into_iter()
+        .flatten()
+        .map(|d| d.join(&file_name))
+        .find(|p| p.is_file());
+    found
+}
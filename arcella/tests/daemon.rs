@@ -0,0 +1,143 @@
+// arcella/arcella/tests/daemon.rs
+//
+// Copyright (c) 2025 Arcella Team
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE>
+// or the MIT license <LICENSE-MIT>, at your option.
+// This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Runs the `arcella` daemon binary against isolated base directories.
+
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn daemon(base_dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_arcella"));
+    command
+        .arg("--base-dir")
+        .arg(base_dir)
+        .arg("--log-level")
+        .arg("warn")
+        .env_remove("ARCELLA_HOME")
+        .stdin(Stdio::null());
+    command
+}
+
+/// Kills the daemon when the test ends, even if it panics.
+struct Running(Child);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn start(base_dir: &Path) -> Running {
+    let child = daemon(base_dir).stdout(Stdio::null()).stderr(Stdio::null()).spawn().unwrap();
+    let running = Running(child);
+
+    let socket = base_dir.join("alme");
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while !socket.exists() {
+        assert!(Instant::now() < deadline, "daemon in {:?} did not start", base_dir);
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    running
+}
+
+#[test]
+fn test_isolated_daemons_run_side_by_side() {
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+
+    let _first = start(first.path());
+    let _second = start(second.path());
+    assert!(first.path().join("arcella.pid").exists());
+    assert!(second.path().join("arcella.pid").exists());
+
+    // A second daemon for the same base directory is refused.
+    let output = daemon(first.path()).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already running"));
+}
+
+#[test]
+fn test_check_config() {
+    let base_dir = TempDir::new().unwrap();
+
+    let output = daemon(base_dir.path()).arg("--check-config").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("is valid"));
+    assert!(stdout.contains("layer 1: "));
+    assert!(stdout.contains("arcella.log.dir = \"log\"  # layer 0: default_config.toml"));
+    assert!(!base_dir.path().join("arcella.lock").exists());
+
+    // The report is printed by this process, not by a background daemon.
+    let output = daemon(base_dir.path()).args(["--check-config", "--daemonize"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+
+    let output = daemon(base_dir.path())
+        .args(["--check-config", "--set", "arcella.cache.max_size_mb=64"])
+        .env("ARCELLA__LOG__DEFAULT_LEVEL", "debug")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("arcella.cache.max_size_mb = 64  # layer 3: --set arcella.cache.max_size_mb"));
+    assert!(stdout.contains("arcella.log.default_level = \"debug\"  # layer 2: env ARCELLA__LOG__DEFAULT_LEVEL"));
+
+    let output = daemon(base_dir.path())
+        .args(["--check-config", "--set", "arcella.integrity.action=panic"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("arcella.integrity.action in \"--set arcella.integrity.action\""));
+
+    std::fs::write(base_dir.path().join("config/arcella.toml"), "[log\n").unwrap();
+    let output = daemon(base_dir.path()).arg("--check-config").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_failed_start_writes_init_log() {
+    let base_dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(base_dir.path().join("config")).unwrap();
+    std::fs::write(base_dir.path().join("config/arcella.toml"), "[log\n").unwrap();
+
+    let output = daemon(base_dir.path()).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Failed to load configuration"));
+
+    let init_log = std::fs::read_to_string(base_dir.path().join("log/init.log")).unwrap();
+    assert!(init_log.contains("ERROR Failed to load configuration"));
+    assert!(!base_dir.path().join("arcella.lock").exists());
+}
+
+#[test]
+fn test_daemonize_waits_for_startup() {
+    let base_dir = TempDir::new().unwrap();
+
+    let output = daemon(base_dir.path()).arg("--daemonize").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(base_dir.path().join("alme").exists());
+
+    let pid = std::fs::read_to_string(base_dir.path().join("arcella.pid")).unwrap();
+    let pid = pid.trim();
+    assert!(stdout.contains(&format!("(pid {})", pid)));
+
+    // A daemon for a base directory that is already in use reports the failure.
+    let output = daemon(base_dir.path()).arg("--daemonize").output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("already running"));
+    assert!(stderr.contains("failed to start"));
+
+    let status = Command::new("kill").arg(pid).status().unwrap();
+    assert!(status.success());
+}